@@ -58,7 +58,9 @@ echo ""
 
 # Update service file to use environment file
 echo "[3/5] Installing systemd service..."
-sed 's|# EnvironmentFile=/etc/academic-paper-interpreter-mcp/env|EnvironmentFile=/etc/academic-paper-interpreter-mcp/env|' \
+sed -e 's|# EnvironmentFile=/etc/academic-paper-interpreter-mcp/env|EnvironmentFile=/etc/academic-paper-interpreter-mcp/env|' \
+    -e "s|@PROJECT_DIR@|${PROJECT_DIR}|g" \
+    -e "s|@SERVICE_USER@|${ACTUAL_USER}|g" \
     "$SERVICE_FILE" > "/etc/systemd/system/${SERVICE_NAME}.service"
 echo "Service file installed: /etc/systemd/system/${SERVICE_NAME}.service"
 echo ""
@@ -88,4 +90,4 @@ echo ""
 echo "  4. View logs:"
 echo "     sudo journalctl -u ${SERVICE_NAME} -f"
 echo ""
-echo "Server will be available at: http://localhost:18080"
+echo "Server will be available at: http://localhost:18080/mcp (SSE: http://localhost:18080/sse)"
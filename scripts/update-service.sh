@@ -52,7 +52,9 @@ echo ""
 
 # Step 3: Update service file
 echo "[3/5] Updating service file..."
-cp "${PROJECT_DIR}/${SERVICE_NAME}.service" "/etc/systemd/system/${SERVICE_NAME}.service"
+sed -e "s|@PROJECT_DIR@|${PROJECT_DIR}|g" \
+    -e "s|@SERVICE_USER@|${ACTUAL_USER}|g" \
+    "${PROJECT_DIR}/${SERVICE_NAME}.service" > "/etc/systemd/system/${SERVICE_NAME}.service"
 
 # Enable EnvironmentFile if exists
 if [[ -f "/etc/${SERVICE_NAME}/env" ]]; then
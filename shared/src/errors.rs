@@ -29,14 +29,21 @@ pub enum AppError {
     #[error("LLM configuration error: {0}")]
     LlmConfigError(String),
 
-    #[error("Rate limit exceeded: {0}")]
-    RateLimitExceeded(String),
+    #[error("Rate limit exceeded: {message}")]
+    RateLimitExceeded {
+        message: String,
+        /// Seconds the upstream asked to wait before retrying (Retry-After)
+        retry_after_secs: Option<u64>,
+    },
 
     #[error("Network error: {0}")]
     NetworkError(String),
 
     #[error("Invalid request: {0}")]
     InvalidRequest(String),
+
+    #[error("Cancelled: {0}")]
+    Cancelled(String),
 }
 
 impl AppError {
@@ -48,12 +55,31 @@ impl AppError {
             AppError::PdfFetchFailed(_) => "PDF_FETCH_FAILED",
             AppError::LlmError(_) => "LLM_ERROR",
             AppError::LlmConfigError(_) => "LLM_CONFIG_ERROR",
-            AppError::RateLimitExceeded(_) => "RATE_LIMIT",
+            AppError::RateLimitExceeded { .. } => "RATE_LIMIT",
             AppError::NetworkError(_) => "NETWORK_ERROR",
             AppError::InvalidRequest(_) => "INVALID_REQUEST",
+            AppError::Cancelled(_) => "CANCELLED",
             _ => "INTERNAL_ERROR",
         }
     }
+
+    /// Whether the same request may succeed if retried later
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            AppError::RateLimitExceeded { .. } | AppError::NetworkError(_)
+        )
+    }
+
+    /// How long the upstream asked to wait before retrying, if it said
+    pub fn retry_after_secs(&self) -> Option<u64> {
+        match self {
+            AppError::RateLimitExceeded {
+                retry_after_secs, ..
+            } => *retry_after_secs,
+            _ => None,
+        }
+    }
 }
 
 pub type AppResult<T> = Result<T, AppError>;
@@ -1,7 +1,18 @@
-use app::server::transport::run_stdio_server;
-use clap::Parser;
+use app::cache::AnalysisCache;
+use app::server::transport::{run_http_server, run_stdio_server};
+use clap::{Parser, ValueEnum};
 use shared::errors::AppResult;
 use shared::logger::init_logger;
+use std::net::SocketAddr;
+
+/// Transport used to serve MCP
+#[derive(ValueEnum, Clone, Debug, PartialEq)]
+enum Transport {
+    /// Standard input/output (one server process per client)
+    Stdio,
+    /// Streamable HTTP with SSE fallback (one shared server for many clients)
+    Http,
+}
 
 #[derive(Parser, Debug)]
 #[command(name = "academic-paper-interpreter-mcp")]
@@ -11,6 +22,18 @@ struct Args {
     /// Log level
     #[arg(long, default_value = "info")]
     log_level: String,
+
+    /// Transport type
+    #[arg(long, value_enum, default_value = "stdio")]
+    transport: Transport,
+
+    /// Address to bind when using the HTTP transport
+    #[arg(long, default_value = "127.0.0.1:18080")]
+    bind: SocketAddr,
+
+    /// Delete all stored LLM analyses and exit
+    #[arg(long)]
+    clear_analysis_cache: bool,
 }
 
 #[tokio::main]
@@ -20,10 +43,24 @@ async fn main() -> AppResult<()> {
     // Initialize logging
     init_logger(&args.log_level)?;
 
+    if args.clear_analysis_cache {
+        let removed = AnalysisCache::from_env().clear().await?;
+        tracing::info!("Removed {} stored analyses", removed);
+        return Ok(());
+    }
+
     tracing::info!("Academic Paper Interpreter MCP Server");
-    tracing::info!("Transport: stdio");
 
-    run_stdio_server().await
+    match args.transport {
+        Transport::Stdio => {
+            tracing::info!("Transport: stdio");
+            run_stdio_server().await
+        }
+        Transport::Http => {
+            tracing::info!("Transport: http ({})", args.bind);
+            run_http_server(args.bind).await
+        }
+    }
 }
 
 #[cfg(test)]
@@ -34,8 +71,17 @@ mod tests {
     fn test_cli_parsing() {
         let args = Args::parse_from(["app"]);
         assert_eq!(args.log_level, "info");
+        assert_eq!(args.transport, Transport::Stdio);
+        assert!(!args.clear_analysis_cache);
 
         let args = Args::parse_from(["app", "--log-level", "debug"]);
         assert_eq!(args.log_level, "debug");
+
+        let args = Args::parse_from(["app", "--transport", "http", "--bind", "0.0.0.0:8080"]);
+        assert_eq!(args.transport, Transport::Http);
+        assert_eq!(args.bind, "0.0.0.0:8080".parse::<SocketAddr>().unwrap());
+
+        let args = Args::parse_from(["app", "--clear-analysis-cache"]);
+        assert!(args.clear_analysis_cache);
     }
 }
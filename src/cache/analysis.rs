@@ -0,0 +1,318 @@
+use super::paper::PaperKey;
+use super::store::{CachedValue, DiskStore};
+use crate::models::analysis::{AnalysisType, PaperAnalysis};
+use crate::models::llm_config::{LlmConfig, LlmProvider};
+use crate::models::paper::Paper;
+use crate::pipeline::prompt_version;
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use shared::errors::AppResult;
+use std::path::{Path, PathBuf};
+
+/// Default time-to-live of a stored analysis (30 days)
+const DEFAULT_TTL_SECS: u64 = 30 * 24 * 60 * 60;
+
+/// Default upper bound on the total size of stored analyses
+const DEFAULT_MAX_MB: u64 = 256;
+
+/// Cache key for an analysis: a hash of the papers' content, the endpoint and
+/// model settings that affect the output, the analysis type, and the prompt
+/// version
+///
+/// OpenAI-compatible servers all default to the model name "default", so the
+/// base URL and headers tell their results apart.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AnalysisKey(String);
+
+impl AnalysisKey {
+    /// `papers` is the analyzed paper followed by any papers it is compared with
+    ///
+    /// `None` for the sampling provider: the MCP client picks the model, so
+    /// its results cannot be told apart and are not stored.
+    pub fn new(papers: &[Paper], config: &LlmConfig, analysis_type: &AnalysisType) -> Option<Self> {
+        if config.provider == LlmProvider::Sampling {
+            return None;
+        }
+        let material = json!({
+            "analysis_type": analysis_type,
+            "prompt_version": prompt_version(analysis_type),
+            "provider": config.provider,
+            "base_url": config.effective_base_url(),
+            "headers": config.effective_headers(),
+            "model": config.effective_model(),
+            "temperature": config.temperature,
+            "max_tokens": config.max_tokens,
+            "context_length": config.effective_context_length(),
+            "papers": papers.iter().map(content_hash).collect::<Vec<_>>(),
+        });
+        Some(Self(format!("{:x}", Sha256::digest(material.to_string()))))
+    }
+
+    fn relative_path(&self) -> PathBuf {
+        PathBuf::from(format!("{}/{}.json", &self.0[..2], self.0))
+    }
+}
+
+/// Directory of the per-paper, per-model index of latest analyses
+const LATEST_DIR: &str = "latest";
+
+fn latest_path(paper: &PaperKey, model: &str) -> PathBuf {
+    PathBuf::from(format!(
+        "{}/{}/{}.json",
+        LATEST_DIR,
+        paper.as_str(),
+        encode_model(model)
+    ))
+}
+
+/// Model names may contain '/' (e.g., `meta-llama/Llama-3`) or ':' (Ollama
+/// tags), so other characters are stored as `%XX` escapes that decode back
+/// to the original name
+fn encode_model(model: &str) -> String {
+    model
+        .bytes()
+        .map(|b| {
+            if b.is_ascii_alphanumeric() || matches!(b, b'.' | b'-' | b'_') {
+                (b as char).to_string()
+            } else {
+                format!("%{:02X}", b)
+            }
+        })
+        .collect()
+}
+
+fn decode_model(stem: &str) -> Option<String> {
+    let mut bytes = Vec::with_capacity(stem.len());
+    let mut rest = stem.as_bytes();
+    while let Some((&b, tail)) = rest.split_first() {
+        if b == b'%' {
+            let hex = std::str::from_utf8(tail.get(..2)?).ok()?;
+            bytes.push(u8::from_str_radix(hex, 16).ok()?);
+            rest = &tail[2..];
+        } else {
+            bytes.push(b);
+            rest = tail;
+        }
+    }
+    String::from_utf8(bytes).ok()
+}
+
+/// SHA-256 of a paper's serialized metadata and content
+///
+/// The detected structure is derived from the content, so it is left out.
+fn content_hash(paper: &Paper) -> String {
+    let paper = Paper {
+        structure: None,
+        ..paper.clone()
+    };
+    let bytes = serde_json::to_vec(&paper).unwrap_or_default();
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+/// On-disk store of LLM analyses, so the same paper is not re-analyzed with
+/// the same model and prompts
+///
+/// Configured with ANALYSIS_CACHE_DIR, ANALYSIS_CACHE_TTL_SECS, and
+/// ANALYSIS_CACHE_MAX_MB; stored under `analyses` in the cache root by default.
+/// Entries written with older prompts are never hit again once a pipeline's
+/// prompt version is bumped, and age out through the TTL and size limit.
+#[derive(Debug, Clone)]
+pub struct AnalysisCache {
+    store: DiskStore,
+}
+
+impl AnalysisCache {
+    pub fn new(store: DiskStore) -> Self {
+        Self { store }
+    }
+
+    pub fn from_env() -> Self {
+        Self::new(DiskStore::from_env(
+            "ANALYSIS_CACHE",
+            "analyses",
+            DEFAULT_TTL_SECS,
+            DEFAULT_MAX_MB,
+        ))
+    }
+
+    /// Look up a stored analysis along with when it was produced
+    pub async fn get(&self, key: &AnalysisKey) -> Option<CachedValue<PaperAnalysis>> {
+        self.store.get(&key.relative_path()).await
+    }
+
+    /// Store an analysis and return its timestamp
+    pub async fn put(
+        &self,
+        key: &AnalysisKey,
+        analysis: &PaperAnalysis,
+    ) -> AppResult<chrono::DateTime<chrono::Utc>> {
+        self.store.put(&key.relative_path(), analysis).await
+    }
+
+    /// Latest analysis of a paper by a model, regardless of analysis type
+    pub async fn get_latest(
+        &self,
+        paper: &PaperKey,
+        model: &str,
+    ) -> Option<CachedValue<PaperAnalysis>> {
+        self.store.get(&latest_path(paper, model)).await
+    }
+
+    /// Record an analysis as the latest one of a paper by a model
+    pub async fn put_latest(
+        &self,
+        paper: &PaperKey,
+        model: &str,
+        analysis: &PaperAnalysis,
+    ) -> AppResult<()> {
+        self.store.put(&latest_path(paper, model), analysis).await?;
+        Ok(())
+    }
+
+    /// (paper, model) pairs that have a latest analysis
+    pub async fn latest_entries(&self) -> Vec<(PaperKey, String)> {
+        self.store
+            .list(Path::new(LATEST_DIR))
+            .await
+            .iter()
+            .filter_map(|path| {
+                let model = decode_model(path.file_stem()?.to_str()?)?;
+                let paper = path.parent()?.strip_prefix(LATEST_DIR).ok()?;
+                let mut parts = paper.iter().filter_map(|p| p.to_str());
+                let key = match (parts.next()?, parts.next()?) {
+                    ("arxiv", id) => PaperKey::arxiv(id),
+                    ("s2", id) => PaperKey::semantic_scholar(id),
+                    _ => return None,
+                };
+                Some((key, model))
+            })
+            .collect()
+    }
+
+    /// Drop every stored analysis and return how many were removed
+    pub async fn clear(&self) -> AppResult<usize> {
+        self.store.clear().await
+    }
+}
+
+impl Default for AnalysisCache {
+    fn default() -> Self {
+        Self::from_env()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn paper(content: &str) -> Paper {
+        Paper {
+            title: "Attention Is All You Need".to_string(),
+            authors: vec![],
+            abstract_text: String::new(),
+            arxiv_id: Some("1706.03762".to_string()),
+            ss_id: None,
+            categories: vec![],
+            published_date: None,
+            pdf_url: None,
+            content: Some(content.to_string()),
+            structure: None,
+        }
+    }
+
+    fn config(model: &str) -> LlmConfig {
+        LlmConfig {
+            provider: LlmProvider::OpenAi,
+            model: Some(model.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_latest_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = AnalysisCache::new(DiskStore::new(
+            dir.path().to_path_buf(),
+            std::time::Duration::from_secs(60),
+            1 << 20,
+        ));
+        let analysis: PaperAnalysis =
+            serde_json::from_value(json!({ "summary": "s", "key_contributions": [] })).unwrap();
+        let paper_key = PaperKey::arxiv("1706.03762");
+
+        // Names that differ only in characters unsafe in file names stay apart
+        for model in ["llama3.2:latest", "a/b", "a_b"] {
+            cache
+                .put_latest(&paper_key, model, &analysis)
+                .await
+                .unwrap();
+            assert!(cache.get_latest(&paper_key, model).await.is_some());
+        }
+        let mut models: Vec<String> = cache
+            .latest_entries()
+            .await
+            .into_iter()
+            .map(|(key, model)| {
+                assert_eq!(key, paper_key);
+                model
+            })
+            .collect();
+        models.sort();
+        assert_eq!(models, ["a/b", "a_b", "llama3.2:latest"]);
+    }
+
+    #[test]
+    fn test_analysis_key() {
+        let key = AnalysisKey::new(&[paper("text")], &config("gpt-4o"), &AnalysisType::Summary);
+        assert_eq!(
+            key,
+            AnalysisKey::new(&[paper("text")], &config("gpt-4o"), &AnalysisType::Summary)
+        );
+
+        // Anything that changes the output changes the key
+        assert_ne!(
+            key,
+            AnalysisKey::new(
+                &[paper("edited")],
+                &config("gpt-4o"),
+                &AnalysisType::Summary
+            )
+        );
+        assert_ne!(
+            key,
+            AnalysisKey::new(
+                &[paper("text")],
+                &config("gpt-4o-mini"),
+                &AnalysisType::Summary
+            )
+        );
+        assert_ne!(
+            key,
+            AnalysisKey::new(&[paper("text")], &config("gpt-4o"), &AnalysisType::Detailed)
+        );
+        let mut warm = config("gpt-4o");
+        warm.temperature = Some(0.7);
+        assert_ne!(
+            key,
+            AnalysisKey::new(&[paper("text")], &warm, &AnalysisType::Summary)
+        );
+        let mut routed = config("gpt-4o");
+        routed
+            .headers
+            .insert("X-Route".to_string(), "eu".to_string());
+        assert_ne!(
+            key,
+            AnalysisKey::new(&[paper("text")], &routed, &AnalysisType::Summary)
+        );
+
+        // Results of whatever model a sampling client ran are not keyed
+        let sampling = LlmConfig {
+            provider: LlmProvider::Sampling,
+            ..config("gpt-4o")
+        };
+        assert_eq!(
+            AnalysisKey::new(&[paper("text")], &sampling, &AnalysisType::Summary),
+            None
+        );
+    }
+}
@@ -0,0 +1,7 @@
+pub mod analysis;
+pub mod paper;
+pub mod store;
+
+pub use analysis::{AnalysisCache, AnalysisKey};
+pub use paper::{PaperCache, PaperKey, PaperListing};
+pub use store::{default_cache_root, CachedValue, DiskStore};
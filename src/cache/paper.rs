@@ -0,0 +1,278 @@
+use super::store::DiskStore;
+use crate::models::paper::Paper;
+use crate::sources::paper_sources;
+use serde::{Deserialize, Serialize};
+use shared::errors::AppResult;
+use std::path::{Path, PathBuf};
+
+/// Default time-to-live of a cached paper (7 days)
+const DEFAULT_TTL_SECS: u64 = 7 * 24 * 60 * 60;
+
+/// Default upper bound on the total size of cached papers (1 GiB)
+const DEFAULT_MAX_MB: u64 = 1024;
+
+/// Directory of the listing entries stored next to each paper
+const LISTING_DIR: &str = "listings";
+
+/// Normalized cache key for a paper, stable across ID spellings
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PaperKey(String);
+
+impl PaperKey {
+    /// Key for an arXiv ID; a version suffix (e.g., v2) is kept as part of the key
+    pub fn arxiv(id: &str) -> Self {
+        let id = id.trim();
+        let id = id
+            .strip_prefix("arXiv:")
+            .or_else(|| id.strip_prefix("arxiv:"))
+            .unwrap_or(id);
+        Self(format!("arxiv/{}", sanitize(&id.to_lowercase())))
+    }
+
+    /// Key for a Semantic Scholar paper ID or CorpusID
+    pub fn semantic_scholar(id: &str) -> Self {
+        Self(format!("s2/{}", sanitize(&id.trim().to_lowercase())))
+    }
+
+    /// Key for an ID from a registered paper source (e.g., `doi`)
+    ///
+    /// Unlike arXiv and Semantic Scholar IDs, these are kept case-sensitive
+    /// (OpenReview forum IDs are).
+    pub fn from_source(source: &str, id: &str) -> Self {
+        Self(format!("{}/{}", source, sanitize(id.trim())))
+    }
+
+    /// Key for whichever catalog ID the paper has, preferring arXiv
+    pub fn for_paper(paper: &Paper) -> Option<Self> {
+        match (&paper.arxiv_id, &paper.ss_id) {
+            (Some(id), _) => Some(Self::arxiv(id)),
+            (None, Some(id)) => Some(Self::semantic_scholar(id)),
+            (None, None) => None,
+        }
+    }
+
+    /// Catalog the key belongs to: `arxiv`, `s2`, or a paper source name
+    pub fn source(&self) -> &str {
+        self.0.split_once('/').map_or("", |(source, _)| source)
+    }
+
+    /// Normalized ID within the catalog
+    pub fn id(&self) -> &str {
+        self.0.split_once('/').map_or(self.0.as_str(), |(_, id)| id)
+    }
+
+    /// `source/id`, usable as a relative directory path
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    fn relative_path(&self) -> PathBuf {
+        PathBuf::from(format!("{}.json", self.0))
+    }
+
+    fn listing_path(&self) -> PathBuf {
+        PathBuf::from(format!("{}/{}.json", LISTING_DIR, self.0))
+    }
+
+    fn from_relative_path(path: &Path) -> Option<Self> {
+        let source = path.parent()?.to_str()?;
+        let id = path.file_stem()?.to_str()?;
+        Some(Self(format!("{}/{}", source, id)))
+    }
+}
+
+/// Replace characters that are not safe in file names (old-style arXiv IDs contain '/')
+fn sanitize(id: &str) -> String {
+    id.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '.' || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// What a resource listing shows of a cached paper
+///
+/// Stored next to the paper so listing does not read every full text.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PaperListing {
+    pub title: String,
+    /// Size of the extracted text in bytes, if the paper has it
+    pub content_len: Option<usize>,
+}
+
+impl PaperListing {
+    fn of(paper: &Paper) -> Self {
+        Self {
+            title: paper.title.clone(),
+            content_len: paper.content.as_ref().map(String::len),
+        }
+    }
+}
+
+/// On-disk cache of fetched papers (metadata and extracted text)
+///
+/// Configured with PAPER_CACHE_DIR, PAPER_CACHE_TTL_SECS, and
+/// PAPER_CACHE_MAX_MB; stored under `papers` in the cache root by default.
+#[derive(Debug, Clone)]
+pub struct PaperCache {
+    store: DiskStore,
+}
+
+impl PaperCache {
+    pub fn new(store: DiskStore) -> Self {
+        Self { store }
+    }
+
+    pub fn from_env() -> Self {
+        Self::new(DiskStore::from_env(
+            "PAPER_CACHE",
+            "papers",
+            DEFAULT_TTL_SECS,
+            DEFAULT_MAX_MB,
+        ))
+    }
+
+    /// Look up a paper, ignoring expired or unreadable entries
+    pub async fn get(&self, key: &PaperKey) -> Option<Paper> {
+        self.store
+            .get(&key.relative_path())
+            .await
+            .map(|cached| cached.value)
+    }
+
+    /// Keys of all cached papers, including expired entries not yet evicted
+    pub async fn keys(&self) -> Vec<PaperKey> {
+        let mut keys = Vec::new();
+        let sources = ["arxiv", "s2"]
+            .into_iter()
+            .chain(paper_sources().iter().map(|source| source.name()));
+        for source in sources {
+            keys.extend(
+                self.store
+                    .list(Path::new(source))
+                    .await
+                    .iter()
+                    .filter_map(|path| PaperKey::from_relative_path(path)),
+            );
+        }
+        keys
+    }
+
+    /// Listings of all unexpired cached papers
+    ///
+    /// Papers cached without a listing get one written from the full entry.
+    pub async fn listings(&self) -> Vec<(PaperKey, PaperListing)> {
+        let mut listings = Vec::new();
+        for key in self.keys().await {
+            let listing = match self.store.get(&key.listing_path()).await {
+                Some(cached) => cached.value,
+                None => {
+                    let Some(paper) = self.get(&key).await else {
+                        continue;
+                    };
+                    let listing = PaperListing::of(&paper);
+                    if let Err(e) = self.store.put(&key.listing_path(), &listing).await {
+                        tracing::warn!("Failed to store listing of {}: {}", key.as_str(), e);
+                    }
+                    listing
+                }
+            };
+            listings.push((key, listing));
+        }
+        listings
+    }
+
+    /// Store a paper and its listing, evicting old entries if the cache is
+    /// over its size limit
+    pub async fn put(&self, key: &PaperKey, paper: &Paper) -> AppResult<()> {
+        self.store.put(&key.relative_path(), paper).await?;
+        self.store
+            .put(&key.listing_path(), &PaperListing::of(paper))
+            .await?;
+        Ok(())
+    }
+}
+
+impl Default for PaperCache {
+    fn default() -> Self {
+        Self::from_env()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn paper(title: &str, content: Option<&str>) -> Paper {
+        Paper {
+            title: title.to_string(),
+            authors: vec!["Ashish Vaswani".to_string()],
+            abstract_text: "Abstract".to_string(),
+            arxiv_id: Some("1706.03762".to_string()),
+            ss_id: None,
+            categories: vec![],
+            published_date: None,
+            pdf_url: None,
+            content: content.map(str::to_string),
+            structure: None,
+        }
+    }
+
+    #[test]
+    fn test_paper_key() {
+        assert_eq!(
+            PaperKey::arxiv("arXiv:1706.03762v5"),
+            PaperKey::arxiv("1706.03762V5")
+        );
+        assert_eq!(PaperKey::arxiv("hep-th/9901001").0, "arxiv/hep-th_9901001");
+        assert_ne!(
+            PaperKey::arxiv("1706.03762"),
+            PaperKey::arxiv("1706.03762v5")
+        );
+        assert_eq!(PaperKey::semantic_scholar("ABC123").0, "s2/abc123");
+        assert_eq!(
+            PaperKey::from_source("doi", "10.1145/3442188.3445922").0,
+            "doi/10.1145_3442188.3445922"
+        );
+        assert_eq!(
+            PaperKey::from_source("openreview", "rJ4km2R5t7").0,
+            "openreview/rJ4km2R5t7"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_and_put() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = PaperCache::new(DiskStore::new(
+            dir.path().to_path_buf(),
+            Duration::from_secs(60),
+            1 << 20,
+        ));
+        let key = PaperKey::arxiv("1706.03762");
+
+        assert!(cache.get(&key).await.is_none());
+        cache
+            .put(&key, &paper("Attention", Some("text")))
+            .await
+            .unwrap();
+        let cached = cache.get(&key).await.unwrap();
+        assert_eq!(cached.title, "Attention");
+        assert_eq!(cached.content.as_deref(), Some("text"));
+        assert_eq!(cache.keys().await, vec![key.clone()]);
+        assert_eq!(
+            cache.listings().await,
+            vec![(
+                key,
+                PaperListing {
+                    title: "Attention".to_string(),
+                    content_len: Some(4),
+                }
+            )]
+        );
+    }
+}
@@ -0,0 +1,303 @@
+use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use shared::errors::{AppError, AppResult};
+use std::env;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// Directory name under the user cache directory
+const CACHE_DIR_NAME: &str = "academic-paper-interpreter-mcp";
+
+#[derive(Debug, Serialize, Deserialize)]
+struct StoredEntry<T> {
+    cached_at: DateTime<Utc>,
+    value: T,
+}
+
+/// A value read back from a store along with when it was written
+#[derive(Debug, Clone)]
+pub struct CachedValue<T> {
+    pub value: T,
+    pub cached_at: DateTime<Utc>,
+}
+
+/// Directory of JSON entries with a TTL and a total size bound
+///
+/// Shared by the paper and analysis caches. Entries are written atomically,
+/// and the least recently written entries are evicted once the directory
+/// grows past its size limit.
+#[derive(Debug, Clone)]
+pub struct DiskStore {
+    dir: PathBuf,
+    ttl: Duration,
+    max_bytes: u64,
+}
+
+impl DiskStore {
+    pub fn new(dir: PathBuf, ttl: Duration, max_bytes: u64) -> Self {
+        Self {
+            dir,
+            ttl,
+            max_bytes,
+        }
+    }
+
+    /// Configure from `{prefix}_DIR`, `{prefix}_TTL_SECS`, and `{prefix}_MAX_MB`
+    ///
+    /// The directory defaults to `subdir` under [`default_cache_root`].
+    pub fn from_env(
+        prefix: &str,
+        subdir: &str,
+        default_ttl_secs: u64,
+        default_max_mb: u64,
+    ) -> Self {
+        let dir = env::var(format!("{}_DIR", prefix))
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| default_cache_root().join(subdir));
+        let ttl_secs = env::var(format!("{}_TTL_SECS", prefix))
+            .ok()
+            .and_then(|v| v.trim().parse().ok())
+            .unwrap_or(default_ttl_secs);
+        let max_mb: u64 = env::var(format!("{}_MAX_MB", prefix))
+            .ok()
+            .and_then(|v| v.trim().parse().ok())
+            .unwrap_or(default_max_mb);
+
+        Self::new(dir, Duration::from_secs(ttl_secs), max_mb * 1024 * 1024)
+    }
+
+    /// Whether caching is turned off (a zero TTL or size limit disables it)
+    pub fn is_disabled(&self) -> bool {
+        self.ttl.is_zero() || self.max_bytes == 0
+    }
+
+    /// Read an entry, ignoring expired or unreadable ones
+    pub async fn get<T: DeserializeOwned>(&self, relative_path: &Path) -> Option<CachedValue<T>> {
+        if self.is_disabled() {
+            return None;
+        }
+
+        let path = self.dir.join(relative_path);
+        let bytes = tokio::fs::read(&path).await.ok()?;
+        let entry: StoredEntry<T> = match serde_json::from_slice(&bytes) {
+            Ok(entry) => entry,
+            Err(e) => {
+                tracing::warn!(
+                    "Discarding unreadable cache entry {}: {}",
+                    path.display(),
+                    e
+                );
+                let _ = tokio::fs::remove_file(&path).await;
+                return None;
+            }
+        };
+
+        let age = Utc::now().signed_duration_since(entry.cached_at);
+        if age.to_std().is_ok_and(|age| age > self.ttl) {
+            tracing::debug!("Cache entry {} expired", path.display());
+            return None;
+        }
+
+        Some(CachedValue {
+            value: entry.value,
+            cached_at: entry.cached_at,
+        })
+    }
+
+    /// Write an entry, then evict old entries if the store is over its size limit
+    pub async fn put<T: Serialize>(
+        &self,
+        relative_path: &Path,
+        value: &T,
+    ) -> AppResult<DateTime<Utc>> {
+        let cached_at = Utc::now();
+        if self.is_disabled() {
+            return Ok(cached_at);
+        }
+
+        let entry = StoredEntry { cached_at, value };
+        let json = serde_json::to_vec(&entry)
+            .map_err(|e| AppError::InternalAppError(format!("Cache serialization error: {}", e)))?;
+
+        write_atomic(&self.dir.join(relative_path), &json).await?;
+
+        let dir = self.dir.clone();
+        let max_bytes = self.max_bytes;
+        let ttl = self.ttl;
+        tokio::task::spawn_blocking(move || evict(&dir, max_bytes, ttl))
+            .await
+            .map_err(|e| AppError::InternalAppError(format!("Cache eviction failed: {}", e)))?;
+
+        Ok(cached_at)
+    }
+
+    /// Relative paths of all entries under `subdir`, without expiry checks
+    pub async fn list(&self, subdir: &Path) -> Vec<PathBuf> {
+        let root = self.dir.clone();
+        let dir = self.dir.join(subdir);
+        tokio::task::spawn_blocking(move || {
+            let mut entries = Vec::new();
+            collect_entries(&dir, &mut entries);
+            entries
+                .into_iter()
+                .filter_map(|(path, _, _)| path.strip_prefix(&root).ok().map(Path::to_path_buf))
+                .collect()
+        })
+        .await
+        .unwrap_or_default()
+    }
+
+    /// Remove every entry in the store and return how many were removed
+    pub async fn clear(&self) -> AppResult<usize> {
+        let dir = self.dir.clone();
+        tokio::task::spawn_blocking(move || {
+            let mut entries = Vec::new();
+            collect_entries(&dir, &mut entries);
+            entries
+                .into_iter()
+                .filter(|(path, _, _)| std::fs::remove_file(path).is_ok())
+                .count()
+        })
+        .await
+        .map_err(|e| AppError::InternalAppError(format!("Cache clear failed: {}", e)))
+    }
+}
+
+/// `$XDG_CACHE_HOME/academic-paper-interpreter-mcp`, falling back to `~/.cache`
+pub fn default_cache_root() -> PathBuf {
+    let base = env::var_os("XDG_CACHE_HOME")
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))
+        .unwrap_or_else(env::temp_dir);
+    base.join(CACHE_DIR_NAME)
+}
+
+/// Write through a temporary file so readers never see a partial entry
+async fn write_atomic(path: &Path, bytes: &[u8]) -> AppResult<()> {
+    let io_error =
+        |e: std::io::Error| AppError::InternalAppError(format!("Cache write failed: {}", e));
+
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent).await.map_err(io_error)?;
+    }
+    let tmp = path.with_extension(format!("tmp.{}", uuid::Uuid::new_v4()));
+    tokio::fs::write(&tmp, bytes).await.map_err(io_error)?;
+    if let Err(e) = tokio::fs::rename(&tmp, path).await {
+        let _ = tokio::fs::remove_file(&tmp).await;
+        return Err(io_error(e));
+    }
+    Ok(())
+}
+
+/// Remove expired entries, then the least recently written ones until under `max_bytes`
+fn evict(dir: &Path, max_bytes: u64, ttl: Duration) {
+    let mut entries = Vec::new();
+    collect_entries(dir, &mut entries);
+
+    let now = SystemTime::now();
+    let mut total: u64 = 0;
+    let mut live = Vec::new();
+    for (path, size, modified) in entries {
+        let expired = now.duration_since(modified).is_ok_and(|age| age > ttl);
+        if expired {
+            let _ = std::fs::remove_file(&path);
+        } else {
+            total += size;
+            live.push((path, size, modified));
+        }
+    }
+
+    if total <= max_bytes {
+        return;
+    }
+
+    live.sort_by_key(|(_, _, modified)| *modified);
+    for (path, size, _) in live {
+        if total <= max_bytes {
+            break;
+        }
+        if std::fs::remove_file(&path).is_ok() {
+            tracing::debug!("Evicted cache entry {}", path.display());
+            total = total.saturating_sub(size);
+        }
+    }
+}
+
+fn collect_entries(dir: &Path, out: &mut Vec<(PathBuf, u64, SystemTime)>) {
+    let Ok(read_dir) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in read_dir.flatten() {
+        let path = entry.path();
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        if metadata.is_dir() {
+            collect_entries(&path, out);
+        } else if path.extension().is_some_and(|ext| ext == "json") {
+            let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+            out.push((path, metadata.len(), modified));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_size_eviction() {
+        let dir = tempfile::tempdir().unwrap();
+        let big = "x".repeat(600);
+        let store = DiskStore::new(dir.path().to_path_buf(), Duration::from_secs(60), 1000);
+
+        store.put(Path::new("a/first.json"), &big).await.unwrap();
+        // Make sure the second entry is strictly newer on coarse-grained filesystems
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        store.put(Path::new("b/second.json"), &big).await.unwrap();
+
+        assert!(store
+            .get::<String>(Path::new("a/first.json"))
+            .await
+            .is_none());
+        assert!(store
+            .get::<String>(Path::new("b/second.json"))
+            .await
+            .is_some());
+    }
+
+    #[tokio::test]
+    async fn test_zero_ttl_disables_store() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = DiskStore::new(dir.path().to_path_buf(), Duration::ZERO, 1 << 20);
+
+        store.put(Path::new("entry.json"), &"value").await.unwrap();
+        assert!(store.get::<String>(Path::new("entry.json")).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_list() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = DiskStore::new(dir.path().to_path_buf(), Duration::from_secs(60), 1 << 20);
+
+        store.put(Path::new("a/1.json"), &1).await.unwrap();
+        store.put(Path::new("b/2.json"), &2).await.unwrap();
+        assert_eq!(
+            store.list(Path::new("a")).await,
+            vec![PathBuf::from("a/1.json")]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_clear() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = DiskStore::new(dir.path().to_path_buf(), Duration::from_secs(60), 1 << 20);
+
+        store.put(Path::new("a/1.json"), &1).await.unwrap();
+        store.put(Path::new("b/2.json"), &2).await.unwrap();
+        assert_eq!(store.clear().await.unwrap(), 2);
+        assert!(store.get::<i32>(Path::new("a/1.json")).await.is_none());
+    }
+}
@@ -1,3 +1,7 @@
+pub mod cache;
 pub mod llm;
 pub mod models;
+pub mod pipeline;
+pub mod progress;
 pub mod server;
+pub mod sources;
@@ -0,0 +1,583 @@
+use super::config::compatible_api_key;
+use crate::models::llm_config::{LlmConfig, LlmProvider};
+use crate::progress::Progress;
+use crate::sources::http::retry_after_secs;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use rmcp::model::{
+    Content, CreateMessageRequestParam, ModelHint, ModelPreferences, Role, SamplingMessage,
+};
+use rmcp::service::{Peer, RoleServer};
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+use shared::errors::{AppError, AppResult};
+use std::env;
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+
+/// Default completion budget for prompt-based pipelines
+const DEFAULT_MAX_TOKENS: u32 = 4096;
+
+/// Default per-request timeout; long papers can take minutes to analyze
+const DEFAULT_TIMEOUT_SECS: u64 = 300;
+
+/// Tokens set aside for a prompt's instructions and metadata around the paper text
+const PROMPT_OVERHEAD_TOKENS: u32 = 1_024;
+
+/// Smallest input budget, so tiny context settings still make progress
+const MIN_INPUT_TOKENS: u32 = 512;
+
+/// Headers that carry credentials, which come only from the provider's key
+const CREDENTIAL_HEADERS: &[&str] = &[
+    "authorization",
+    "proxy-authorization",
+    "x-api-key",
+    "api-key",
+];
+
+/// Minimal chat-completion client used by the analysis pipelines
+///
+/// Model, sampling parameters and timeout all come from the resolved
+/// `LlmConfig`, so they can be overridden per call. The base URL, API key,
+/// and extra headers are server configuration only. With the sampling
+/// provider, prompts go to the MCP client instead of an HTTP API.
+pub struct ChatClient {
+    provider: LlmProvider,
+    model: String,
+    model_hint: Option<String>,
+    base_url: String,
+    api_key: Option<String>,
+    temperature: Option<f32>,
+    max_tokens: Option<u32>,
+    context_length: u32,
+    timeout: Duration,
+    headers: HeaderMap,
+    http: reqwest::Client,
+    peer: Option<Peer<RoleServer>>,
+    progress: Progress,
+    cancel: CancellationToken,
+}
+
+impl ChatClient {
+    /// Create a client for the resolved LLM configuration
+    pub fn from_config(config: &LlmConfig) -> AppResult<Self> {
+        config.endpoint_profile()?;
+        let base_url = config.effective_base_url();
+        if config.provider == LlmProvider::OpenAiCompatible && base_url.is_empty() {
+            return Err(AppError::LlmConfigError(
+                "OPENAI_COMPATIBLE_BASE_URL environment variable not set".to_string(),
+            ));
+        }
+        Self::with_base_url(config, base_url)
+    }
+
+    /// Create a client that sends requests to `base_url`
+    fn with_base_url(config: &LlmConfig, base_url: String) -> AppResult<Self> {
+        let api_key = match config.provider {
+            LlmProvider::OpenAi => Some(env::var("OPENAI_API_KEY").map_err(|_| {
+                AppError::LlmConfigError("OPENAI_API_KEY environment variable not set".to_string())
+            })?),
+            LlmProvider::Anthropic => Some(env::var("ANTHROPIC_API_KEY").map_err(|_| {
+                AppError::LlmConfigError(
+                    "ANTHROPIC_API_KEY environment variable not set".to_string(),
+                )
+            })?),
+            LlmProvider::OpenAiCompatible => {
+                compatible_api_key(config.endpoint_profile()?.as_ref())?
+            }
+            LlmProvider::Ollama | LlmProvider::Sampling => None,
+        };
+
+        let mut headers = HeaderMap::new();
+        for (name, value) in &config.effective_headers() {
+            let name = HeaderName::try_from(name.as_str())
+                .map_err(|e| AppError::LlmConfigError(format!("Invalid header {}: {}", name, e)))?;
+            if CREDENTIAL_HEADERS.contains(&name.as_str()) {
+                return Err(AppError::LlmConfigError(format!(
+                    "Extra LLM headers cannot set {}; the API key comes from the provider's key variable",
+                    name
+                )));
+            }
+            let value = HeaderValue::try_from(value.as_str()).map_err(|e| {
+                AppError::LlmConfigError(format!("Invalid value for header {}: {}", name, e))
+            })?;
+            headers.insert(name, value);
+        }
+
+        let timeout = Duration::from_secs(config.timeout_secs.unwrap_or(DEFAULT_TIMEOUT_SECS));
+        let http = reqwest::Client::builder()
+            .timeout(timeout)
+            .build()
+            .map_err(|e| AppError::LlmConfigError(format!("Failed to build HTTP client: {}", e)))?;
+
+        Ok(Self {
+            provider: config.provider.clone(),
+            model: config.effective_model(),
+            model_hint: config.model.clone(),
+            base_url,
+            api_key,
+            temperature: config.temperature,
+            max_tokens: config.max_tokens,
+            context_length: config.effective_context_length(),
+            timeout,
+            headers,
+            http,
+            peer: None,
+            progress: Progress::none(),
+            cancel: CancellationToken::new(),
+        })
+    }
+
+    /// Report each LLM call (and pipeline stages) to `progress`
+    pub fn with_progress(mut self, progress: Progress) -> Self {
+        self.progress = progress;
+        self
+    }
+
+    /// Send sampling requests to `peer`, the client of the tool call
+    pub fn with_peer(mut self, peer: Option<Peer<RoleServer>>) -> Self {
+        self.peer = peer;
+        self
+    }
+
+    /// Abort in-flight LLM calls, and fail new ones, once `ct` is cancelled
+    pub fn with_cancellation(mut self, ct: CancellationToken) -> Self {
+        self.cancel = ct;
+        self
+    }
+
+    /// Progress reporter of the tool call this client serves
+    pub fn progress(&self) -> &Progress {
+        &self.progress
+    }
+
+    /// Tokens of input text that fit in one prompt, leaving room for the
+    /// instructions and the reply
+    pub fn input_budget(&self) -> usize {
+        let reply = self
+            .max_tokens
+            .unwrap_or(DEFAULT_MAX_TOKENS)
+            .min(self.context_length / 4);
+        self.context_length
+            .saturating_sub(reply + PROMPT_OVERHEAD_TOKENS)
+            .max(MIN_INPUT_TOKENS) as usize
+    }
+
+    /// Send a single system + user prompt and return the assistant's text
+    pub async fn complete(&self, system: &str, user: &str) -> AppResult<String> {
+        if self.cancel.is_cancelled() {
+            return Err(AppError::Cancelled("LLM call skipped".to_string()));
+        }
+        self.progress
+            .stage(&format!("Calling {}", self.model))
+            .await;
+
+        // Dropping the request future closes the connection, so the provider stops generating
+        self.cancel
+            .run_until_cancelled(self.send(system, user))
+            .await
+            .unwrap_or_else(|| Err(AppError::Cancelled("LLM call aborted".to_string())))
+    }
+
+    async fn send(&self, system: &str, user: &str) -> AppResult<String> {
+        if self.provider == LlmProvider::Sampling {
+            return self.sample(system, user).await;
+        }
+        let (url, body) = self.build_request(system, user);
+
+        let mut request = self
+            .http
+            .post(&url)
+            .headers(self.headers.clone())
+            .json(&body);
+        if let Some(ref key) = self.api_key {
+            request = match self.provider {
+                LlmProvider::Anthropic => request
+                    .header("x-api-key", key)
+                    .header("anthropic-version", "2023-06-01"),
+                _ => request.bearer_auth(key),
+            };
+        }
+
+        tracing::debug!("LLM request: {} (model {})", url, self.model);
+        let response = request.send().await.map_err(|e| {
+            if e.is_timeout() {
+                AppError::NetworkError(format!("LLM request to {} timed out", url))
+            } else {
+                AppError::NetworkError(format!("LLM request failed: {}", e))
+            }
+        })?;
+
+        let status = response.status();
+        if status.as_u16() == 429 {
+            return Err(AppError::RateLimitExceeded {
+                message: format!("{} returned 429 Too Many Requests", url),
+                retry_after_secs: retry_after_secs(response.headers()),
+            });
+        }
+        if !status.is_success() {
+            let text = response.text().await.unwrap_or_default();
+            return Err(AppError::LlmError(format!(
+                "{} returned {}: {}",
+                url, status, text
+            )));
+        }
+
+        let value: Value = response
+            .json()
+            .await
+            .map_err(|e| AppError::LlmError(format!("Invalid LLM response: {}", e)))?;
+
+        extract_message_text(&self.provider, &value)
+            .ok_or_else(|| AppError::LlmError(format!("Unexpected LLM response shape: {}", value)))
+    }
+
+    /// Ask the MCP client to run the prompt on its own model
+    async fn sample(&self, system: &str, user: &str) -> AppResult<String> {
+        let peer = self.peer.as_ref().ok_or_else(|| {
+            AppError::LlmConfigError("Sampling needs a connected MCP client".to_string())
+        })?;
+        if peer.peer_info().capabilities.sampling.is_none() {
+            return Err(AppError::LlmConfigError(
+                "The MCP client does not support sampling; configure another LLM provider"
+                    .to_string(),
+            ));
+        }
+
+        tracing::debug!("LLM request: sampling/createMessage");
+        let result = tokio::time::timeout(
+            self.timeout,
+            peer.create_message(self.sampling_request(system, user)),
+        )
+        .await
+        .map_err(|_| AppError::NetworkError("Sampling request timed out".to_string()))?
+        .map_err(|e| AppError::LlmError(format!("Sampling request failed: {}", e)))?;
+        tracing::debug!("Sampled with {}", result.model);
+
+        result
+            .message
+            .content
+            .as_text()
+            .map(|content| content.text.clone())
+            .ok_or_else(|| AppError::LlmError("Sampling reply was not text".to_string()))
+    }
+
+    /// Build the `sampling/createMessage` parameters for a prompt
+    fn sampling_request(&self, system: &str, user: &str) -> CreateMessageRequestParam {
+        CreateMessageRequestParam {
+            messages: vec![SamplingMessage {
+                role: Role::User,
+                content: Content::text(user),
+            }],
+            model_preferences: self.model_hint.as_ref().map(|name| ModelPreferences {
+                hints: Some(vec![ModelHint {
+                    name: Some(name.clone()),
+                }]),
+                cost_priority: None,
+                speed_priority: None,
+                intelligence_priority: None,
+            }),
+            system_prompt: Some(system.to_string()),
+            include_context: None,
+            temperature: self.temperature,
+            max_tokens: self.max_tokens.unwrap_or(DEFAULT_MAX_TOKENS),
+            stop_sequences: None,
+            metadata: None,
+        }
+    }
+
+    /// Build the provider-specific endpoint URL and request body
+    fn build_request(&self, system: &str, user: &str) -> (String, Value) {
+        match self.provider {
+            LlmProvider::OpenAi | LlmProvider::OpenAiCompatible => {
+                let mut body = json!({
+                    "model": self.model,
+                    "messages": [
+                        { "role": "system", "content": system },
+                        { "role": "user", "content": user },
+                    ],
+                });
+                if let Some(temperature) = self.temperature {
+                    body["temperature"] = json!(temperature);
+                }
+                if let Some(max_tokens) = self.max_tokens {
+                    // Compatible servers do not all know the newer parameter name
+                    let key = match self.provider {
+                        LlmProvider::OpenAi => "max_completion_tokens",
+                        _ => "max_tokens",
+                    };
+                    body[key] = json!(max_tokens);
+                }
+                (format!("{}/chat/completions", self.base_url), body)
+            }
+            LlmProvider::Anthropic => {
+                let mut body = json!({
+                    "model": self.model,
+                    "max_tokens": self.max_tokens.unwrap_or(DEFAULT_MAX_TOKENS),
+                    "system": system,
+                    "messages": [{ "role": "user", "content": user }],
+                });
+                if let Some(temperature) = self.temperature {
+                    body["temperature"] = json!(temperature);
+                }
+                (format!("{}/v1/messages", self.base_url), body)
+            }
+            LlmProvider::Ollama => {
+                let mut options = json!({});
+                if let Some(temperature) = self.temperature {
+                    options["temperature"] = json!(temperature);
+                }
+                if let Some(max_tokens) = self.max_tokens {
+                    options["num_predict"] = json!(max_tokens);
+                }
+                // Ollama otherwise truncates prompts to its small default context
+                options["num_ctx"] = json!(self.context_length);
+                let body = json!({
+                    "model": self.model,
+                    "stream": false,
+                    "options": options,
+                    "messages": [
+                        { "role": "system", "content": system },
+                        { "role": "user", "content": user },
+                    ],
+                });
+                (format!("{}/api/chat", self.base_url), body)
+            }
+            LlmProvider::Sampling => unreachable!("sampling prompts are sent to the MCP client"),
+        }
+    }
+
+    /// Send a prompt that asks for JSON and deserialize the reply
+    pub async fn complete_json<T: DeserializeOwned>(
+        &self,
+        system: &str,
+        user: &str,
+    ) -> AppResult<T> {
+        let text = self.complete(system, user).await?;
+        parse_json_reply(&text)
+    }
+}
+
+/// Pull the assistant text out of a provider-specific response body
+fn extract_message_text(provider: &LlmProvider, value: &Value) -> Option<String> {
+    match provider {
+        LlmProvider::OpenAi | LlmProvider::OpenAiCompatible => value["choices"][0]["message"]
+            ["content"]
+            .as_str()
+            .map(str::to_string),
+        LlmProvider::Anthropic => {
+            let text: String = value["content"]
+                .as_array()?
+                .iter()
+                .filter_map(|block| block["text"].as_str())
+                .collect();
+            Some(text)
+        }
+        LlmProvider::Ollama => value["message"]["content"].as_str().map(str::to_string),
+        LlmProvider::Sampling => None,
+    }
+}
+
+/// Parse a JSON object out of an LLM reply, tolerating code fences and prose around it
+pub fn parse_json_reply<T: DeserializeOwned>(text: &str) -> AppResult<T> {
+    let start = text.find('{');
+    let end = text.rfind('}');
+    let json = match (start, end) {
+        (Some(start), Some(end)) if start < end => &text[start..=end],
+        _ => {
+            return Err(AppError::LlmError(format!(
+                "LLM reply did not contain a JSON object: {}",
+                text
+            )));
+        }
+    };
+
+    serde_json::from_str(json)
+        .map_err(|e| AppError::LlmError(format!("Failed to parse LLM JSON reply: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Reply {
+        summary: String,
+    }
+
+    #[test]
+    fn test_parse_json_reply() {
+        let reply: Reply = parse_json_reply(r#"{"summary": "ok"}"#).unwrap();
+        assert_eq!(reply.summary, "ok");
+
+        let fenced = "Here you go:\n```json\n{\"summary\": \"fenced\"}\n```";
+        let reply: Reply = parse_json_reply(fenced).unwrap();
+        assert_eq!(reply.summary, "fenced");
+
+        assert!(parse_json_reply::<Reply>("no json here").is_err());
+    }
+
+    fn client(
+        provider: LlmProvider,
+        temperature: Option<f32>,
+        max_tokens: Option<u32>,
+    ) -> ChatClient {
+        ChatClient {
+            provider,
+            model: "test-model".to_string(),
+            model_hint: Some("test-model".to_string()),
+            base_url: "http://localhost:8000".to_string(),
+            api_key: None,
+            temperature,
+            max_tokens,
+            context_length: 8_192,
+            timeout: Duration::from_secs(DEFAULT_TIMEOUT_SECS),
+            headers: HeaderMap::new(),
+            http: reqwest::Client::new(),
+            peer: None,
+            progress: Progress::none(),
+            cancel: CancellationToken::new(),
+        }
+    }
+
+    #[test]
+    fn test_build_request() {
+        let (url, body) = client(LlmProvider::OpenAi, Some(0.3), Some(256)).build_request("s", "u");
+        assert_eq!(url, "http://localhost:8000/chat/completions");
+        assert_eq!(body["model"], "test-model");
+        assert_eq!(body["temperature"].as_f64(), Some(0.3f32 as f64));
+        assert_eq!(body["max_completion_tokens"], 256);
+
+        // Unset options are left to the provider's defaults
+        let (_, body) = client(LlmProvider::OpenAi, None, None).build_request("s", "u");
+        assert!(body.get("temperature").is_none());
+        assert!(body.get("max_completion_tokens").is_none());
+
+        let (url, body) = client(LlmProvider::Anthropic, None, None).build_request("s", "u");
+        assert_eq!(url, "http://localhost:8000/v1/messages");
+        assert_eq!(body["max_tokens"], DEFAULT_MAX_TOKENS);
+
+        let (_, body) = client(LlmProvider::Ollama, Some(0.0), Some(128)).build_request("s", "u");
+        assert_eq!(body["options"]["temperature"], 0.0);
+        assert_eq!(body["options"]["num_predict"], 128);
+        assert_eq!(body["options"]["num_ctx"], 8_192);
+
+        let request = client(LlmProvider::Sampling, None, None).sampling_request("s", "u");
+        assert_eq!(request.system_prompt.as_deref(), Some("s"));
+        assert_eq!(request.messages[0].content.as_text().unwrap().text, "u");
+        assert_eq!(request.max_tokens, DEFAULT_MAX_TOKENS);
+        let hints = request.model_preferences.unwrap().hints.unwrap();
+        assert_eq!(hints[0].name.as_deref(), Some("test-model"));
+    }
+
+    #[test]
+    fn test_extract_message_text() {
+        let openai = json!({ "choices": [{ "message": { "content": "hello" } }] });
+        assert_eq!(
+            extract_message_text(&LlmProvider::OpenAi, &openai),
+            Some("hello".to_string())
+        );
+
+        let anthropic = json!({ "content": [{ "type": "text", "text": "hi" }] });
+        assert_eq!(
+            extract_message_text(&LlmProvider::Anthropic, &anthropic),
+            Some("hi".to_string())
+        );
+
+        let ollama = json!({ "message": { "role": "assistant", "content": "hey" } });
+        assert_eq!(
+            extract_message_text(&LlmProvider::Ollama, &ollama),
+            Some("hey".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_openai_compatible_provider() {
+        use axum::extract::State;
+        use axum::http::HeaderMap as Headers;
+        use std::collections::BTreeMap;
+        use std::sync::{Arc, Mutex};
+
+        type Requests = Arc<Mutex<Vec<(Headers, Value)>>>;
+
+        // Records each request's headers and body, and answers like vLLM
+        let seen: Requests = Arc::default();
+        let app = axum::Router::new()
+            .route(
+                "/v1/chat/completions",
+                axum::routing::post(
+                    |State(seen): State<Requests>,
+                     headers: Headers,
+                     axum::Json(body): axum::Json<Value>| async move {
+                        seen.lock().unwrap().push((headers, body));
+                        axum::Json(json!({
+                            "choices": [{ "message": { "role": "assistant", "content": "{\"summary\": \"ok\"}" } }]
+                        }))
+                    },
+                ),
+            )
+            .with_state(seen.clone());
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}/v1", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let config = LlmConfig {
+            provider: LlmProvider::OpenAiCompatible,
+            model: Some("Qwen/Qwen2.5-7B-Instruct".to_string()),
+            max_tokens: Some(64),
+            headers: BTreeMap::from([("X-Team".to_string(), "papers".to_string())]),
+            ..Default::default()
+        };
+        let mut chat = ChatClient::with_base_url(&config, base_url.clone()).unwrap();
+        chat.api_key = Some("test-key".to_string());
+
+        let reply: Reply = chat.complete_json("s", "u").await.unwrap();
+        assert_eq!(reply.summary, "ok");
+
+        let (headers, body) = seen.lock().unwrap().pop().unwrap();
+        assert_eq!(headers["authorization"], "Bearer test-key");
+        assert_eq!(headers["x-team"], "papers");
+        assert_eq!(body["model"], "Qwen/Qwen2.5-7B-Instruct");
+        assert_eq!(body["max_tokens"], 64);
+        assert_eq!(body["messages"][1]["content"], "u");
+
+        // Credentials cannot be added next to the provider's own key
+        let config = LlmConfig {
+            headers: BTreeMap::from([("Authorization".to_string(), "Bearer x".to_string())]),
+            ..config
+        };
+        assert!(matches!(
+            ChatClient::with_base_url(&config, base_url),
+            Err(AppError::LlmConfigError(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_cancel_aborts_in_flight_call() {
+        // A server that accepts the connection but never answers
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let _connection = listener.accept().await;
+            std::future::pending::<()>().await;
+        });
+
+        let ct = CancellationToken::new();
+        let mut chat = client(LlmProvider::Ollama, None, None).with_cancellation(ct.clone());
+        chat.base_url = base_url;
+
+        let canceller = ct.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            canceller.cancel();
+        });
+
+        let result = chat.complete("s", "u").await;
+        assert!(matches!(result, Err(AppError::Cancelled(_))));
+        // Later calls fail without reaching the provider
+        assert!(matches!(
+            chat.complete("s", "u").await,
+            Err(AppError::Cancelled(_))
+        ));
+    }
+}
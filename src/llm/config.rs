@@ -1,7 +1,10 @@
-use crate::models::llm_config::{LlmConfig, LlmProvider};
+use crate::models::llm_config::{EndpointProfile, LlmConfig, LlmProvider};
 use shared::errors::{AppError, AppResult};
 use std::env;
 
+/// Key variable of an OpenAI-compatible server unless OPENAI_COMPATIBLE_API_KEY_ENV names another
+const DEFAULT_COMPATIBLE_KEY_ENV: &str = "OPENAI_COMPATIBLE_API_KEY";
+
 /// Resolves LLM configuration from environment and parameters
 pub struct LlmConfigResolver {
     default_config: LlmConfig,
@@ -21,6 +24,7 @@ impl LlmConfigResolver {
 
     /// Validate that required API keys are present
     pub fn validate_api_key(&self, config: &LlmConfig) -> AppResult<()> {
+        let profile = config.endpoint_profile()?;
         match config.provider {
             LlmProvider::OpenAi => {
                 if env::var("OPENAI_API_KEY").is_err() {
@@ -38,15 +42,55 @@ impl LlmConfigResolver {
             }
             LlmProvider::Ollama => {
                 // Ollama doesn't require API key, but check server URL
-                let base_url = env::var("OLLAMA_BASE_URL")
-                    .unwrap_or_else(|_| "http://localhost:11434".to_string());
-                tracing::debug!("Using Ollama at {}", base_url);
+                tracing::debug!("Using Ollama at {}", config.effective_base_url());
+            }
+            LlmProvider::OpenAiCompatible => {
+                if config.effective_base_url().is_empty() {
+                    return Err(AppError::LlmConfigError(match config.endpoint {
+                        Some(ref name) => format!("Endpoint {} has no base_url", name),
+                        None => {
+                            "OPENAI_COMPATIBLE_BASE_URL environment variable not set".to_string()
+                        }
+                    }));
+                }
+                compatible_api_key(profile.as_ref())?;
+            }
+            LlmProvider::Sampling => {
+                // The client's model runs the prompts; whether the client
+                // supports sampling is only known per connection
             }
         }
         Ok(())
     }
 }
 
+/// API key of an OpenAI-compatible server
+///
+/// Read from the variable the selected endpoint's `api_key_env` names, or
+/// otherwise OPENAI_COMPATIBLE_API_KEY_ENV, so an operator can reuse a key
+/// already in the environment; calls cannot choose it. Local servers often
+/// need no key, so an endpoint may omit it and the default variable may be
+/// unset.
+pub(crate) fn compatible_api_key(profile: Option<&EndpointProfile>) -> AppResult<Option<String>> {
+    if let Some(profile) = profile {
+        return profile
+            .api_key_env
+            .as_deref()
+            .map(|var| {
+                env::var(var).map_err(|_| {
+                    AppError::LlmConfigError(format!("{} environment variable not set", var))
+                })
+            })
+            .transpose();
+    }
+    let Ok(var) = env::var("OPENAI_COMPATIBLE_API_KEY_ENV") else {
+        return Ok(env::var(DEFAULT_COMPATIBLE_KEY_ENV).ok());
+    };
+    env::var(&var)
+        .map(Some)
+        .map_err(|_| AppError::LlmConfigError(format!("{} environment variable not set", var)))
+}
+
 impl Default for LlmConfigResolver {
     fn default() -> Self {
         Self::new()
@@ -1,5 +1,7 @@
+pub mod chat;
 pub mod config;
 pub mod provider;
 
+pub use chat::ChatClient;
 pub use config::LlmConfigResolver;
-pub use provider::{create_analyzer, create_paper_client, AnalyzerType};
+pub use provider::create_paper_client;
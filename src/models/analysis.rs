@@ -1,3 +1,4 @@
+use super::paper::PaperSummary;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
@@ -21,6 +22,181 @@ pub struct PaperAnalysis {
     #[schemars(description = "Related work references")]
     #[serde(default)]
     pub related_work: Vec<String>,
+
+    #[schemars(description = "Per-section deep dive (detailed analysis only)")]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sections: Vec<SectionAnalysis>,
+
+    #[schemars(description = "Structured comparison with other papers (comparison analysis only)")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comparison: Option<PaperComparison>,
+}
+
+/// Answer to a question about a paper, with the passages that support it
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct PaperAnswer {
+    #[schemars(description = "Answer to the question, based only on the paper")]
+    pub answer: String,
+
+    #[schemars(description = "Verbatim quotes from the paper supporting the answer")]
+    #[serde(default)]
+    pub evidence: Vec<Evidence>,
+}
+
+/// A verbatim span of the paper's text
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Evidence {
+    #[schemars(description = "Text copied exactly from the section")]
+    pub quote: String,
+
+    #[schemars(description = "Heading of the section the quote is from")]
+    pub section: String,
+
+    #[schemars(
+        description = "Character offset where the quote starts in the section's text, as returned by get_paper_section"
+    )]
+    pub start: usize,
+
+    #[schemars(description = "Character offset just past the end of the quote")]
+    pub end: usize,
+}
+
+/// Analysis of a single paper section
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SectionAnalysis {
+    #[schemars(description = "Section heading as it appears in the paper")]
+    pub heading: String,
+
+    #[schemars(description = "Summary of the section")]
+    pub summary: String,
+
+    #[schemars(description = "Key points, claims, or findings in the section")]
+    #[serde(default)]
+    pub key_points: Vec<String>,
+}
+
+/// Structured comparison across several papers
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct PaperComparison {
+    #[schemars(description = "Titles of the compared papers, in comparison order")]
+    pub papers: Vec<String>,
+
+    #[schemars(description = "Overall comparison narrative")]
+    pub summary: String,
+
+    #[schemars(description = "What the papers have in common")]
+    #[serde(default)]
+    pub similarities: Vec<String>,
+
+    #[schemars(description = "Where the papers differ")]
+    #[serde(default)]
+    pub differences: Vec<String>,
+
+    #[schemars(description = "Side-by-side comparison along individual aspects")]
+    #[serde(default)]
+    pub aspects: Vec<ComparisonAspect>,
+}
+
+/// One row of a paper comparison
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ComparisonAspect {
+    #[schemars(description = "Aspect being compared (e.g., method, datasets)")]
+    pub aspect: String,
+
+    #[schemars(description = "Value for each paper, in the same order as `papers`")]
+    pub values: Vec<String>,
+}
+
+/// Papers compared along fixed dimensions, with where they agree and disagree
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ComparisonMatrix {
+    #[schemars(description = "One row per paper, in the order requested")]
+    pub rows: Vec<ComparisonRow>,
+
+    #[schemars(description = "Narrative of where the papers agree and disagree")]
+    pub narrative: String,
+
+    #[schemars(description = "Points the papers agree on")]
+    #[serde(default)]
+    pub agreements: Vec<String>,
+
+    #[schemars(description = "Points where the papers disagree or report conflicting results")]
+    #[serde(default)]
+    pub disagreements: Vec<String>,
+}
+
+/// One paper's entry in a comparison matrix
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ComparisonRow {
+    pub title: String,
+
+    #[schemars(description = "Task or problem the paper addresses")]
+    pub task: String,
+
+    #[schemars(description = "Proposed method")]
+    pub method: String,
+
+    #[serde(default)]
+    pub datasets: Vec<String>,
+
+    #[serde(default)]
+    pub metrics: Vec<String>,
+
+    #[schemars(
+        description = "Main reported numbers, with dataset and metric (e.g., \"28.4 BLEU on WMT14 En-De\")"
+    )]
+    #[serde(default)]
+    pub reported_results: Vec<String>,
+
+    #[schemars(description = "Assumptions the method or evaluation relies on")]
+    #[serde(default)]
+    pub assumptions: Vec<String>,
+
+    #[serde(default)]
+    pub limitations: Vec<String>,
+}
+
+/// Literature review of the papers found for a topic
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct LiteratureReview {
+    #[schemars(description = "Topic the review covers")]
+    pub topic: String,
+
+    #[schemars(
+        description = "Review text citing papers inline as [n], the `citation` of an entry in `papers`"
+    )]
+    pub review: String,
+
+    #[schemars(description = "Themes the papers are grouped into")]
+    pub themes: Vec<ReviewTheme>,
+
+    #[schemars(description = "Reviewed papers, in ranking order")]
+    pub papers: Vec<ReviewedPaper>,
+}
+
+/// A group of reviewed papers with a common approach or question
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ReviewTheme {
+    pub name: String,
+    pub description: String,
+
+    #[schemars(description = "Citation numbers of the papers in the theme")]
+    pub papers: Vec<usize>,
+}
+
+/// A paper included in a literature review
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ReviewedPaper {
+    #[schemars(description = "Number the review cites the paper by, as [n]")]
+    pub citation: usize,
+
+    pub paper: PaperSummary,
+
+    #[schemars(description = "Summary from the paper's analysis")]
+    pub summary: String,
+
+    #[serde(default)]
+    pub key_contributions: Vec<String>,
 }
 
 /// Type of analysis to perform
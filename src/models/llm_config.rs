@@ -1,5 +1,7 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use shared::errors::{AppError, AppResult};
+use std::collections::BTreeMap;
 use std::env;
 
 /// Supported LLM providers
@@ -10,18 +12,62 @@ pub enum LlmProvider {
     OpenAi,
     Anthropic,
     Ollama,
+    /// Any server speaking the OpenAI Chat Completions API (vLLM, LM Studio, gateways)
+    #[serde(rename = "openai_compatible")]
+    OpenAiCompatible,
+    /// The MCP client's own model, through `sampling/createMessage`
+    Sampling,
 }
 
 /// LLM configuration with environment variable fallbacks
+///
+/// Unknown fields are rejected rather than ignored, so a call that tries to
+/// set server-only settings such as `base_url` fails instead of silently
+/// running against the server's endpoint.
 #[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct LlmConfig {
-    #[schemars(description = "LLM provider: openai, anthropic, or ollama")]
+    #[schemars(
+        description = "LLM provider: openai, anthropic, ollama, openai_compatible, or sampling (the MCP client's own model)"
+    )]
     #[serde(default)]
     pub provider: LlmProvider,
 
     #[schemars(description = "Model name to use (provider-specific)")]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub model: Option<String>,
+
+    #[schemars(
+        description = "Name of an OpenAI-compatible endpoint configured on the server (LLM_ENDPOINTS), with its own base URL, API key, and headers; requires provider openai_compatible"
+    )]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub endpoint: Option<String>,
+
+    #[schemars(description = "Sampling temperature (provider default if omitted)")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+
+    #[schemars(description = "Maximum number of tokens to generate per completion")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<u32>,
+
+    /// Extra HTTP headers sent with every LLM request, from LLM_HEADERS
+    ///
+    /// Server configuration only, like the base URL: headers can carry
+    /// credentials, and calls must not choose where those go. A named
+    /// endpoint sends its own headers instead.
+    #[serde(skip)]
+    pub headers: BTreeMap<String, String>,
+
+    #[schemars(description = "Request timeout in seconds")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_secs: Option<u64>,
+
+    #[schemars(
+        description = "Model context window in tokens, used to size the chunks long papers are split into (derived from the model name if omitted)"
+    )]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context_length: Option<u32>,
 }
 
 impl LlmConfig {
@@ -34,22 +80,45 @@ impl LlmConfig {
         {
             "anthropic" => LlmProvider::Anthropic,
             "ollama" => LlmProvider::Ollama,
+            "openai_compatible" => LlmProvider::OpenAiCompatible,
+            "sampling" => LlmProvider::Sampling,
             _ => LlmProvider::OpenAi,
         };
 
         Self {
             provider,
             model: None,
+            endpoint: None,
+            temperature: env_parse("LLM_TEMPERATURE"),
+            max_tokens: env_parse("LLM_MAX_TOKENS"),
+            headers: env_headers("LLM_HEADERS"),
+            timeout_secs: env_parse("LLM_TIMEOUT_SECS"),
+            context_length: env_parse("LLM_CONTEXT_LENGTH"),
         }
     }
 
     /// Merge with parameter overrides (parameters take precedence)
     pub fn merge_with(&self, override_config: Option<&LlmConfig>) -> Self {
         match override_config {
-            Some(cfg) => Self {
-                provider: cfg.provider.clone(),
-                model: cfg.model.clone().or_else(|| self.model.clone()),
-            },
+            Some(cfg) => {
+                // Headers only make sense for the provider they were configured for
+                let headers = if cfg.provider == self.provider {
+                    self.headers.clone()
+                } else {
+                    BTreeMap::new()
+                };
+
+                Self {
+                    provider: cfg.provider.clone(),
+                    model: cfg.model.clone().or_else(|| self.model.clone()),
+                    endpoint: cfg.endpoint.clone(),
+                    temperature: cfg.temperature.or(self.temperature),
+                    max_tokens: cfg.max_tokens.or(self.max_tokens),
+                    headers,
+                    timeout_secs: cfg.timeout_secs.or(self.timeout_secs),
+                    context_length: cfg.context_length.or(self.context_length),
+                }
+            }
             None => self.clone(),
         }
     }
@@ -69,6 +138,261 @@ impl LlmConfig {
             LlmProvider::Ollama => {
                 env::var("OLLAMA_MODEL").unwrap_or_else(|_| "llama3.2".to_string())
             }
+            LlmProvider::OpenAiCompatible => self
+                .endpoint_profile()
+                .ok()
+                .flatten()
+                .map_or_else(
+                    || env::var("OPENAI_COMPATIBLE_MODEL").ok(),
+                    |profile| profile.model,
+                )
+                .unwrap_or_else(|| "default".to_string()),
+            // The client picks the model; a configured one is only sent as a hint
+            LlmProvider::Sampling => "client".to_string(),
+        }
+    }
+
+    /// API base URL of the provider, from server configuration only
+    ///
+    /// Requests carry the server's API keys, so a call cannot point them at
+    /// a host of its choosing; it can only pick a configured endpoint. Empty
+    /// for sampling, and for an OpenAI-compatible server or endpoint that is
+    /// not configured.
+    pub fn effective_base_url(&self) -> String {
+        if self.endpoint.is_some() {
+            return self
+                .endpoint_profile()
+                .ok()
+                .flatten()
+                .map(|profile| profile.base_url.trim_end_matches('/').to_string())
+                .unwrap_or_default();
+        }
+        let (var, default) = match self.provider {
+            LlmProvider::OpenAi => ("OPENAI_BASE_URL", "https://api.openai.com/v1"),
+            LlmProvider::Anthropic => ("ANTHROPIC_BASE_URL", "https://api.anthropic.com"),
+            LlmProvider::Ollama => ("OLLAMA_BASE_URL", "http://localhost:11434"),
+            LlmProvider::OpenAiCompatible => ("OPENAI_COMPATIBLE_BASE_URL", ""),
+            LlmProvider::Sampling => return String::new(),
+        };
+        env::var(var)
+            .unwrap_or_else(|_| default.to_string())
+            .trim_end_matches('/')
+            .to_string()
+    }
+
+    /// Extra headers sent with every LLM request
+    pub fn effective_headers(&self) -> BTreeMap<String, String> {
+        match self.endpoint_profile() {
+            Ok(Some(profile)) => profile.headers,
+            Ok(None) => self.headers.clone(),
+            Err(_) => BTreeMap::new(),
+        }
+    }
+
+    /// The server-side endpoint the call selected, if any
+    pub fn endpoint_profile(&self) -> AppResult<Option<EndpointProfile>> {
+        if self.endpoint.is_none() {
+            return Ok(None);
+        }
+        let profiles = match env::var("LLM_ENDPOINTS") {
+            Ok(value) => parse_endpoint_profiles(&value)?,
+            Err(_) => BTreeMap::new(),
+        };
+        self.select_endpoint(profiles).map(Some)
+    }
+
+    fn select_endpoint(
+        &self,
+        mut profiles: BTreeMap<String, EndpointProfile>,
+    ) -> AppResult<EndpointProfile> {
+        let name = self.endpoint.as_deref().unwrap_or_default();
+        if self.provider != LlmProvider::OpenAiCompatible {
+            return Err(AppError::LlmConfigError(format!(
+                "endpoint {} requires provider openai_compatible",
+                name
+            )));
+        }
+        profiles.remove(name).ok_or_else(|| {
+            let names: Vec<_> = profiles.keys().map(String::as_str).collect();
+            AppError::LlmConfigError(format!(
+                "Unknown endpoint {} (configured in LLM_ENDPOINTS: {})",
+                name,
+                if names.is_empty() {
+                    "none".to_string()
+                } else {
+                    names.join(", ")
+                }
+            ))
+        })
+    }
+
+    /// Context window of the effective model, in tokens
+    ///
+    /// Ollama serves models with the context it is asked for (`num_ctx`), so
+    /// its default is a size small local models handle well rather than the
+    /// model's maximum. The models behind sampling and OpenAI-compatible
+    /// servers are unknown, so they get sizes most such models handle.
+    pub fn effective_context_length(&self) -> u32 {
+        if let Some(length) = self.context_length {
+            return length;
+        }
+        let model = self.effective_model().to_lowercase();
+        match self.provider {
+            LlmProvider::Anthropic => 200_000,
+            LlmProvider::Ollama | LlmProvider::OpenAiCompatible => 8_192,
+            LlmProvider::Sampling => 32_768,
+            LlmProvider::OpenAi if model.starts_with("gpt-3.5") => 16_385,
+            LlmProvider::OpenAi if model == "gpt-4" || model.starts_with("gpt-4-0") => 8_192,
+            LlmProvider::OpenAi => 128_000,
+        }
+    }
+}
+
+/// A named OpenAI-compatible endpoint from LLM_ENDPOINTS
+///
+/// Lets one server reach several compatible servers (vLLM, LM Studio, an
+/// internal gateway) that calls select by name, each with its own key.
+#[derive(Debug, Clone, Default, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct EndpointProfile {
+    /// Base URL, up to but not including `/chat/completions`
+    pub base_url: String,
+    /// Environment variable holding the API key; none is sent if omitted
+    #[serde(default)]
+    pub api_key_env: Option<String>,
+    /// Extra headers sent with every request to the endpoint
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    /// Model used when the call names none
+    #[serde(default)]
+    pub model: Option<String>,
+}
+
+fn parse_endpoint_profiles(value: &str) -> AppResult<BTreeMap<String, EndpointProfile>> {
+    serde_json::from_str(value).map_err(|e| {
+        AppError::LlmConfigError(format!(
+            "Invalid LLM_ENDPOINTS (expected a JSON object of named endpoints): {}",
+            e
+        ))
+    })
+}
+
+/// Parse a JSON object of header names and values, ignoring an invalid one
+fn env_headers(key: &str) -> BTreeMap<String, String> {
+    let Ok(value) = env::var(key) else {
+        return BTreeMap::new();
+    };
+    serde_json::from_str(&value).unwrap_or_else(|e| {
+        tracing::warn!("Ignoring invalid {} (expected a JSON object): {}", key, e);
+        BTreeMap::new()
+    })
+}
+
+/// Parse an optional numeric environment variable, ignoring unparsable values
+fn env_parse<T: std::str::FromStr>(key: &str) -> Option<T> {
+    let value = env::var(key).ok()?;
+    match value.trim().parse() {
+        Ok(parsed) => Some(parsed),
+        Err(_) => {
+            tracing::warn!("Ignoring invalid {}: {}", key, value);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_with() {
+        let defaults = LlmConfig {
+            provider: LlmProvider::OpenAiCompatible,
+            temperature: Some(0.2),
+            timeout_secs: Some(60),
+            headers: BTreeMap::from([("X-Team".to_string(), "papers".to_string())]),
+            ..Default::default()
+        };
+
+        let merged = defaults.merge_with(Some(&LlmConfig {
+            provider: LlmProvider::OpenAiCompatible,
+            model: Some("Qwen/Qwen2.5-7B-Instruct".to_string()),
+            max_tokens: Some(512),
+            ..Default::default()
+        }));
+        assert_eq!(merged.effective_model(), "Qwen/Qwen2.5-7B-Instruct");
+        assert_eq!(merged.temperature, Some(0.2));
+        assert_eq!(merged.max_tokens, Some(512));
+        assert_eq!(merged.timeout_secs, Some(60));
+        assert_eq!(merged.headers["X-Team"], "papers");
+
+        // Switching provider drops the other provider's headers
+        let merged = defaults.merge_with(Some(&LlmConfig {
+            provider: LlmProvider::Ollama,
+            ..Default::default()
+        }));
+        assert!(merged.headers.is_empty());
+        assert_eq!(merged.temperature, Some(0.2));
+        assert_eq!(merged.effective_context_length(), 8_192);
+    }
+
+    #[test]
+    fn test_select_endpoint() {
+        let profiles = parse_endpoint_profiles(
+            r#"{
+                "vllm": { "base_url": "http://gpu:8000/v1/", "model": "Qwen/Qwen2.5-7B-Instruct" },
+                "gateway": {
+                    "base_url": "https://llm.internal/v1",
+                    "api_key_env": "GATEWAY_TOKEN",
+                    "headers": { "X-Team": "papers" }
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let config = LlmConfig {
+            provider: LlmProvider::OpenAiCompatible,
+            endpoint: Some("gateway".to_string()),
+            ..Default::default()
+        };
+        let gateway = config.select_endpoint(profiles.clone()).unwrap();
+        assert_eq!(gateway.api_key_env.as_deref(), Some("GATEWAY_TOKEN"));
+        assert_eq!(gateway.headers["X-Team"], "papers");
+
+        let unknown = LlmConfig {
+            endpoint: Some("lmstudio".to_string()),
+            ..config.clone()
+        };
+        let error = unknown.select_endpoint(profiles.clone()).unwrap_err();
+        assert!(error.to_string().contains("gateway, vllm"), "{}", error);
+
+        let wrong_provider = LlmConfig {
+            provider: LlmProvider::OpenAi,
+            ..config
+        };
+        assert!(wrong_provider.select_endpoint(profiles).is_err());
+
+        // Profiles are server configuration, so typos fail loudly too
+        assert!(parse_endpoint_profiles(r#"{ "x": { "base_url": "u", "header": {} } }"#).is_err());
+    }
+
+    #[test]
+    fn test_rejects_server_settings() {
+        // Calls cannot set where requests go or what credentials they carry
+        for (field, value) in [
+            ("base_url", serde_json::json!("https://attacker.example")),
+            ("api_key_env", serde_json::json!("ANTHROPIC_API_KEY")),
+            (
+                "headers",
+                serde_json::json!({ "Authorization": "Bearer x" }),
+            ),
+        ] {
+            let error = serde_json::from_value::<LlmConfig>(serde_json::json!({
+                "provider": "openai_compatible",
+                field: value
+            }))
+            .unwrap_err();
+            assert!(error.to_string().contains(field), "{}", error);
         }
     }
 }
@@ -37,6 +37,187 @@ pub struct Paper {
     #[schemars(description = "Full paper content (if fetched)")]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub content: Option<String>,
+
+    #[schemars(description = "Sections, captions, and references detected in the content")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub structure: Option<PaperStructure>,
+}
+
+/// Layout of a paper's full text, detected from its headings
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct PaperStructure {
+    pub sections: Vec<PaperSection>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub captions: Vec<Caption>,
+
+    /// Entries of the reference list, one per cited work
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub references: Vec<Reference>,
+
+    /// Whether the references have been looked up on Semantic Scholar
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub references_resolved: bool,
+}
+
+/// A section of the full text under one heading
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct PaperSection {
+    /// Heading as printed, e.g., "3.1 Encoder and Decoder Stacks"
+    pub heading: String,
+    /// 1 for top-level sections, 2 for subsections, and so on
+    pub level: u8,
+    pub kind: SectionKind,
+    pub text: String,
+}
+
+/// Role of a section in the paper; subsections share their parent's kind
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SectionKind {
+    /// Title, authors, and affiliations before the first heading
+    FrontMatter,
+    Abstract,
+    Introduction,
+    RelatedWork,
+    Method,
+    Experiments,
+    Results,
+    Discussion,
+    Conclusion,
+    Acknowledgments,
+    References,
+    Appendix,
+    Other,
+}
+
+impl SectionKind {
+    /// Name used in requests, e.g., `related_work`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SectionKind::FrontMatter => "front_matter",
+            SectionKind::Abstract => "abstract",
+            SectionKind::Introduction => "introduction",
+            SectionKind::RelatedWork => "related_work",
+            SectionKind::Method => "method",
+            SectionKind::Experiments => "experiments",
+            SectionKind::Results => "results",
+            SectionKind::Discussion => "discussion",
+            SectionKind::Conclusion => "conclusion",
+            SectionKind::Acknowledgments => "acknowledgments",
+            SectionKind::References => "references",
+            SectionKind::Appendix => "appendix",
+            SectionKind::Other => "other",
+        }
+    }
+}
+
+/// Caption of a figure or table
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Caption {
+    pub kind: CaptionKind,
+    /// e.g., "Figure 2" or "Table A.1"
+    pub label: String,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CaptionKind {
+    Figure,
+    Table,
+}
+
+/// An entry of a paper's reference list, parsed into bibliographic fields
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Reference {
+    /// The entry as printed
+    pub raw: String,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub authors: Vec<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+
+    /// Journal, conference, or publisher
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub venue: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub year: Option<u16>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub arxiv_id: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub doi: Option<String>,
+
+    /// Semantic Scholar paper ID, set when the entry was matched to a catalog paper
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ss_id: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub citation_count: Option<u32>,
+}
+
+impl Reference {
+    /// One-line citation: title, year, and catalog ID, or the raw entry if no title was found
+    pub fn citation(&self) -> String {
+        let Some(title) = &self.title else {
+            return self.raw.clone();
+        };
+        let mut citation = title.clone();
+        if let Some(year) = self.year {
+            citation.push_str(&format!(" ({})", year));
+        }
+        if let Some(arxiv_id) = &self.arxiv_id {
+            citation.push_str(&format!(" arXiv:{}", arxiv_id));
+        } else if let Some(doi) = &self.doi {
+            citation.push_str(&format!(" doi:{}", doi));
+        }
+        citation
+    }
+}
+
+/// Table of contents of a paper: its structure without the section text
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct PaperOutline {
+    pub sections: Vec<SectionOutline>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub captions: Vec<Caption>,
+
+    /// Number of entries in the reference list
+    pub reference_count: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct SectionOutline {
+    pub heading: String,
+    pub level: u8,
+    pub kind: SectionKind,
+    /// Length of the section text in characters
+    pub chars: usize,
+}
+
+impl PaperStructure {
+    pub fn outline(&self) -> PaperOutline {
+        PaperOutline {
+            sections: self
+                .sections
+                .iter()
+                .map(|s| SectionOutline {
+                    heading: s.heading.clone(),
+                    level: s.level,
+                    kind: s.kind,
+                    chars: s.text.chars().count(),
+                })
+                .collect(),
+            captions: self.captions.clone(),
+            reference_count: self.references.len(),
+        }
+    }
 }
 
 /// Represents a paper search result with limited metadata
@@ -49,7 +230,30 @@ pub struct PaperSummary {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub arxiv_id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub ss_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub categories: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub published_date: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub citation_count: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub pdf_url: Option<String>,
 }
+
+impl From<PaperSummary> for Paper {
+    fn from(summary: PaperSummary) -> Self {
+        Paper {
+            title: summary.title,
+            authors: summary.authors,
+            abstract_text: summary.abstract_text,
+            arxiv_id: summary.arxiv_id,
+            ss_id: summary.ss_id,
+            categories: summary.categories,
+            published_date: summary.published_date,
+            pdf_url: summary.pdf_url,
+            content: None,
+            structure: None,
+        }
+    }
+}
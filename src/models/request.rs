@@ -1,4 +1,7 @@
 use super::{analysis::AnalysisType, llm_config::LlmConfig, paper::Paper};
+use crate::sources::pdf::PdfSource;
+use crate::sources::semantic_scholar::RecommendationPool;
+use rmcp::model::ProgressToken;
 use schemars::JsonSchema;
 use serde::Deserialize;
 
@@ -11,35 +14,96 @@ pub struct InterpretPaperRequest {
     #[schemars(description = "LLM configuration (optional, uses env defaults)")]
     #[serde(default)]
     pub llm_config: Option<LlmConfig>,
+
+    #[schemars(description = "Bypass the paper and analysis caches (default: false)")]
+    #[serde(default)]
+    pub force_refresh: bool,
+
+    #[serde(flatten)]
+    pub progress: ProgressOptions,
 }
 
-/// Query parameters to identify a paper
-#[derive(Debug, Clone, Deserialize, JsonSchema)]
-pub struct PaperQuery {
-    #[schemars(description = "Paper title for search")]
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub title: Option<String>,
+/// Progress reporting options shared by the long-running tools
+#[derive(Debug, Clone, Default, Deserialize, JsonSchema)]
+pub struct ProgressOptions {
+    #[schemars(
+        description = "Token for MCP progress notifications sent while the call runs (string or integer); normally sent as _meta.progressToken instead",
+        with = "Option<serde_json::Value>"
+    )]
+    #[serde(default)]
+    pub progress_token: Option<ProgressToken>,
+}
 
-    #[schemars(description = "Paper URL")]
-    #[serde(skip_serializing_if = "Option::is_none")]
+/// Fields that locate a single paper, shared by the tools that read one
+#[derive(Debug, Clone, Default, Deserialize, JsonSchema)]
+pub struct PaperLocator {
+    #[schemars(description = "arXiv ID (e.g., 2301.00001)")]
+    #[serde(default)]
+    pub arxiv_id: Option<String>,
+
+    #[schemars(
+        description = "Paper URL or identifier (if no arxiv_id): arXiv, Semantic Scholar, DOI (doi.org URL or bare 10.xxxx/...), OpenReview forum, ACL Anthology, PubMed/PMC, or bioRxiv/medRxiv"
+    )]
+    #[serde(default)]
     pub url: Option<String>,
 
-    #[schemars(description = "PDF URL")]
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        description = "Direct PDF URL, e.g., a camera-ready paper on a conference site (arXiv PDF links are fetched as arXiv papers)"
+    )]
+    #[serde(default)]
     pub pdf_url: Option<String>,
 
-    #[schemars(description = "arXiv ID (e.g., 2301.00001)")]
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub arxiv_id: Option<String>,
+    #[schemars(
+        description = "Path to a PDF file under the server's PDF_ROOT_DIR (relative paths start there; disabled if unset)"
+    )]
+    #[serde(default)]
+    pub pdf_path: Option<String>,
 }
 
-impl PaperQuery {
+impl PaperLocator {
     /// Check if at least one identifier is provided
     pub fn has_identifier(&self) -> bool {
-        self.title.is_some()
+        self.arxiv_id.is_some()
             || self.url.is_some()
             || self.pdf_url.is_some()
-            || self.arxiv_id.is_some()
+            || self.pdf_path.is_some()
+    }
+
+    /// The PDF to read when no catalog identifier applies; a local path wins over a URL
+    pub fn pdf_source(&self) -> Option<PdfSource> {
+        self.pdf_path
+            .clone()
+            .map(PdfSource::Path)
+            .or_else(|| self.pdf_url.clone().map(PdfSource::Url))
+    }
+
+    /// The fetch_paper request for this paper's full text
+    pub fn fetch_request(&self, force_refresh: bool) -> FetchPaperRequest {
+        FetchPaperRequest {
+            locator: self.clone(),
+            include_pdf_content: true,
+            content_mode: Some(ContentMode::Full),
+            force_refresh,
+            progress: ProgressOptions::default(),
+        }
+    }
+}
+
+/// Query parameters to identify a paper
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+pub struct PaperQuery {
+    #[schemars(description = "Paper title for search")]
+    #[serde(default)]
+    pub title: Option<String>,
+
+    #[serde(flatten)]
+    pub locator: PaperLocator,
+}
+
+impl PaperQuery {
+    /// Check if at least one identifier is provided
+    pub fn has_identifier(&self) -> bool {
+        self.title.is_some() || self.locator.has_identifier()
     }
 }
 
@@ -57,6 +121,24 @@ pub struct SearchPapersRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub category: Option<String>,
 
+    #[schemars(description = "Only papers published on or after this date (YYYY-MM-DD)")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub published_after: Option<String>,
+
+    #[schemars(description = "Only papers published on or before this date (YYYY-MM-DD)")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub published_before: Option<String>,
+
+    #[schemars(description = "Sort order: relevance (default), date, or citations")]
+    #[serde(default)]
+    pub sort_by: SortOrder,
+
+    #[schemars(
+        description = "Search backend: arxiv or semantic_scholar (default: semantic_scholar when sorting by citations, otherwise arxiv)"
+    )]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<SearchSource>,
+
     #[schemars(description = "Maximum number of results")]
     #[serde(default = "default_max_results")]
     pub max_results: u32,
@@ -66,20 +148,46 @@ fn default_max_results() -> u32 {
     10
 }
 
+/// Sort order for search results
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    #[default]
+    Relevance,
+    Date,
+    Citations,
+}
+
+/// Backend used by search_papers
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchSource {
+    Arxiv,
+    SemanticScholar,
+}
+
 /// Request for fetch_paper tool
 #[derive(Debug, Clone, Deserialize, JsonSchema)]
 pub struct FetchPaperRequest {
-    #[schemars(description = "arXiv ID")]
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub arxiv_id: Option<String>,
-
-    #[schemars(description = "Paper URL (if no arxiv_id)")]
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub url: Option<String>,
+    #[serde(flatten)]
+    pub locator: PaperLocator,
 
-    #[schemars(description = "Include PDF content in response")]
+    #[schemars(description = "Include PDF content in response (false skips PDF download)")]
     #[serde(default = "default_include_pdf")]
     pub include_pdf_content: bool,
+
+    #[schemars(
+        description = "How much content to return: none, abstract_only, full, {\"truncated\": {\"max_chars\": N}}, or {\"sections\": [\"Introduction\", ...]} (default: full, or abstract_only when include_pdf_content is false)"
+    )]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_mode: Option<ContentMode>,
+
+    #[schemars(description = "Bypass the local paper cache and fetch again (default: false)")]
+    #[serde(default)]
+    pub force_refresh: bool,
+
+    #[serde(flatten)]
+    pub progress: ProgressOptions,
 }
 
 fn default_include_pdf() -> bool {
@@ -87,12 +195,401 @@ fn default_include_pdf() -> bool {
 }
 
 impl FetchPaperRequest {
-    /// Check if at least one identifier is provided
-    pub fn has_identifier(&self) -> bool {
-        self.arxiv_id.is_some() || self.url.is_some()
+    /// Resolve the content mode from `content_mode` and `include_pdf_content`
+    pub fn effective_content_mode(&self) -> Result<ContentMode, String> {
+        match (&self.content_mode, self.include_pdf_content) {
+            (None, true) => Ok(ContentMode::Full),
+            (None, false) => Ok(ContentMode::AbstractOnly),
+            (Some(mode), false) if mode.needs_pdf() => Err(format!(
+                "content_mode {:?} requires PDF content, but include_pdf_content is false",
+                mode
+            )),
+            (Some(mode), _) => Ok(mode.clone()),
+        }
+    }
+}
+
+/// How much paper content fetch_paper returns
+#[derive(Debug, Clone, PartialEq, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ContentMode {
+    /// Bibliographic metadata only (no abstract, no body)
+    None,
+    /// Metadata and abstract, no PDF download
+    AbstractOnly,
+    /// Full extracted text
+    Full,
+    /// Full extracted text cut to at most `max_chars` characters
+    Truncated { max_chars: usize },
+    /// Only the sections whose headings match one of the given names
+    Sections(Vec<String>),
+}
+
+impl ContentMode {
+    /// Whether this mode needs the PDF to be downloaded and extracted
+    pub fn needs_pdf(&self) -> bool {
+        !matches!(self, ContentMode::None | ContentMode::AbstractOnly)
+    }
+}
+
+/// Request for get_paper_section tool
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+pub struct GetPaperSectionRequest {
+    #[serde(flatten)]
+    pub locator: PaperLocator,
+
+    #[schemars(
+        description = "Section kind (abstract, introduction, related_work, method, experiments, results, discussion, conclusion, acknowledgments, references, appendix) or text in its heading, e.g., \"Encoder\""
+    )]
+    pub section: String,
+
+    #[schemars(description = "Include the section's subsections (default: true)")]
+    #[serde(default = "default_include_subsections")]
+    pub include_subsections: bool,
+
+    #[schemars(description = "Bypass the local paper cache and fetch again (default: false)")]
+    #[serde(default)]
+    pub force_refresh: bool,
+
+    #[serde(flatten)]
+    pub progress: ProgressOptions,
+}
+
+fn default_include_subsections() -> bool {
+    true
+}
+
+/// Request for ask_paper tool
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+pub struct AskPaperRequest {
+    #[serde(flatten)]
+    pub locator: PaperLocator,
+
+    #[schemars(description = "Question about the paper")]
+    pub question: String,
+
+    #[schemars(
+        description = "Number of passages retrieved and given to the LLM (default: 6, max: 20)"
+    )]
+    #[serde(default = "default_passages")]
+    pub passages: usize,
+
+    #[schemars(description = "LLM configuration")]
+    #[serde(default)]
+    pub llm_config: Option<LlmConfig>,
+
+    #[schemars(description = "Bypass the local paper cache and fetch again (default: false)")]
+    #[serde(default)]
+    pub force_refresh: bool,
+
+    #[serde(flatten)]
+    pub progress: ProgressOptions,
+}
+
+fn default_passages() -> usize {
+    6
+}
+
+/// Request for compare_papers tool
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+pub struct ComparePapersRequest {
+    #[schemars(
+        description = "2 to 10 papers to compare: arXiv IDs, or URLs and identifiers as accepted by fetch_paper's url (PDF links are read directly)"
+    )]
+    pub papers: Vec<String>,
+
+    #[schemars(description = "LLM configuration")]
+    #[serde(default)]
+    pub llm_config: Option<LlmConfig>,
+
+    #[schemars(description = "Bypass the local paper cache and fetch again (default: false)")]
+    #[serde(default)]
+    pub force_refresh: bool,
+
+    #[serde(flatten)]
+    pub progress: ProgressOptions,
+}
+
+impl ComparePapersRequest {
+    /// A fetch_paper request for each paper; links no catalog knows are read as PDFs
+    pub fn fetch_requests(&self) -> Vec<FetchPaperRequest> {
+        self.papers
+            .iter()
+            .map(|paper| {
+                let paper = paper.trim();
+                let is_link = paper.starts_with("http://") || paper.starts_with("https://");
+                PaperLocator {
+                    url: Some(paper.to_string()),
+                    pdf_url: is_link.then(|| paper.to_string()),
+                    ..Default::default()
+                }
+                .fetch_request(self.force_refresh)
+            })
+            .collect()
+    }
+}
+
+/// Request for literature_review tool
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+pub struct LiteratureReviewRequest {
+    #[schemars(description = "Topic to review, used as the search query")]
+    pub query: String,
+
+    #[schemars(description = "Number of papers analyzed and reviewed (default: 8, max: 20)")]
+    #[serde(default = "default_review_papers")]
+    pub max_papers: usize,
+
+    #[schemars(description = "arXiv category filter (e.g., cs.CL)")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+
+    #[schemars(description = "Only papers published on or after this date (YYYY-MM-DD)")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub published_after: Option<String>,
+
+    #[schemars(description = "Only papers published on or before this date (YYYY-MM-DD)")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub published_before: Option<String>,
+
+    #[schemars(description = "Search backend: semantic_scholar (default) or arxiv")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<SearchSource>,
+
+    #[schemars(
+        description = "Analyze each paper's full text instead of its abstract; slower (default: false)"
+    )]
+    #[serde(default)]
+    pub full_text: bool,
+
+    #[schemars(description = "LLM configuration")]
+    #[serde(default)]
+    pub llm_config: Option<LlmConfig>,
+
+    #[serde(flatten)]
+    pub progress: ProgressOptions,
+}
+
+fn default_review_papers() -> usize {
+    8
+}
+
+impl LiteratureReviewRequest {
+    /// The search_papers request for `candidates` results on the topic
+    pub fn search_request(&self, candidates: u32) -> SearchPapersRequest {
+        SearchPapersRequest {
+            query: self.query.clone(),
+            author: None,
+            category: self.category.clone(),
+            published_after: self.published_after.clone(),
+            published_before: self.published_before.clone(),
+            sort_by: SortOrder::Relevance,
+            source: Some(self.source.unwrap_or(SearchSource::SemanticScholar)),
+            max_results: candidates,
+        }
     }
 }
 
+/// Request for get_references tool
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+pub struct GetReferencesRequest {
+    #[serde(flatten)]
+    pub locator: PaperLocator,
+
+    #[schemars(description = "Semantic Scholar paper ID (semantic_scholar source only)")]
+    #[serde(default)]
+    pub ss_id: Option<String>,
+
+    #[schemars(description = "Index of the first reference to return (default: 0)")]
+    #[serde(default)]
+    pub offset: usize,
+
+    #[schemars(description = "Maximum number of references to return (default: 20, max: 100)")]
+    #[serde(default = "default_page_size")]
+    pub limit: usize,
+
+    #[schemars(
+        description = "bibliography (default): parse the reference list of the paper's PDF; semantic_scholar: the cited papers in the Semantic Scholar citation graph, with sort, influential_only, and depth"
+    )]
+    #[serde(default)]
+    pub source: ReferenceSource,
+
+    #[schemars(
+        description = "Match references to Semantic Scholar papers, adding their IDs and citation counts (bibliography source; default: true)"
+    )]
+    #[serde(default = "default_resolve")]
+    pub resolve: bool,
+
+    #[schemars(
+        description = "Sort order: relevance (default, Semantic Scholar's order), date, or citations"
+    )]
+    #[serde(default)]
+    pub sort: SortOrder,
+
+    #[schemars(
+        description = "Only citations Semantic Scholar classifies as influential (default: false)"
+    )]
+    #[serde(default)]
+    pub influential_only: bool,
+
+    #[schemars(
+        description = "1 (default) lists the cited papers; 2 also returns a graph of what the first papers in turn cite"
+    )]
+    #[serde(default = "default_depth")]
+    pub depth: u8,
+
+    #[schemars(
+        description = "At depth 2, the number of papers expanded and of neighbors added for each (default: 5, max: 20)"
+    )]
+    #[serde(default = "default_expand_limit")]
+    pub expand_limit: usize,
+
+    #[schemars(description = "Bypass the local paper cache and fetch again (default: false)")]
+    #[serde(default)]
+    pub force_refresh: bool,
+
+    #[serde(flatten)]
+    pub progress: ProgressOptions,
+}
+
+fn default_page_size() -> usize {
+    20
+}
+
+fn default_resolve() -> bool {
+    true
+}
+
+/// Where get_references reads a paper's references from
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReferenceSource {
+    /// The reference list printed in the paper
+    #[default]
+    Bibliography,
+    /// Semantic Scholar's citation graph
+    SemanticScholar,
+}
+
+impl GetReferencesRequest {
+    /// The same lookup as a citation graph request, for the semantic_scholar source
+    pub fn graph_request(&self) -> CitationGraphRequest {
+        CitationGraphRequest {
+            arxiv_id: self.locator.arxiv_id.clone(),
+            ss_id: self.ss_id.clone(),
+            url: self
+                .locator
+                .url
+                .clone()
+                .or_else(|| self.locator.pdf_url.clone()),
+            offset: self.offset,
+            limit: self.limit,
+            sort: self.sort,
+            influential_only: self.influential_only,
+            depth: self.depth,
+            expand_limit: self.expand_limit,
+            progress: ProgressOptions::default(),
+        }
+    }
+}
+
+/// Request for get_citations tool
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+pub struct CitationGraphRequest {
+    #[schemars(description = "arXiv ID")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub arxiv_id: Option<String>,
+
+    #[schemars(description = "Semantic Scholar paper ID")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ss_id: Option<String>,
+
+    #[schemars(
+        description = "Paper URL or identifier: arXiv, Semantic Scholar, DOI, ACL Anthology, PubMed/PMC, or bioRxiv/medRxiv"
+    )]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+
+    #[schemars(description = "Index of the first paper to return (default: 0)")]
+    #[serde(default)]
+    pub offset: usize,
+
+    #[schemars(description = "Maximum number of papers to return (default: 20, max: 100)")]
+    #[serde(default = "default_page_size")]
+    pub limit: usize,
+
+    #[schemars(
+        description = "Sort order: relevance (default, Semantic Scholar's order), date, or citations"
+    )]
+    #[serde(default)]
+    pub sort: SortOrder,
+
+    #[schemars(
+        description = "Only citations Semantic Scholar classifies as influential (default: false)"
+    )]
+    #[serde(default)]
+    pub influential_only: bool,
+
+    #[schemars(
+        description = "1 (default) lists the neighbors; 2 also returns a graph of the first neighbors' own neighbors"
+    )]
+    #[serde(default = "default_depth")]
+    pub depth: u8,
+
+    #[schemars(
+        description = "At depth 2, the number of papers expanded and of neighbors added for each (default: 5, max: 20)"
+    )]
+    #[serde(default = "default_expand_limit")]
+    pub expand_limit: usize,
+
+    #[serde(flatten)]
+    pub progress: ProgressOptions,
+}
+
+fn default_depth() -> u8 {
+    1
+}
+
+fn default_expand_limit() -> usize {
+    5
+}
+
+/// Request for recommend_similar tool
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+pub struct RecommendSimilarRequest {
+    #[schemars(description = "arXiv ID")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub arxiv_id: Option<String>,
+
+    #[schemars(description = "Semantic Scholar paper ID")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ss_id: Option<String>,
+
+    #[schemars(description = "Paper URL or identifier, as for get_citations")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+
+    #[schemars(description = "Index of the first paper to return (default: 0)")]
+    #[serde(default)]
+    pub offset: usize,
+
+    #[schemars(description = "Maximum number of papers to return (default: 20, max: 100)")]
+    #[serde(default = "default_page_size")]
+    pub limit: usize,
+
+    #[schemars(
+        description = "Sort order: relevance (default, best match first), date, or citations"
+    )]
+    #[serde(default)]
+    pub sort: SortOrder,
+
+    #[schemars(description = "Candidate pool: recent (default) or all_cs")]
+    #[serde(default)]
+    pub pool: RecommendationPool,
+
+    #[serde(flatten)]
+    pub progress: ProgressOptions,
+}
+
 /// Request for analyze_paper tool
 #[derive(Debug, Clone, Deserialize, JsonSchema)]
 pub struct AnalyzePaperRequest {
@@ -106,4 +603,81 @@ pub struct AnalyzePaperRequest {
     #[schemars(description = "Type of analysis")]
     #[serde(default)]
     pub analysis_type: AnalysisType,
+
+    #[schemars(description = "Papers to compare against (required for comparison analysis)")]
+    #[serde(default)]
+    pub compare_with: Vec<Paper>,
+
+    #[schemars(
+        description = "Re-run the LLM even if a stored analysis exists for the same paper, model, and prompts (default: false)"
+    )]
+    #[serde(default)]
+    pub force_refresh: bool,
+
+    #[serde(flatten)]
+    pub progress: ProgressOptions,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rmcp::model::NumberOrString;
+    use serde_json::json;
+
+    #[test]
+    fn test_progress_token() {
+        let request: FetchPaperRequest =
+            serde_json::from_value(json!({ "arxiv_id": "1706.03762", "progress_token": 7 }))
+                .unwrap();
+        assert_eq!(
+            request.progress.progress_token,
+            Some(NumberOrString::Number(7))
+        );
+
+        let request: FetchPaperRequest =
+            serde_json::from_value(json!({ "arxiv_id": "1706.03762", "progress_token": "abc" }))
+                .unwrap();
+        assert_eq!(
+            request.progress.progress_token,
+            Some(NumberOrString::String("abc".into()))
+        );
+
+        let request: FetchPaperRequest =
+            serde_json::from_value(json!({ "arxiv_id": "1706.03762" })).unwrap();
+        assert!(request.progress.progress_token.is_none());
+    }
+
+    #[test]
+    fn test_paper_locator() {
+        let request: AskPaperRequest = serde_json::from_value(json!({
+            "pdf_url": "https://example.org/paper.pdf",
+            "question": "What is the main result?",
+            "force_refresh": true,
+            "progress_token": "p1"
+        }))
+        .unwrap();
+        assert_eq!(
+            request.locator.pdf_url.as_deref(),
+            Some("https://example.org/paper.pdf")
+        );
+        assert_eq!(
+            request.progress.progress_token,
+            Some(NumberOrString::String("p1".into()))
+        );
+
+        let fetch = request.locator.fetch_request(request.force_refresh);
+        assert!(fetch.force_refresh);
+        assert_eq!(fetch.effective_content_mode(), Ok(ContentMode::Full));
+        assert!(fetch.progress.progress_token.is_none());
+
+        // Flattened fields still show up in the tool's input schema
+        let schema = serde_json::to_value(schemars::schema_for!(GetPaperSectionRequest)).unwrap();
+        for field in ["arxiv_id", "url", "pdf_url", "pdf_path", "progress_token"] {
+            assert!(
+                schema["properties"].get(field).is_some(),
+                "missing {}",
+                field
+            );
+        }
+    }
 }
@@ -1,6 +1,6 @@
 use super::{
-    analysis::PaperAnalysis,
-    paper::{Paper, PaperSummary},
+    analysis::{ComparisonMatrix, LiteratureReview, PaperAnalysis, PaperAnswer},
+    paper::{Caption, Paper, PaperOutline, PaperSection, PaperSummary, Reference},
 };
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -10,6 +10,14 @@ use serde::{Deserialize, Serialize};
 pub struct InterpretPaperResponse {
     pub paper: Paper,
     pub analysis: PaperAnalysis,
+
+    /// Whether the analysis was served from the analysis cache
+    #[serde(default)]
+    pub cached: bool,
+
+    /// When the analysis was produced (RFC 3339)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub analyzed_at: Option<String>,
 }
 
 /// Response for search_papers tool
@@ -23,12 +31,168 @@ pub struct SearchPapersResponse {
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct FetchPaperResponse {
     pub paper: Paper,
+
+    /// Length in characters of the full extracted text, when content was cut down
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total_content_chars: Option<usize>,
+
+    /// Sections, captions, and reference count of the full text; fetch a
+    /// section's text with get_paper_section
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub outline: Option<PaperOutline>,
+}
+
+/// Response for get_paper_section tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GetPaperSectionResponse {
+    pub title: String,
+    pub sections: Vec<PaperSection>,
+
+    /// Captions of the figures and tables the returned text refers to
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub captions: Vec<Caption>,
+}
+
+/// Response for ask_paper tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct AskPaperResponse {
+    pub title: String,
+    pub question: String,
+
+    #[serde(flatten)]
+    pub answer: PaperAnswer,
+}
+
+/// Response for compare_papers tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ComparePapersResponse {
+    pub comparison: ComparisonMatrix,
+}
+
+/// Response for literature_review tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct LiteratureReviewResponse {
+    pub review: LiteratureReview,
+
+    /// Number of search results the reviewed papers were ranked from
+    pub candidates: usize,
+}
+
+/// Response for get_references tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GetReferencesResponse {
+    pub title: String,
+
+    /// Number of entries in the paper's reference list
+    pub total: usize,
+
+    pub offset: usize,
+    pub references: Vec<Reference>,
+
+    /// Offset of the next page, if there are more references
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_offset: Option<usize>,
+
+    /// Whether the references were matched against Semantic Scholar
+    pub resolved: bool,
+}
+
+/// Response for get_citations, and for get_references from Semantic Scholar
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CitationGraphResponse {
+    /// Semantic Scholar ID the paper was looked up by
+    pub paper_id: String,
+
+    /// `citations` (papers citing it) or `references` (papers it cites)
+    pub direction: String,
+
+    pub offset: usize,
+    pub papers: Vec<NeighborPaper>,
+
+    /// Number of matching papers, known when they were filtered or sorted here
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total: Option<usize>,
+
+    /// Offset of the next page, if there are more papers
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_offset: Option<usize>,
+
+    /// The paper, its neighbors, and their neighbors (depth 2 only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub graph: Option<CitationGraph>,
+}
+
+/// A citing or cited paper
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct NeighborPaper {
+    #[serde(flatten)]
+    pub paper: PaperSummary,
+
+    /// Whether Semantic Scholar classifies the citation as influential
+    pub is_influential: bool,
+}
+
+/// A small citation graph; edges point from the citing to the cited paper
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct CitationGraph {
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct GraphNode {
+    /// Semantic Scholar paper ID
+    pub id: String,
+    pub title: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub arxiv_id: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub year: Option<u16>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub citation_count: Option<u32>,
+
+    /// Steps from the requested paper (0 for the paper itself)
+    pub depth: u8,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct GraphEdge {
+    /// ID of the citing paper
+    pub from: String,
+    /// ID of the cited paper
+    pub to: String,
+    pub influential: bool,
+}
+
+/// Response for recommend_similar tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RecommendSimilarResponse {
+    /// Semantic Scholar ID the paper was looked up by
+    pub paper_id: String,
+    pub total: usize,
+    pub offset: usize,
+    pub papers: Vec<PaperSummary>,
+
+    /// Offset of the next page, if there are more papers
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_offset: Option<usize>,
 }
 
 /// Response for analyze_paper tool
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct AnalyzePaperResponse {
     pub analysis: PaperAnalysis,
+
+    /// Whether the analysis was served from the analysis cache
+    #[serde(default)]
+    pub cached: bool,
+
+    /// When the analysis was produced (RFC 3339)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub analyzed_at: Option<String>,
 }
 
 /// MCP error response structure
@@ -37,10 +201,16 @@ pub struct McpErrorResponse {
     pub error: McpErrorDetail,
 }
 
+/// Error details; also sent as the `data` of MCP errors returned by tools
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct McpErrorDetail {
     pub code: String,
     pub message: String,
+    /// Whether retrying the same request later may succeed
+    #[serde(default)]
+    pub retryable: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_after_secs: Option<u64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub details: Option<serde_json::Value>,
 }
@@ -0,0 +1,71 @@
+/// Characters per token assumed when estimating prompt sizes
+///
+/// English prose runs close to four characters per token with common BPE
+/// tokenizers; equations and non-Latin text run lower, so budgets derived
+/// from this leave some headroom.
+pub const CHARS_PER_TOKEN: usize = 4;
+
+/// Estimated number of tokens in `text`
+pub fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(CHARS_PER_TOKEN)
+}
+
+/// Split text into chunks of at most `max_tokens` estimated tokens
+///
+/// Chunks break at a paragraph boundary where one falls in the second half of
+/// the window, else at a line break, else between words.
+pub fn chunk_text(text: &str, max_tokens: usize) -> Vec<&str> {
+    let max_chars = (max_tokens * CHARS_PER_TOKEN).max(1);
+    let mut chunks = Vec::new();
+    let mut rest = text;
+    loop {
+        let Some((limit, _)) = rest.char_indices().nth(max_chars) else {
+            if !rest.trim().is_empty() {
+                chunks.push(rest);
+            }
+            return chunks;
+        };
+        let window = &rest[..limit];
+        let half = window.len() / 2;
+        let end = ["\n\n", "\n", " "]
+            .iter()
+            .find_map(|boundary| {
+                window
+                    .rfind(boundary)
+                    .filter(|&i| i >= half)
+                    .map(|i| i + boundary.len())
+            })
+            .unwrap_or(limit);
+        if !rest[..end].trim().is_empty() {
+            chunks.push(&rest[..end]);
+        }
+        rest = &rest[end..];
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chunk_text() {
+        let paragraph = "word ".repeat(30);
+        let text = format!("{}\n\n{}\n\n{}", paragraph, paragraph, paragraph);
+
+        // 50 tokens is 200 characters: one 150-character paragraph per chunk
+        let chunks = chunk_text(&text, 50);
+        assert_eq!(chunks.len(), 3);
+        assert!(chunks.iter().all(|c| estimate_tokens(c) <= 50));
+        assert_eq!(chunks.concat(), text);
+
+        // Text without breaks is cut at the limit
+        let unbroken = "x".repeat(250);
+        let chunks = chunk_text(&unbroken, 50);
+        assert_eq!(
+            chunks.iter().map(|c| c.len()).collect::<Vec<_>>(),
+            vec![200, 50]
+        );
+
+        assert_eq!(chunk_text("short", 50), vec!["short"]);
+    }
+}
@@ -0,0 +1,241 @@
+use super::chunking::CHARS_PER_TOKEN;
+use super::truncate_chars;
+use crate::llm::ChatClient;
+use crate::models::analysis::{ComparisonAspect, ComparisonMatrix, ComparisonRow, PaperComparison};
+use crate::models::paper::Paper;
+use futures::future::try_join_all;
+use serde::Deserialize;
+use shared::errors::{AppError, AppResult};
+
+/// Characters of body text included per paper, on top of the abstract
+const MAX_EXCERPT_CHARS: usize = 6_000;
+
+/// Characters of body text read when filling in a paper's comparison row
+const MAX_ROW_CONTENT_CHARS: usize = 40_000;
+
+/// Bump when the comparison prompt changes so stored comparisons are recomputed
+pub const PROMPT_VERSION: u32 = 1;
+
+const COMPARISON_SYSTEM_PROMPT: &str = "You are an expert reviewer of academic papers. \
+    You compare papers objectively and answer only with a JSON object.";
+
+#[derive(Debug, Deserialize)]
+struct ComparisonReply {
+    summary: String,
+    #[serde(default)]
+    similarities: Vec<String>,
+    #[serde(default)]
+    differences: Vec<String>,
+    #[serde(default)]
+    aspects: Vec<ComparisonAspect>,
+}
+
+/// Compare several papers and return a structured comparison
+pub async fn compare_papers(chat: &ChatClient, papers: &[Paper]) -> AppResult<PaperComparison> {
+    if papers.len() < 2 {
+        return Err(AppError::InvalidRequest(
+            "Comparison analysis requires at least one paper in compare_with".to_string(),
+        ));
+    }
+
+    tracing::info!("Comparing {} papers", papers.len());
+
+    // Excerpts share the model's input budget, less room for the abstracts
+    let excerpt_chars =
+        (chat.input_budget() * CHARS_PER_TOKEN / 2 / papers.len()).min(MAX_EXCERPT_CHARS);
+    let mut prompt = String::new();
+    for (i, paper) in papers.iter().enumerate() {
+        prompt.push_str(&format!(
+            "[Paper {}] {}\nAuthors: {}\nAbstract: {}\n",
+            i + 1,
+            paper.title,
+            paper.authors.join(", "),
+            paper.abstract_text
+        ));
+        if let Some(ref content) = paper.content {
+            prompt.push_str(&format!(
+                "Excerpt:\n{}\n",
+                truncate_chars(content, excerpt_chars)
+            ));
+        }
+        prompt.push('\n');
+    }
+    prompt.push_str(&format!(
+        "Compare these {n} papers. Respond with JSON of the form \
+         {{\"summary\": \"...\", \"similarities\": [\"...\"], \"differences\": [\"...\"], \
+         \"aspects\": [{{\"aspect\": \"...\", \"values\": [\"...\"]}}]}}. \
+         Include aspects for the problem addressed, method, datasets, evaluation metrics, \
+         and main results. Each `values` array must have exactly {n} entries, one per paper \
+         in the order given.",
+        n = papers.len()
+    ));
+
+    let reply: ComparisonReply = chat
+        .complete_json(COMPARISON_SYSTEM_PROMPT, &prompt)
+        .await?;
+
+    Ok(PaperComparison {
+        papers: papers.iter().map(|p| p.title.clone()).collect(),
+        summary: reply.summary,
+        similarities: reply.similarities,
+        differences: reply.differences,
+        aspects: reply.aspects,
+    })
+}
+
+#[derive(Debug, Deserialize)]
+struct RowReply {
+    #[serde(default)]
+    task: String,
+    #[serde(default)]
+    method: String,
+    #[serde(default)]
+    datasets: Vec<String>,
+    #[serde(default)]
+    metrics: Vec<String>,
+    #[serde(default)]
+    reported_results: Vec<String>,
+    #[serde(default)]
+    assumptions: Vec<String>,
+    #[serde(default)]
+    limitations: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct NarrativeReply {
+    narrative: String,
+    #[serde(default)]
+    agreements: Vec<String>,
+    #[serde(default)]
+    disagreements: Vec<String>,
+}
+
+/// Compare papers in a matrix of task, method, datasets, metrics, results,
+/// assumptions, and limitations
+///
+/// Each paper's row is extracted from its own text in a separate, concurrent
+/// call, so papers are not cut down to fit one prompt; a final call compares
+/// the rows.
+pub async fn comparison_matrix(chat: &ChatClient, papers: &[Paper]) -> AppResult<ComparisonMatrix> {
+    if papers.len() < 2 {
+        return Err(AppError::InvalidRequest(
+            "A comparison needs at least two papers".to_string(),
+        ));
+    }
+
+    chat.progress()
+        .stage(&format!(
+            "Extracting comparison rows for {} papers",
+            papers.len()
+        ))
+        .await;
+    let rows = try_join_all(papers.iter().map(|paper| comparison_row(chat, paper))).await?;
+
+    chat.progress().stage("Comparing the papers").await;
+    let mut prompt = String::new();
+    for (i, row) in rows.iter().enumerate() {
+        prompt.push_str(&format!(
+            "[Paper {}] {}\n{}\n",
+            i + 1,
+            row.title,
+            describe_row(row)
+        ));
+    }
+    prompt.push_str(
+        "Compare these papers. Respond with JSON of the form {\"narrative\": \"...\", \
+         \"agreements\": [\"...\"], \"disagreements\": [\"...\"]}. The narrative should be \
+         one or two paragraphs on where the papers agree and disagree, covering their \
+         assumptions and any conflicting reported results. Refer to papers as [Paper N].",
+    );
+    let reply: NarrativeReply = chat
+        .complete_json(COMPARISON_SYSTEM_PROMPT, &prompt)
+        .await?;
+
+    Ok(ComparisonMatrix {
+        rows,
+        narrative: reply.narrative,
+        agreements: reply.agreements,
+        disagreements: reply.disagreements,
+    })
+}
+
+/// Fill in one paper's row of the comparison matrix
+async fn comparison_row(chat: &ChatClient, paper: &Paper) -> AppResult<ComparisonRow> {
+    let mut prompt = format!(
+        "Title: {}\nAuthors: {}\nAbstract: {}\n",
+        paper.title,
+        paper.authors.join(", "),
+        paper.abstract_text
+    );
+    if let Some(ref content) = paper.content {
+        let max_chars =
+            (chat.input_budget() * CHARS_PER_TOKEN).saturating_sub(prompt.chars().count());
+        prompt.push_str(&format!(
+            "\nFull text:\n{}\n",
+            truncate_chars(content, max_chars.min(MAX_ROW_CONTENT_CHARS))
+        ));
+    }
+    prompt.push_str(
+        "\nDescribe this paper for a comparison with related papers. Respond with JSON of the \
+         form {\"task\": \"...\", \"method\": \"...\", \"datasets\": [\"...\"], \"metrics\": [\"...\"], \
+         \"reported_results\": [\"...\"], \"assumptions\": [\"...\"], \"limitations\": [\"...\"]}. \
+         Give task and method in one or two sentences each. Each reported result must state \
+         the number with its metric and dataset, as reported in the paper. Use empty lists \
+         for what the paper does not state.",
+    );
+
+    let reply: RowReply = chat
+        .complete_json(COMPARISON_SYSTEM_PROMPT, &prompt)
+        .await?;
+    Ok(ComparisonRow {
+        title: paper.title.clone(),
+        task: reply.task,
+        method: reply.method,
+        datasets: reply.datasets,
+        metrics: reply.metrics,
+        reported_results: reply.reported_results,
+        assumptions: reply.assumptions,
+        limitations: reply.limitations,
+    })
+}
+
+/// A row as labeled lines for the comparison prompt
+fn describe_row(row: &ComparisonRow) -> String {
+    [
+        ("Task", vec![row.task.clone()]),
+        ("Method", vec![row.method.clone()]),
+        ("Datasets", row.datasets.clone()),
+        ("Metrics", row.metrics.clone()),
+        ("Reported results", row.reported_results.clone()),
+        ("Assumptions", row.assumptions.clone()),
+        ("Limitations", row.limitations.clone()),
+    ]
+    .into_iter()
+    .filter(|(_, values)| values.iter().any(|v| !v.trim().is_empty()))
+    .map(|(label, values)| format!("{}: {}\n", label, values.join("; ")))
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_describe_row() {
+        let row = ComparisonRow {
+            title: "Attention Is All You Need".to_string(),
+            task: "Machine translation".to_string(),
+            method: "Transformer".to_string(),
+            datasets: vec!["WMT14 En-De".to_string(), "WMT14 En-Fr".to_string()],
+            metrics: vec![],
+            reported_results: vec!["28.4 BLEU on WMT14 En-De".to_string()],
+            assumptions: vec![],
+            limitations: vec![],
+        };
+        assert_eq!(
+            describe_row(&row),
+            "Task: Machine translation\nMethod: Transformer\n\
+             Datasets: WMT14 En-De; WMT14 En-Fr\nReported results: 28.4 BLEU on WMT14 En-De\n"
+        );
+    }
+}
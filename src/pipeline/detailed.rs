@@ -0,0 +1,109 @@
+use super::chunking::CHARS_PER_TOKEN;
+use super::sections::{split_sections, TextSection};
+use super::truncate_chars;
+use crate::llm::ChatClient;
+use crate::models::analysis::SectionAnalysis;
+use crate::models::paper::Paper;
+use futures::{StreamExt, TryStreamExt};
+use serde::Deserialize;
+use shared::errors::{AppError, AppResult};
+
+/// Sections that carry no content worth analyzing
+const SKIPPED_HEADINGS: &[&str] = &[
+    "references",
+    "bibliography",
+    "acknowledgments",
+    "acknowledgements",
+];
+
+/// Upper bound on the number of sections sent to the LLM
+const MAX_SECTIONS: usize = 16;
+
+/// Number of section prompts in flight at once
+const CONCURRENCY: usize = 4;
+
+/// Part of the analysis cache key; bump when the section prompt changes
+pub const PROMPT_VERSION: u32 = 2;
+
+const SECTION_SYSTEM_PROMPT: &str = "You are an expert reviewer of academic papers. \
+    You analyze one section of a paper at a time and answer only with a JSON object.";
+
+#[derive(Debug, Deserialize)]
+struct SectionReply {
+    summary: String,
+    #[serde(default)]
+    key_points: Vec<String>,
+}
+
+/// Analyze each section of a paper's content separately
+pub async fn analyze_sections(chat: &ChatClient, paper: &Paper) -> AppResult<Vec<SectionAnalysis>> {
+    let content = paper.content.as_deref().unwrap_or_default();
+    if content.trim().is_empty() {
+        return Err(AppError::InvalidRequest(
+            "Detailed analysis requires paper content; fetch the paper with its PDF content first"
+                .to_string(),
+        ));
+    }
+
+    chat.progress()
+        .stage("Splitting the paper into sections")
+        .await;
+    let mut sections: Vec<TextSection> = split_sections(content)
+        .into_iter()
+        .filter(|s| !s.text.trim().is_empty())
+        .filter(|s| !is_skipped(&s.heading))
+        .take(MAX_SECTIONS)
+        .collect();
+
+    if sections.is_empty() {
+        sections.push(TextSection {
+            heading: "Full Text".to_string(),
+            level: 1,
+            text: content.to_string(),
+        });
+    }
+
+    tracing::info!(
+        "Running detailed analysis over {} sections of \"{}\"",
+        sections.len(),
+        paper.title
+    );
+
+    futures::stream::iter(sections)
+        .map(|section| analyze_section(chat, &paper.title, section))
+        .buffered(CONCURRENCY)
+        .try_collect()
+        .await
+}
+
+async fn analyze_section(
+    chat: &ChatClient,
+    title: &str,
+    section: TextSection,
+) -> AppResult<SectionAnalysis> {
+    // A section may fill the model's input budget, less its heading and title
+    let max_chars = (chat.input_budget() * CHARS_PER_TOKEN)
+        .saturating_sub(title.chars().count() + section.heading.chars().count());
+    let text = truncate_chars(&section.text, max_chars);
+    let prompt = format!(
+        "Paper: {title}\nSection: {heading}\n\n{text}\n\n\
+         Analyze this section in depth. Respond with JSON of the form \
+         {{\"summary\": \"...\", \"key_points\": [\"...\"]}}. The summary should explain \
+         what the section says and why it matters; key points should list the concrete \
+         claims, design decisions, equations, or results it contains.",
+        heading = section.heading,
+    );
+
+    let reply: SectionReply = chat.complete_json(SECTION_SYSTEM_PROMPT, &prompt).await?;
+
+    Ok(SectionAnalysis {
+        heading: section.heading,
+        summary: reply.summary,
+        key_points: reply.key_points,
+    })
+}
+
+fn is_skipped(heading: &str) -> bool {
+    let lower = heading.to_lowercase();
+    SKIPPED_HEADINGS.iter().any(|h| lower.ends_with(h))
+}
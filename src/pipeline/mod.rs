@@ -0,0 +1,37 @@
+pub mod chunking;
+pub mod comparison;
+pub mod detailed;
+pub mod qa;
+pub mod references;
+pub mod review;
+pub mod sections;
+pub mod structure;
+pub mod summary;
+
+pub use comparison::{compare_papers, comparison_matrix};
+pub use detailed::analyze_sections;
+pub use qa::answer_question;
+pub use review::{rank_candidates, write_review};
+pub use summary::summarize;
+
+use crate::models::analysis::AnalysisType;
+
+/// Versions of the prompts an analysis type runs, used in analysis cache keys
+pub fn prompt_version(analysis_type: &AnalysisType) -> String {
+    let summary = format!("summary:{}", summary::PROMPT_VERSION);
+    match analysis_type {
+        AnalysisType::Summary => summary,
+        AnalysisType::Detailed => format!("{}+detailed:{}", summary, detailed::PROMPT_VERSION),
+        AnalysisType::Comparison => {
+            format!("{}+comparison:{}", summary, comparison::PROMPT_VERSION)
+        }
+    }
+}
+
+/// Truncate to at most `max` characters without splitting a UTF-8 code point
+pub fn truncate_chars(text: &str, max: usize) -> &str {
+    match text.char_indices().nth(max) {
+        Some((idx, _)) => &text[..idx],
+        None => text,
+    }
+}
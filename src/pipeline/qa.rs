@@ -0,0 +1,359 @@
+use super::chunking::CHARS_PER_TOKEN;
+use crate::llm::ChatClient;
+use crate::models::analysis::{Evidence, PaperAnswer};
+use crate::models::paper::{Paper, SectionKind};
+use serde::Deserialize;
+use shared::errors::AppResult;
+use std::collections::{HashMap, HashSet};
+
+/// Target length of a retrieval passage, in characters
+const PASSAGE_CHARS: usize = 1_200;
+
+/// BM25 term-frequency saturation and length normalization
+const BM25_K1: f64 = 1.2;
+const BM25_B: f64 = 0.75;
+
+const QA_SYSTEM_PROMPT: &str = "You are an expert reader of academic papers. \
+    You answer questions using only the passages you are given, quote them exactly, \
+    and answer only with a JSON object.";
+
+/// Words too common to tell passages apart
+const STOPWORDS: &[&str] = &[
+    "a", "an", "and", "are", "as", "at", "be", "by", "can", "do", "does", "for", "from", "how",
+    "in", "is", "it", "its", "of", "on", "or", "paper", "that", "the", "their", "they", "this",
+    "to", "was", "we", "were", "what", "when", "which", "who", "why", "with",
+];
+
+/// A retrievable span of one section's text
+#[derive(Debug, Clone, PartialEq)]
+pub struct Passage {
+    /// Index into the sections the passage was split from
+    pub section: usize,
+    /// Character offset of the passage in the section's text
+    pub start: usize,
+    pub text: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct AnswerReply {
+    answer: String,
+    #[serde(default)]
+    evidence: Vec<QuoteReply>,
+}
+
+#[derive(Debug, Deserialize)]
+struct QuoteReply {
+    #[serde(default)]
+    passage: Option<usize>,
+    quote: String,
+}
+
+/// Answer a question from the paper passages most relevant to it
+///
+/// Quotes the LLM gives as evidence are located in the paper's text; ones
+/// that cannot be found verbatim are dropped rather than passed on.
+pub async fn answer_question(
+    chat: &ChatClient,
+    paper: &Paper,
+    question: &str,
+    max_passages: usize,
+) -> AppResult<PaperAnswer> {
+    chat.progress().stage("Retrieving passages").await;
+    let sections = searchable_sections(paper);
+    let passages = split_passages(&sections);
+    // As many passages as the model's input budget holds, up to max_passages
+    let fits = chat.input_budget() * CHARS_PER_TOKEN / PASSAGE_CHARS;
+    let retrieved = retrieve(&passages, question, max_passages.min(fits).max(1));
+
+    let mut prompt = format!("Title: {}\n\nPassages:\n", paper.title);
+    for (i, passage) in retrieved.iter().enumerate() {
+        prompt.push_str(&format!(
+            "[{}] (Section: {})\n{}\n\n",
+            i + 1,
+            sections[passage.section].0,
+            passage.text.trim()
+        ));
+    }
+    prompt.push_str(&format!(
+        "Question: {}\n\nAnswer the question using only these passages. Respond with JSON of \
+         the form {{\"answer\": \"...\", \"evidence\": [{{\"passage\": 1, \"quote\": \"...\"}}]}}. \
+         Each quote must be one or more sentences copied exactly, character for character, \
+         from the numbered passage. If the passages do not answer the question, say so in the \
+         answer and leave evidence empty.",
+        question
+    ));
+
+    let reply: AnswerReply = chat.complete_json(QA_SYSTEM_PROMPT, &prompt).await?;
+
+    let mut evidence: Vec<Evidence> = Vec::new();
+    for quote in reply.evidence {
+        let passage = quote
+            .passage
+            .and_then(|n| n.checked_sub(1))
+            .and_then(|i| retrieved.get(i).copied());
+        match locate_quote(&quote.quote, passage, &sections) {
+            Some(found) if !evidence.contains(&found) => evidence.push(found),
+            Some(_) => {}
+            None => tracing::warn!("Dropping evidence not found in the paper: {}", quote.quote),
+        }
+    }
+    Ok(PaperAnswer {
+        answer: reply.answer,
+        evidence,
+    })
+}
+
+/// Headings and text of the sections worth searching
+///
+/// The reference list is left out, since its titles match almost any
+/// question. Without full text, the abstract is the only section.
+fn searchable_sections(paper: &Paper) -> Vec<(String, String)> {
+    let sections: Vec<(String, String)> = paper
+        .structure
+        .iter()
+        .flat_map(|s| &s.sections)
+        .filter(|s| s.kind != SectionKind::References && !s.text.trim().is_empty())
+        .map(|s| (s.heading.clone(), s.text.clone()))
+        .collect();
+    if sections.is_empty() {
+        return vec![("Abstract".to_string(), paper.abstract_text.clone())];
+    }
+    sections
+}
+
+/// Split sections into passages of about `PASSAGE_CHARS`, breaking between lines
+pub fn split_passages(sections: &[(String, String)]) -> Vec<Passage> {
+    let mut passages = Vec::new();
+    for (index, (_, text)) in sections.iter().enumerate() {
+        let mut current = String::new();
+        let mut current_chars = 0;
+        let mut start = 0;
+        let mut offset = 0;
+        for piece in pieces(text) {
+            let piece_chars = piece.chars().count();
+            if current_chars > 0 && current_chars + piece_chars > PASSAGE_CHARS {
+                push_passage(&mut passages, index, start, &current);
+                current.clear();
+                current_chars = 0;
+                start = offset;
+            }
+            current.push_str(piece);
+            current_chars += piece_chars;
+            offset += piece_chars;
+        }
+        push_passage(&mut passages, index, start, &current);
+    }
+    passages
+}
+
+/// Lines, with lines longer than a passage split between words
+fn pieces(text: &str) -> impl Iterator<Item = &str> {
+    text.split_inclusive('\n').flat_map(|line| {
+        let long = line.chars().count() > PASSAGE_CHARS;
+        line.split_inclusive(move |c: char| long && c == ' ')
+    })
+}
+
+fn push_passage(passages: &mut Vec<Passage>, section: usize, start: usize, text: &str) {
+    if !text.trim().is_empty() {
+        passages.push(Passage {
+            section,
+            start,
+            text: text.to_string(),
+        });
+    }
+}
+
+/// The `max` passages scoring highest for the question under BM25, in document order
+///
+/// When no passage shares a term with the question, the first passages are used.
+pub fn retrieve<'a>(passages: &'a [Passage], question: &str, max: usize) -> Vec<&'a Passage> {
+    let query: HashSet<String> = terms(question).into_iter().collect();
+    let docs: Vec<Vec<String>> = passages.iter().map(|p| terms(&p.text)).collect();
+    let n = docs.len() as f64;
+    let avg_len = (docs.iter().map(Vec::len).sum::<usize>() as f64 / n.max(1.0)).max(1.0);
+    let doc_freq: HashMap<&str, usize> = query
+        .iter()
+        .map(|q| (q.as_str(), docs.iter().filter(|d| d.contains(q)).count()))
+        .collect();
+
+    let mut scored: Vec<(f64, usize)> = docs
+        .iter()
+        .enumerate()
+        .map(|(i, doc)| {
+            let len = doc.len() as f64;
+            let score: f64 = query
+                .iter()
+                .map(|q| {
+                    let tf = doc.iter().filter(|t| *t == q).count() as f64;
+                    let df = doc_freq[q.as_str()] as f64;
+                    let idf = ((n - df + 0.5) / (df + 0.5) + 1.0).ln();
+                    idf * tf * (BM25_K1 + 1.0)
+                        / (tf + BM25_K1 * (1.0 - BM25_B + BM25_B * len / avg_len))
+                })
+                .sum();
+            (score, i)
+        })
+        .collect();
+    scored.sort_by(|a, b| b.0.total_cmp(&a.0).then(a.1.cmp(&b.1)));
+
+    let mut top: Vec<usize> = scored
+        .iter()
+        .filter(|(score, _)| *score > 0.0)
+        .take(max)
+        .map(|(_, i)| *i)
+        .collect();
+    if top.is_empty() {
+        top = (0..passages.len().min(max)).collect();
+    }
+    top.sort_unstable();
+    top.into_iter().map(|i| &passages[i]).collect()
+}
+
+/// Lowercased words without stopwords, with plural "s" dropped
+pub(crate) fn terms(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| w.chars().count() >= 2)
+        .map(str::to_lowercase)
+        .filter(|w| !STOPWORDS.contains(&w.as_str()))
+        .map(|w| {
+            if w.len() > 3 && w.ends_with('s') && !w.ends_with("ss") {
+                w[..w.len() - 1].to_string()
+            } else {
+                w
+            }
+        })
+        .collect()
+}
+
+/// Find a quote in the passage it was attributed to, then in any section
+fn locate_quote(
+    quote: &str,
+    passage: Option<&Passage>,
+    sections: &[(String, String)],
+) -> Option<Evidence> {
+    let evidence =
+        |section: usize, text: &str, offset: usize, (start, end): (usize, usize)| Evidence {
+            quote: text.chars().skip(start).take(end - start).collect(),
+            section: sections[section].0.clone(),
+            start: offset + start,
+            end: offset + end,
+        };
+    if let Some(passage) = passage
+        && let Some(range) = find_quote(&passage.text, quote)
+    {
+        return Some(evidence(
+            passage.section,
+            &passage.text,
+            passage.start,
+            range,
+        ));
+    }
+    // Quotes can run across passage boundaries
+    sections
+        .iter()
+        .enumerate()
+        .find_map(|(i, (_, text))| find_quote(text, quote).map(|range| evidence(i, text, 0, range)))
+}
+
+/// Character range of `quote` in `text`, treating each run of whitespace as one space
+pub fn find_quote(text: &str, quote: &str) -> Option<(usize, usize)> {
+    let (quote, _) = collapse_whitespace(quote.trim());
+    if quote.is_empty() {
+        return None;
+    }
+    let (chars, index) = collapse_whitespace(text);
+    let pos = chars
+        .windows(quote.len())
+        .position(|window| window == quote.as_slice())?;
+    Some((index[pos], index[pos + quote.len() - 1] + 1))
+}
+
+/// Characters with whitespace runs collapsed to a space, and each one's original index
+fn collapse_whitespace(text: &str) -> (Vec<char>, Vec<usize>) {
+    let mut chars = Vec::new();
+    let mut index = Vec::new();
+    let mut in_space = false;
+    for (i, c) in text.chars().enumerate() {
+        if c.is_whitespace() {
+            if in_space {
+                continue;
+            }
+            in_space = true;
+            chars.push(' ');
+        } else {
+            in_space = false;
+            chars.push(c);
+        }
+        index.push(i);
+    }
+    (chars, index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sections() -> Vec<(String, String)> {
+        vec![
+            (
+                "1 Introduction".to_string(),
+                "Recurrent models are slow to train.\nWe propose the Transformer.\n".to_string(),
+            ),
+            (
+                "5 Training".to_string(),
+                "We trained on 8 NVIDIA P100 GPUs.\nThe big models were trained for\n\
+                 300,000 steps (3.5 days).\n"
+                    .to_string(),
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_retrieve_passages() {
+        let passages = split_passages(&sections());
+        assert_eq!(passages.len(), 2);
+
+        let retrieved = retrieve(&passages, "How many GPUs were the models trained on?", 1);
+        assert_eq!(retrieved.len(), 1);
+        assert_eq!(retrieved[0].section, 1);
+
+        // Long sections become several passages whose offsets point into the section
+        let long = vec![("Method".to_string(), "word ".repeat(600))];
+        let passages = split_passages(&long);
+        assert!(passages.len() > 1);
+        assert_eq!(passages[1].start, passages[0].text.chars().count());
+    }
+
+    #[test]
+    fn test_locate_quote() {
+        let sections = sections();
+        let passages = split_passages(&sections);
+
+        // The quote's line break was turned into a space
+        let found = locate_quote(
+            "The big models were trained for 300,000 steps",
+            Some(&passages[1]),
+            &sections,
+        )
+        .unwrap();
+        assert_eq!(found.section, "5 Training");
+        assert_eq!(
+            found.quote,
+            "The big models were trained for\n300,000 steps"
+        );
+        let text: String = sections[1]
+            .1
+            .chars()
+            .skip(found.start)
+            .take(found.end - found.start)
+            .collect();
+        assert_eq!(text, found.quote);
+
+        // Attributed to the wrong passage, but still in the paper
+        let found = locate_quote("We propose the Transformer.", Some(&passages[1]), &sections);
+        assert_eq!(found.unwrap().section, "1 Introduction");
+
+        assert!(locate_quote("Not in the paper.", None, &sections).is_none());
+    }
+}
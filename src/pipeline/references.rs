@@ -0,0 +1,255 @@
+use crate::models::paper::Reference;
+use crate::sources::doi::parse_doi;
+
+/// Parse a reference entry into authors, title, venue, year, and identifiers
+///
+/// Handles the common layouts:
+/// - `A. Author, B. Author, and C. Author. Title. In Venue, 2015.`
+/// - `Author, A., and Author, B. (2017). Title. Venue.`
+/// - `A. Author et al., "Title," in Venue, 2017.`
+pub fn parse_reference(raw: &str) -> Reference {
+    let raw = raw.trim();
+    let arxiv_id = find_arxiv_id(raw);
+
+    let (authors, title, venue) = if let Some((before, title, after)) = quoted_title(raw) {
+        (
+            before.to_string(),
+            Some(title.to_string()),
+            after.to_string(),
+        )
+    } else if let Some((start, end)) = parenthesized_year(raw) {
+        let rest = raw[end..].trim_start_matches(['.', ',', ' ']);
+        let mut sentences = split_sentences(rest).into_iter();
+        let title = sentences.next().map(str::to_string);
+        (
+            raw[..start].to_string(),
+            title,
+            sentences.collect::<Vec<_>>().join(". "),
+        )
+    } else {
+        let mut sentences = split_sentences(raw).into_iter();
+        let authors = sentences.next().unwrap_or_default().to_string();
+        let title = sentences.next().map(str::to_string);
+        (authors, title, sentences.collect::<Vec<_>>().join(". "))
+    };
+
+    let year = find_year(raw, arxiv_id.as_deref());
+    Reference {
+        raw: raw.to_string(),
+        authors: parse_authors(&authors),
+        title: title
+            .map(|t| t.trim_matches([',', '.', ' ']).to_string())
+            .filter(|t| !t.is_empty()),
+        venue: clean_venue(&venue, year),
+        year,
+        doi: find_doi(raw),
+        arxiv_id,
+        ..Default::default()
+    }
+}
+
+/// Split at sentence ends, keeping initials ("J. Smith") and "U.S." together
+fn split_sentences(text: &str) -> Vec<&str> {
+    let mut sentences = Vec::new();
+    let mut start = 0;
+    for (i, c) in text.char_indices() {
+        if !matches!(c, '.' | '?' | '!') || !text[i + 1..].starts_with(' ') {
+            continue;
+        }
+        if c == '.' {
+            let word = text[start..i]
+                .rsplit([' ', '-', '(', '.'])
+                .next()
+                .unwrap_or_default();
+            let is_initial = word.chars().count() == 1 && word.chars().all(char::is_uppercase);
+            if is_initial {
+                continue;
+            }
+        }
+        // Keep '?' and '!', which belong to the title
+        let end = if c == '.' { i } else { i + 1 };
+        sentences.push(text[start..end].trim());
+        start = i + 1;
+    }
+    sentences.push(text[start..].trim().trim_end_matches('.'));
+    sentences.retain(|s| !s.is_empty());
+    sentences
+}
+
+/// Text before, inside, and after a quoted title
+fn quoted_title(raw: &str) -> Option<(&str, &str, &str)> {
+    for (open, close) in [('“', '”'), ('"', '"')] {
+        if let Some(start) = raw.find(open) {
+            let inner_start = start + open.len_utf8();
+            let inner_end = inner_start + raw[inner_start..].find(close)?;
+            let after = &raw[inner_end + close.len_utf8()..];
+            return Some((&raw[..start], &raw[inner_start..inner_end], after));
+        }
+    }
+    None
+}
+
+/// Byte range of an author-year "(2017)" or "(2019a)"
+fn parenthesized_year(raw: &str) -> Option<(usize, usize)> {
+    raw.match_indices('(').find_map(|(start, _)| {
+        let close = start + raw[start..].find(')')?;
+        let inner = raw[start + 1..close].trim_end_matches(|c: char| c.is_ascii_lowercase());
+        year_value(inner).map(|_| (start, close + 1))
+    })
+}
+
+fn year_value(word: &str) -> Option<u16> {
+    let year: u16 = word.parse().ok().filter(|_| word.len() == 4)?;
+    (1900..=2099).contains(&year).then_some(year)
+}
+
+/// The author-year year, or else the last year-like number outside the arXiv ID
+fn find_year(raw: &str, arxiv_id: Option<&str>) -> Option<u16> {
+    if let Some((start, end)) = parenthesized_year(raw) {
+        return year_value(
+            raw[start + 1..end - 1].trim_end_matches(|c: char| c.is_ascii_lowercase()),
+        );
+    }
+    let text = match arxiv_id {
+        Some(id) => raw.replace(id, ""),
+        None => raw.to_string(),
+    };
+    text.split(|c: char| !c.is_ascii_alphanumeric())
+        .filter_map(|word| year_value(word.trim_end_matches(|c: char| c.is_ascii_lowercase())))
+        .next_back()
+}
+
+/// arXiv ID after "arXiv:" or in an arxiv.org URL
+fn find_arxiv_id(raw: &str) -> Option<String> {
+    // ASCII lowercasing keeps byte offsets valid in `raw`
+    let lower = raw.to_ascii_lowercase();
+    ["arxiv:", "arxiv.org/abs/", "arxiv.org/pdf/"]
+        .iter()
+        .find_map(|marker| {
+            let start = lower.find(marker)? + marker.len();
+            let id: String = raw[start..]
+                .trim_start()
+                .chars()
+                .take_while(|c| c.is_ascii_digit() || *c == '.')
+                .collect();
+            let id = id.trim_end_matches('.');
+            let (yymm, number) = id.split_once('.')?;
+            (yymm.len() == 4 && (4..=5).contains(&number.len())).then(|| id.to_string())
+        })
+}
+
+fn find_doi(raw: &str) -> Option<String> {
+    raw.split_whitespace()
+        .find_map(|token| parse_doi(token.trim_end_matches(['.', ',', ';', ')'])))
+}
+
+/// Split an author list, turning "Vaswani, A." into "A. Vaswani"
+fn parse_authors(text: &str) -> Vec<String> {
+    let text = text
+        .replace("et al.", "")
+        .replace("et al", "")
+        .replace(" and ", ", ")
+        .replace(" & ", ", ");
+    let parts: Vec<&str> = text
+        .split(',')
+        .map(|p| p.trim().trim_start_matches("and ").trim_matches(['.', ' ']))
+        .filter(|p| !p.is_empty())
+        .collect();
+
+    let mut authors = Vec::new();
+    let mut i = 0;
+    while i < parts.len() {
+        if i + 1 < parts.len() && is_initials(parts[i + 1]) && !is_initials(parts[i]) {
+            authors.push(format!("{}. {}", parts[i + 1], parts[i]));
+            i += 2;
+        } else {
+            authors.push(parts[i].to_string());
+            i += 1;
+        }
+    }
+    authors
+}
+
+/// "A", "J. L", or "M.-W" (trailing period already trimmed)
+fn is_initials(text: &str) -> bool {
+    text.split([' ', '-'])
+        .map(|w| w.trim_matches('.'))
+        .filter(|w| !w.is_empty())
+        .all(|w| w.chars().count() == 1 && w.chars().all(char::is_uppercase))
+}
+
+/// Drop the "In" prefix and the trailing year from what follows the title
+fn clean_venue(venue: &str, year: Option<u16>) -> Option<String> {
+    let mut venue = venue
+        .trim()
+        .trim_start_matches(['.', ',', ' '])
+        .trim_start_matches("In ")
+        .trim_start_matches("in ")
+        .trim_end_matches(['.', ',', ' '])
+        .to_string();
+    if let Some(year) = year
+        && let Some(stripped) = venue.strip_suffix(&year.to_string())
+    {
+        venue = stripped.trim_end_matches([',', ' ']).to_string();
+    }
+    (!venue.is_empty()).then_some(venue)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_numbered_reference() {
+        let reference = parse_reference(
+            "Dzmitry Bahdanau, Kyunghyun Cho, and Yoshua Bengio. Neural machine translation by \
+             jointly learning to align and translate. In ICLR, 2015. arXiv:1409.0473.",
+        );
+        assert_eq!(
+            reference.authors,
+            vec!["Dzmitry Bahdanau", "Kyunghyun Cho", "Yoshua Bengio"]
+        );
+        assert_eq!(
+            reference.title.as_deref(),
+            Some("Neural machine translation by jointly learning to align and translate")
+        );
+        assert_eq!(reference.year, Some(2015));
+        assert_eq!(reference.arxiv_id.as_deref(), Some("1409.0473"));
+        assert!(reference.venue.unwrap().starts_with("ICLR"));
+
+        // Characters whose lowercase is longer must not shift the ID's offset
+        assert_eq!(
+            find_arxiv_id("İİİİ Öztürk. Some title. arXiv:2301.00001").as_deref(),
+            Some("2301.00001")
+        );
+    }
+
+    #[test]
+    fn test_parse_author_year_and_quoted_references() {
+        let reference = parse_reference(
+            "Vaswani, A., Shazeer, N., et al. (2017). Attention is all you need. \
+             In Advances in Neural Information Processing Systems. doi:10.5555/3295222.3295349",
+        );
+        assert_eq!(reference.authors, vec!["A. Vaswani", "N. Shazeer"]);
+        assert_eq!(
+            reference.title.as_deref(),
+            Some("Attention is all you need")
+        );
+        assert_eq!(reference.year, Some(2017));
+        assert_eq!(reference.doi.as_deref(), Some("10.5555/3295222.3295349"));
+
+        let reference = parse_reference(
+            "K. He, X. Zhang, S. Ren, and J. Sun, “Deep residual learning for image recognition,” \
+             in Proc. CVPR, 2016, pp. 770–778.",
+        );
+        assert_eq!(
+            reference.authors,
+            vec!["K. He", "X. Zhang", "S. Ren", "J. Sun"]
+        );
+        assert_eq!(
+            reference.title.as_deref(),
+            Some("Deep residual learning for image recognition")
+        );
+        assert_eq!(reference.year, Some(2016));
+    }
+}
@@ -0,0 +1,260 @@
+use super::qa::terms;
+use crate::llm::ChatClient;
+use crate::models::analysis::{LiteratureReview, PaperAnalysis, ReviewTheme, ReviewedPaper};
+use crate::models::paper::PaperSummary;
+use serde::Deserialize;
+use shared::errors::{AppError, AppResult};
+use std::collections::HashSet;
+
+/// Weights of query match, citation count, and search position in a candidate's rank
+const MATCH_WEIGHT: f64 = 0.6;
+const CITATION_WEIGHT: f64 = 0.25;
+const POSITION_WEIGHT: f64 = 0.15;
+
+/// Credit for a query term found only in the abstract, relative to the title
+const ABSTRACT_MATCH: f64 = 0.6;
+
+const REVIEW_SYSTEM_PROMPT: &str = "You are an expert researcher writing a literature review. \
+    You cite only the papers you are given and answer only with a JSON object.";
+
+#[derive(Debug, Deserialize)]
+struct ReviewReply {
+    review: String,
+    #[serde(default)]
+    themes: Vec<ThemeReply>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ThemeReply {
+    name: String,
+    #[serde(default)]
+    description: String,
+    #[serde(default)]
+    papers: Vec<usize>,
+}
+
+/// The `n` search results that best cover the topic
+///
+/// Scores how many query terms each title and abstract contain, with
+/// citation counts and the search engine's own order as tie-breakers.
+/// Duplicate titles are dropped.
+pub fn rank_candidates(query: &str, candidates: Vec<PaperSummary>, n: usize) -> Vec<PaperSummary> {
+    let query: HashSet<String> = terms(query).into_iter().collect();
+    let max_citations = candidates
+        .iter()
+        .filter_map(|p| p.citation_count)
+        .max()
+        .unwrap_or(0);
+    let count = candidates.len().max(1) as f64;
+
+    let mut seen = HashSet::new();
+    let mut scored: Vec<(f64, PaperSummary)> = candidates
+        .into_iter()
+        .filter(|p| seen.insert(p.title.trim().to_lowercase()))
+        .enumerate()
+        .map(|(position, paper)| {
+            let title: HashSet<String> = terms(&paper.title).into_iter().collect();
+            let abstract_terms: HashSet<String> = terms(&paper.abstract_text).into_iter().collect();
+            let matched: f64 = query
+                .iter()
+                .map(|q| {
+                    if title.contains(q) {
+                        1.0
+                    } else if abstract_terms.contains(q) {
+                        ABSTRACT_MATCH
+                    } else {
+                        0.0
+                    }
+                })
+                .sum();
+            let coverage = matched / query.len().max(1) as f64;
+            let citations = match (paper.citation_count, max_citations) {
+                (Some(c), max) if max > 0 => (1.0 + c as f64).ln() / (1.0 + max as f64).ln(),
+                _ => 0.0,
+            };
+            let position = 1.0 - position as f64 / count;
+            let score =
+                MATCH_WEIGHT * coverage + CITATION_WEIGHT * citations + POSITION_WEIGHT * position;
+            (score, paper)
+        })
+        .collect();
+    scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+    scored.into_iter().take(n).map(|(_, paper)| paper).collect()
+}
+
+/// Group analyzed papers into themes and write a review citing them as [n]
+///
+/// Papers are numbered from 1 in the order given. Theme members and inline
+/// citations that do not name one of them are removed.
+pub async fn write_review(
+    chat: &ChatClient,
+    topic: &str,
+    papers: Vec<(PaperSummary, PaperAnalysis)>,
+) -> AppResult<LiteratureReview> {
+    if papers.is_empty() {
+        return Err(AppError::InvalidRequest(
+            "A literature review needs at least one analyzed paper".to_string(),
+        ));
+    }
+
+    chat.progress().stage("Writing the review").await;
+    let mut prompt = format!("Topic: {}\n\n", topic);
+    for (i, (paper, analysis)) in papers.iter().enumerate() {
+        let year = paper
+            .published_date
+            .as_deref()
+            .and_then(|d| d.get(..4))
+            .unwrap_or("n.d.");
+        prompt.push_str(&format!(
+            "[{}] {} ({})\nSummary: {}\n",
+            i + 1,
+            paper.title,
+            year,
+            analysis.summary
+        ));
+        if !analysis.key_contributions.is_empty() {
+            prompt.push_str(&format!(
+                "Contributions: {}\n",
+                analysis.key_contributions.join("; ")
+            ));
+        }
+        if let Some(ref methodology) = analysis.methodology {
+            prompt.push_str(&format!("Method: {}\n", methodology));
+        }
+        prompt.push('\n');
+    }
+    prompt.push_str(
+        "Write a literature review of these papers on the topic. Respond with JSON of the form \
+         {\"themes\": [{\"name\": \"...\", \"description\": \"...\", \"papers\": [1, 2]}], \
+         \"review\": \"...\"}. Group the papers into two to six themes by approach or research \
+         question; every paper belongs to at least one theme. The review should have an \
+         introduction, a few paragraphs per theme comparing the papers, and a closing \
+         paragraph on open problems. Cite papers inline as [n], using only the numbers above.",
+    );
+
+    let reply: ReviewReply = chat.complete_json(REVIEW_SYSTEM_PROMPT, &prompt).await?;
+
+    let count = papers.len();
+    let themes = reply
+        .themes
+        .into_iter()
+        .filter_map(|theme| {
+            let mut members: Vec<usize> = theme
+                .papers
+                .into_iter()
+                .filter(|n| (1..=count).contains(n))
+                .collect();
+            members.sort_unstable();
+            members.dedup();
+            (!members.is_empty()).then_some(ReviewTheme {
+                name: theme.name,
+                description: theme.description,
+                papers: members,
+            })
+        })
+        .collect();
+
+    Ok(LiteratureReview {
+        topic: topic.to_string(),
+        review: check_citations(&reply.review, count),
+        themes,
+        papers: papers
+            .into_iter()
+            .enumerate()
+            .map(|(i, (paper, analysis))| ReviewedPaper {
+                citation: i + 1,
+                paper,
+                summary: analysis.summary,
+                key_contributions: analysis.key_contributions,
+            })
+            .collect(),
+    })
+}
+
+/// Drop citation numbers outside 1..=count from "[n]" and "[n, m]" markers
+fn check_citations(review: &str, count: usize) -> String {
+    let mut checked = String::with_capacity(review.len());
+    let mut rest = review;
+    while let Some(open) = rest.find('[') {
+        checked.push_str(&rest[..open]);
+        let after = &rest[open + 1..];
+        let Some(close) = after.find(']') else {
+            checked.push_str(&rest[open..]);
+            return checked;
+        };
+        let inner = &after[..close];
+        let numbers: Option<Vec<usize>> = inner.split(',').map(|n| n.trim().parse().ok()).collect();
+        match numbers {
+            Some(numbers) => {
+                let valid: Vec<String> = numbers
+                    .iter()
+                    .filter(|n| (1..=count).contains(*n))
+                    .map(usize::to_string)
+                    .collect();
+                if valid.len() < numbers.len() {
+                    tracing::warn!("Removing unknown citations from the review: [{}]", inner);
+                }
+                if valid.is_empty() {
+                    checked.truncate(checked.trim_end().len());
+                } else {
+                    checked.push_str(&format!("[{}]", valid.join(", ")));
+                }
+            }
+            None => checked.push_str(&rest[open..=open + 1 + close]),
+        }
+        rest = &after[close + 1..];
+    }
+    checked.push_str(rest);
+    checked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary(title: &str, abstract_text: &str, citations: u32) -> PaperSummary {
+        PaperSummary {
+            title: title.to_string(),
+            authors: vec![],
+            abstract_text: abstract_text.to_string(),
+            arxiv_id: None,
+            ss_id: None,
+            categories: vec![],
+            published_date: None,
+            citation_count: Some(citations),
+            pdf_url: None,
+        }
+    }
+
+    #[test]
+    fn test_rank_candidates() {
+        let candidates = vec![
+            summary("A survey of graph databases", "Storage engines.", 5000),
+            summary(
+                "Retrieval-augmented generation",
+                "We retrieve passages for LLMs.",
+                300,
+            ),
+            summary("Retrieval-Augmented Generation", "Duplicate entry.", 300),
+            summary(
+                "Dense passage retrieval",
+                "Retrieval for open-domain QA.",
+                2000,
+            ),
+        ];
+        let ranked = rank_candidates("retrieval augmented generation", candidates, 2);
+        let titles: Vec<&str> = ranked.iter().map(|p| p.title.as_str()).collect();
+        assert_eq!(
+            titles,
+            vec!["Retrieval-augmented generation", "Dense passage retrieval"]
+        );
+    }
+
+    #[test]
+    fn test_check_citations() {
+        assert_eq!(
+            check_citations("RAG [1] builds on DPR [2, 7] and [9]; see [a] too.", 3),
+            "RAG [1] builds on DPR [2] and; see [a] too."
+        );
+    }
+}
@@ -0,0 +1,187 @@
+/// A section of paper text detected from its headings
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextSection {
+    pub heading: String,
+    pub level: u8,
+    pub text: String,
+}
+
+/// Unnumbered headings commonly found in papers
+const KNOWN_HEADINGS: &[&str] = &[
+    "abstract",
+    "introduction",
+    "background",
+    "related work",
+    "preliminaries",
+    "method",
+    "methods",
+    "methodology",
+    "approach",
+    "model",
+    "experiments",
+    "experimental setup",
+    "evaluation",
+    "results",
+    "discussion",
+    "limitations",
+    "conclusion",
+    "conclusions",
+    "future work",
+    "acknowledgments",
+    "acknowledgements",
+    "references",
+    "bibliography",
+    "appendix",
+];
+
+/// Maximum length of a line that can be treated as a heading
+const MAX_HEADING_LEN: usize = 80;
+
+/// Split extracted plain text into sections by detecting heading lines
+///
+/// Recognizes numbered headings ("3 Method", "3.1. Encoder", "A Proofs")
+/// and well-known unnumbered headings ("Abstract", "References").
+/// Text before the first heading is returned as a "Preamble" section.
+pub fn split_sections(text: &str) -> Vec<TextSection> {
+    let mut sections = Vec::new();
+    let mut current: Option<TextSection> = None;
+    let mut preamble = String::new();
+
+    for line in text.lines() {
+        if let Some((heading, level)) = parse_heading(line) {
+            if let Some(section) = current.take() {
+                sections.push(section);
+            }
+            current = Some(TextSection {
+                heading,
+                level,
+                text: String::new(),
+            });
+            continue;
+        }
+
+        let target = match current.as_mut() {
+            Some(section) => &mut section.text,
+            None => &mut preamble,
+        };
+        target.push_str(line);
+        target.push('\n');
+    }
+
+    if let Some(section) = current {
+        sections.push(section);
+    }
+
+    if !preamble.trim().is_empty() {
+        sections.insert(
+            0,
+            TextSection {
+                heading: "Preamble".to_string(),
+                level: 1,
+                text: preamble,
+            },
+        );
+    }
+
+    for section in &mut sections {
+        section.text = section.text.trim().to_string();
+    }
+    sections
+}
+
+/// Returns the heading text and nesting level if the line looks like a heading
+fn parse_heading(line: &str) -> Option<(String, u8)> {
+    let line = line.trim();
+    if line.is_empty() || line.len() > MAX_HEADING_LEN || line.ends_with('.') {
+        return None;
+    }
+
+    let normalized = line.trim_end_matches(':').to_lowercase();
+    if KNOWN_HEADINGS.contains(&normalized.as_str()) {
+        return Some((line.trim_end_matches(':').to_string(), 1));
+    }
+
+    // Numbered heading: "3", "3.1", "3.1." or an appendix letter "A", followed by a title
+    let (number, title) = line.split_once(char::is_whitespace)?;
+    let number = number.trim_end_matches('.');
+    let title = title.trim();
+    if title.is_empty() || !title.starts_with(|c: char| c.is_uppercase()) {
+        return None;
+    }
+
+    let is_numbered = !number.is_empty()
+        && number.split('.').all(|part| {
+            !part.is_empty() && part.len() <= 2 && part.chars().all(|c| c.is_ascii_digit())
+        });
+    // Appendix headings must be in title case, otherwise "A model ..." would match
+    let is_appendix = number.len() == 1
+        && number.chars().all(|c| c.is_ascii_uppercase())
+        && title
+            .split_whitespace()
+            .filter(|word| word.len() > 3)
+            .all(|word| word.starts_with(|c: char| c.is_uppercase() || c.is_ascii_digit()));
+    if !is_numbered && !is_appendix {
+        return None;
+    }
+
+    // Guard against table rows and equations that happen to start with a number
+    if title.chars().filter(|c| c.is_ascii_digit()).count() > title.len() / 3 {
+        return None;
+    }
+
+    let level = if is_numbered {
+        number.split('.').count().min(u8::MAX as usize) as u8
+    } else {
+        1
+    };
+    Some((format!("{} {}", number, title), level))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_sections() {
+        let text = "Attention Is All You Need\nAbstract\nWe propose the Transformer.\n\
+                    1 Introduction\nRecurrent models are slow.\n\
+                    3.1 Encoder and Decoder Stacks\nThe encoder has six layers.\n\
+                    References\n[1] Bahdanau et al.";
+        let sections = split_sections(text);
+        let headings: Vec<&str> = sections.iter().map(|s| s.heading.as_str()).collect();
+        assert_eq!(
+            headings,
+            vec![
+                "Preamble",
+                "Abstract",
+                "1 Introduction",
+                "3.1 Encoder and Decoder Stacks",
+                "References"
+            ]
+        );
+        assert_eq!(sections[3].level, 2);
+        assert_eq!(sections[2].text, "Recurrent models are slow.");
+    }
+
+    #[test]
+    fn test_parse_heading_rejects_body_text() {
+        assert_eq!(
+            parse_heading("2 Related Work"),
+            Some(("2 Related Work".to_string(), 1))
+        );
+        assert_eq!(
+            parse_heading("A Proof of Theorem 1"),
+            Some(("A Proof of Theorem 1".to_string(), 1))
+        );
+        assert_eq!(
+            parse_heading("In this paper we show that the model works."),
+            None
+        );
+        assert_eq!(parse_heading("12 34.5 67.8 90.1"), None);
+        assert_eq!(parse_heading("2 the model is trained"), None);
+        assert_eq!(
+            parse_heading("A Transformer model without recurrence"),
+            None
+        );
+    }
+}
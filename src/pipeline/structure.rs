@@ -0,0 +1,357 @@
+use super::references::parse_reference;
+use super::sections::split_sections;
+use crate::models::paper::{
+    Caption, CaptionKind, Paper, PaperSection, PaperStructure, SectionKind,
+};
+
+/// Upper bound on a caption's length; longer runs are body text that followed it
+const MAX_CAPTION_CHARS: usize = 600;
+
+/// Reference entries shorter than this are page numbers or stray headers
+const MIN_REFERENCE_CHARS: usize = 15;
+
+/// Fill in `paper.structure` from its content, unless already present
+pub fn add_structure(paper: &mut Paper) {
+    if paper.structure.is_none()
+        && let Some(content) = paper.content.as_deref()
+    {
+        paper.structure = Some(paper_structure(content));
+    }
+}
+
+/// Detect sections, figure/table captions, and reference entries in full text
+pub fn paper_structure(text: &str) -> PaperStructure {
+    let mut sections: Vec<PaperSection> = Vec::new();
+    let mut parent_kind = SectionKind::Other;
+    let mut in_appendix = false;
+
+    for section in split_sections(text) {
+        let own_kind = if section.heading == "Preamble" {
+            SectionKind::FrontMatter
+        } else {
+            section_kind(&section.heading)
+        };
+        let kind = match own_kind {
+            SectionKind::Other if section.level > 1 => parent_kind,
+            // Unlabeled top-level sections after "Appendix" belong to it
+            SectionKind::Other if in_appendix => SectionKind::Appendix,
+            kind => kind,
+        };
+        if section.level == 1 {
+            parent_kind = kind;
+            in_appendix = kind == SectionKind::Appendix;
+        }
+        sections.push(PaperSection {
+            heading: section.heading,
+            level: section.level,
+            kind,
+            text: section.text,
+        });
+    }
+
+    let references = sections
+        .iter()
+        .filter(|s| s.kind == SectionKind::References)
+        .flat_map(|s| split_references(&s.text))
+        .map(|entry| parse_reference(&entry))
+        .collect();
+
+    PaperStructure {
+        sections,
+        captions: extract_captions(text),
+        references,
+        references_resolved: false,
+    }
+}
+
+/// Classify a heading such as "3.2 Experimental Setup" by its keywords
+pub fn section_kind(heading: &str) -> SectionKind {
+    let (number, title) = heading.split_once(' ').unwrap_or(("", heading));
+    let numbered = number.starts_with(|c: char| c.is_ascii_alphanumeric())
+        && number.trim_end_matches('.').len() <= 5
+        && number
+            .chars()
+            .any(|c| c.is_ascii_digit() || c.is_ascii_uppercase())
+        && !number.chars().any(|c| c.is_ascii_lowercase());
+    let title = if numbered { title } else { heading }.to_lowercase();
+    let has = |words: &[&str]| words.iter().any(|w| title.contains(w));
+
+    // Appendix headings are lettered: "A Proofs", "B.2 Hyperparameters"
+    if numbered && number.starts_with(|c: char| c.is_ascii_uppercase()) {
+        return SectionKind::Appendix;
+    }
+    if has(&["appendix", "appendices", "supplementary"]) {
+        SectionKind::Appendix
+    } else if has(&["references", "bibliography"]) {
+        SectionKind::References
+    } else if has(&["acknowledg"]) {
+        SectionKind::Acknowledgments
+    } else if has(&["abstract"]) {
+        SectionKind::Abstract
+    } else if has(&["introduction"]) {
+        SectionKind::Introduction
+    } else if has(&["related work", "background", "preliminar", "literature"]) {
+        SectionKind::RelatedWork
+    } else if has(&["conclusion", "future work", "summary"]) {
+        SectionKind::Conclusion
+    } else if has(&["discussion", "limitation"]) {
+        SectionKind::Discussion
+    } else if has(&["result", "analysis", "ablation"]) {
+        SectionKind::Results
+    } else if has(&["experiment", "evaluation", "setup", "benchmark"]) {
+        SectionKind::Experiments
+    } else if has(&["method", "approach", "model", "architecture", "framework"]) {
+        SectionKind::Method
+    } else {
+        SectionKind::Other
+    }
+}
+
+/// Captions of the form "Figure 2: ..." or "Table 1. ...", first occurrence of each label
+///
+/// In-text mentions ("Figure 2 shows ...") lack the separator and are skipped.
+/// A caption runs until a blank line or the next caption.
+pub fn extract_captions(text: &str) -> Vec<Caption> {
+    let mut captions: Vec<Caption> = Vec::new();
+    let mut current: Option<Caption> = None;
+
+    for line in text.lines() {
+        let line = line.trim();
+        let next = parse_caption(line);
+        if let Some(caption) = current.as_mut()
+            && next.is_none()
+            && !line.is_empty()
+            && caption.text.len() < MAX_CAPTION_CHARS
+        {
+            caption.text.push(' ');
+            caption.text.push_str(line);
+            continue;
+        }
+
+        if let Some(caption) = current.take()
+            && !captions.iter().any(|c| c.label == caption.label)
+        {
+            captions.push(caption);
+        }
+        current = next;
+    }
+    if let Some(caption) = current
+        && !captions.iter().any(|c| c.label == caption.label)
+    {
+        captions.push(caption);
+    }
+    captions
+}
+
+fn parse_caption(line: &str) -> Option<Caption> {
+    const PREFIXES: [(&str, CaptionKind); 6] = [
+        ("Figure ", CaptionKind::Figure),
+        ("FIGURE ", CaptionKind::Figure),
+        ("Fig. ", CaptionKind::Figure),
+        ("Fig ", CaptionKind::Figure),
+        ("Table ", CaptionKind::Table),
+        ("TABLE ", CaptionKind::Table),
+    ];
+    let (rest, kind) = PREFIXES
+        .iter()
+        .find_map(|(prefix, kind)| line.strip_prefix(prefix).map(|rest| (rest, *kind)))?;
+
+    let end = rest
+        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '.'))
+        .unwrap_or(rest.len());
+    let (number, after) = rest.split_at(end);
+    let (number, text) = match after.chars().next() {
+        Some(':' | '|') => (number, &after[1..]),
+        // "Table 1. Results" leaves the period on the number
+        None | Some(' ') if number.ends_with('.') => (number.trim_end_matches('.'), after),
+        _ => return None,
+    };
+    if number.is_empty() || number.len() > 6 || !number.chars().any(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    let name = match kind {
+        CaptionKind::Figure => "Figure",
+        CaptionKind::Table => "Table",
+    };
+    Some(Caption {
+        kind,
+        label: format!("{} {}", name, number),
+        text: text.trim().to_string(),
+    })
+}
+
+/// Split a reference list into one string per entry
+///
+/// Numbered lists ("[12] ..." or "12. ...") are split at their markers,
+/// which are dropped. Author-year lists are split at blank lines, or failing
+/// that where a line ending in a period is followed by "Surname, ...".
+pub fn split_references(text: &str) -> Vec<String> {
+    let lines: Vec<&str> = text.lines().map(str::trim).collect();
+    let numbered = |marker: fn(&str) -> Option<&str>| {
+        lines.iter().filter(|l| marker(l).is_some()).count() >= 2
+    };
+
+    let entries: Vec<Vec<&str>> = if numbered(bracket_marker) {
+        split_at_markers(&lines, bracket_marker)
+    } else if numbered(dotted_marker) {
+        split_at_markers(&lines, dotted_marker)
+    } else if lines.iter().filter(|l| l.is_empty()).count() >= 2 {
+        lines
+            .split(|l| l.is_empty())
+            .map(|entry| entry.to_vec())
+            .collect()
+    } else {
+        let mut entries: Vec<Vec<&str>> = Vec::new();
+        for (i, line) in lines.iter().enumerate() {
+            let starts_entry = i == 0 || (lines[i - 1].ends_with('.') && starts_with_surname(line));
+            match entries.last_mut() {
+                Some(entry) if !starts_entry => entry.push(line),
+                _ => entries.push(vec![line]),
+            }
+        }
+        entries
+    };
+
+    entries
+        .iter()
+        .map(|entry| join_lines(entry))
+        .filter(|entry| entry.chars().count() >= MIN_REFERENCE_CHARS)
+        .collect()
+}
+
+/// Text after a "[12]" marker
+fn bracket_marker(line: &str) -> Option<&str> {
+    let rest = line.strip_prefix('[')?;
+    let (number, rest) = rest.split_once(']')?;
+    (!number.is_empty() && number.len() <= 3 && number.chars().all(|c| c.is_ascii_digit()))
+        .then_some(rest)
+}
+
+/// Text after a "12." marker
+fn dotted_marker(line: &str) -> Option<&str> {
+    let (number, rest) = line.split_once(". ")?;
+    (!number.is_empty() && number.len() <= 3 && number.chars().all(|c| c.is_ascii_digit()))
+        .then_some(rest)
+}
+
+fn split_at_markers<'a>(lines: &[&'a str], marker: fn(&str) -> Option<&str>) -> Vec<Vec<&'a str>> {
+    let mut entries: Vec<Vec<&'a str>> = Vec::new();
+    for line in lines {
+        match marker(line) {
+            Some(rest) => entries.push(vec![rest.trim_start()]),
+            // Lines before the first marker are a stray header
+            None => {
+                if let Some(entry) = entries.last_mut() {
+                    entry.push(line);
+                }
+            }
+        }
+    }
+    entries
+}
+
+/// "Vaswani, A." or "Vaswani A," at the start of an author-year entry
+fn starts_with_surname(line: &str) -> bool {
+    let Some(first) = line.split_whitespace().next() else {
+        return false;
+    };
+    first.starts_with(|c: char| c.is_uppercase())
+        && first.ends_with(',')
+        && first.chars().filter(|c| c.is_alphabetic()).count() >= 2
+}
+
+/// Join wrapped lines, rejoining words hyphenated across a line break
+fn join_lines(lines: &[&str]) -> String {
+    let mut joined = String::new();
+    for line in lines.iter().filter(|l| !l.is_empty()) {
+        if joined.ends_with('-') {
+            joined.pop();
+        } else if !joined.is_empty() {
+            joined.push(' ');
+        }
+        joined.push_str(line);
+    }
+    joined
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paper_structure() {
+        let text = "Attention Is All You Need\nAbstract\nWe propose the Transformer.\n\
+                    1 Introduction\nRecurrent models are slow.\n\
+                    3 Model Architecture\nAn encoder and a decoder.\n\
+                    Figure 1: The Transformer - model\narchitecture.\n\n\
+                    3.1 Encoder and Decoder Stacks\nThe encoder has six layers.\n\
+                    Table 2. BLEU scores on WMT 2014.\n\n\
+                    As Figure 1 shows, attention is all you need.\n\
+                    7 Conclusion\nIt works.\n\
+                    References\n[1] Dzmitry Bahdanau, Kyunghyun Cho, and Yoshua Bengio. Neural machine\n\
+                    translation by jointly learning to align and translate. In ICLR, 2015.\n\
+                    [2] Kaiming He, Xiangyu Zhang, Shaoqing Ren, and Jian Sun. Deep residual learn-\n\
+                    ing for image recognition. In CVPR, 2016.\n\
+                    A Attention Visualizations\nLayer 5 of 6.";
+        let structure = paper_structure(text);
+        let kinds: Vec<(&str, SectionKind)> = structure
+            .sections
+            .iter()
+            .map(|s| (s.heading.as_str(), s.kind))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                ("Preamble", SectionKind::FrontMatter),
+                ("Abstract", SectionKind::Abstract),
+                ("1 Introduction", SectionKind::Introduction),
+                ("3 Model Architecture", SectionKind::Method),
+                ("3.1 Encoder and Decoder Stacks", SectionKind::Method),
+                ("7 Conclusion", SectionKind::Conclusion),
+                ("References", SectionKind::References),
+                ("A Attention Visualizations", SectionKind::Appendix),
+            ]
+        );
+
+        assert_eq!(
+            structure.captions,
+            vec![
+                Caption {
+                    kind: CaptionKind::Figure,
+                    label: "Figure 1".to_string(),
+                    text: "The Transformer - model architecture.".to_string(),
+                },
+                Caption {
+                    kind: CaptionKind::Table,
+                    label: "Table 2".to_string(),
+                    text: "BLEU scores on WMT 2014.".to_string(),
+                },
+            ]
+        );
+
+        assert_eq!(structure.references.len(), 2);
+        assert!(structure.references[1]
+            .raw
+            .ends_with("Deep residual learning for image recognition. In CVPR, 2016."));
+        assert_eq!(
+            structure.references[1].title.as_deref(),
+            Some("Deep residual learning for image recognition")
+        );
+    }
+
+    #[test]
+    fn test_split_author_year_references() {
+        let text = "Ba, J. L., Kiros, J. R., and Hinton, G. E. (2016). Layer\nnormalization.\n\
+                    Vaswani, A., Shazeer, N., et al. (2017). Attention is all you need.\n\
+                    In Advances in Neural Information Processing Systems.";
+        let references = split_references(text);
+        assert_eq!(
+            references,
+            vec![
+                "Ba, J. L., Kiros, J. R., and Hinton, G. E. (2016). Layer normalization.",
+                "Vaswani, A., Shazeer, N., et al. (2017). Attention is all you need. In Advances in Neural Information Processing Systems.",
+            ]
+        );
+    }
+}
@@ -0,0 +1,202 @@
+use super::chunking::{chunk_text, estimate_tokens};
+use crate::llm::ChatClient;
+use crate::models::analysis::PaperAnalysis;
+use crate::models::paper::Paper;
+use futures::{StreamExt, TryStreamExt};
+use serde::Deserialize;
+use shared::errors::AppResult;
+
+/// Most tokens of body text in one prompt, even for models with larger
+/// contexts; longer texts are summarized part by part
+const MAX_CHUNK_TOKENS: usize = 24_000;
+
+/// Smallest chunk, for models whose context barely fits the instructions
+const MIN_CHUNK_TOKENS: usize = 256;
+
+/// Number of part prompts in flight at once
+const CONCURRENCY: usize = 4;
+
+/// Bump whenever the prompts below change, so stored analyses are not reused
+pub const PROMPT_VERSION: u32 = 2;
+
+const SUMMARY_SYSTEM_PROMPT: &str = "You are an expert reviewer of academic papers. \
+    You summarize papers accurately for researchers and answer only with a JSON object.";
+
+const NOTES_SYSTEM_PROMPT: &str = "You are an expert reviewer of academic papers. \
+    You take notes on one part of a paper at a time and answer only with a JSON object.";
+
+#[derive(Debug, Deserialize)]
+struct SummaryReply {
+    summary: String,
+    #[serde(default)]
+    key_contributions: Vec<String>,
+    #[serde(default)]
+    methodology: Option<String>,
+    #[serde(default)]
+    limitations: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct NotesReply {
+    notes: String,
+}
+
+/// Summarize a paper: overview, contributions, methodology, and limitations
+///
+/// Full text longer than one prompt allows is map-reduced: each chunk is
+/// condensed into notes, and the notes are summarized in its place.
+pub async fn summarize(chat: &ChatClient, paper: &Paper) -> AppResult<PaperAnalysis> {
+    let mut prompt = format!(
+        "Title: {}\nAuthors: {}\nAbstract: {}\n",
+        paper.title,
+        paper.authors.join(", "),
+        paper.abstract_text
+    );
+    // The budget covers the body text; the title and abstract come on top
+    let budget = chat
+        .input_budget()
+        .min(MAX_CHUNK_TOKENS)
+        .saturating_sub(estimate_tokens(&prompt))
+        .max(MIN_CHUNK_TOKENS);
+    match paper.content.as_deref() {
+        Some(content) if estimate_tokens(content) > budget => {
+            let notes = condense(chat, &paper.title, content, budget).await?;
+            prompt.push_str(&format!(
+                "\nNotes on the full text, part by part:\n{}\n",
+                notes
+            ));
+        }
+        Some(content) => prompt.push_str(&format!("\nFull text:\n{}\n", content)),
+        None => {}
+    }
+    prompt.push_str(
+        "\nSummarize this paper. Respond with JSON of the form \
+         {\"summary\": \"...\", \"key_contributions\": [\"...\"], \"methodology\": \"...\", \
+         \"limitations\": [\"...\"]}. The summary should be one or two paragraphs; \
+         limitations should cover weaknesses and open questions for future work.",
+    );
+
+    let reply: SummaryReply = chat.complete_json(SUMMARY_SYSTEM_PROMPT, &prompt).await?;
+
+    Ok(PaperAnalysis {
+        summary: reply.summary,
+        key_contributions: reply.key_contributions,
+        methodology: reply.methodology.filter(|m| !m.trim().is_empty()),
+        limitations: reply.limitations,
+        related_work: vec![],
+        sections: vec![],
+        comparison: None,
+    })
+}
+
+/// Condense text too long for one prompt into notes that fit in `budget` tokens
+///
+/// Each chunk is turned into notes; while the notes together are still too
+/// long, neighboring notes are merged.
+async fn condense(
+    chat: &ChatClient,
+    title: &str,
+    content: &str,
+    budget: usize,
+) -> AppResult<String> {
+    // Owned chunks, since a stream of borrowed ones does not prove Send to the tool router
+    let chunks: Vec<String> = chunk_text(content, budget)
+        .into_iter()
+        .map(str::to_string)
+        .collect();
+    let parts = chunks.len();
+    tracing::info!("Summarizing \"{}\" in {} parts", title, parts);
+    chat.progress()
+        .stage(&format!("Taking notes on {} parts of the paper", parts))
+        .await;
+    let mut notes: Vec<String> = futures::stream::iter(chunks.into_iter().enumerate())
+        .map(|(i, chunk)| take_notes(chat, title, i + 1, parts, chunk))
+        .buffered(CONCURRENCY)
+        .try_collect()
+        .await?;
+
+    while notes.len() > 1 && estimate_tokens(&notes.join("\n\n")) > budget {
+        chat.progress().stage("Merging notes").await;
+        notes = futures::stream::iter(group_notes(notes, budget))
+            .map(|group| merge_notes(chat, title, group))
+            .buffered(CONCURRENCY)
+            .try_collect()
+            .await?;
+    }
+    Ok(notes.join("\n\n"))
+}
+
+async fn take_notes(
+    chat: &ChatClient,
+    title: &str,
+    part: usize,
+    parts: usize,
+    text: String,
+) -> AppResult<String> {
+    let prompt = format!(
+        "Paper: {title}\nPart {part} of {parts}:\n\n{text}\n\n\
+         Take notes on this part of the paper for a summary of the whole paper. Respond with \
+         JSON of the form {{\"notes\": \"...\"}}. Cover the problem, contributions, method, \
+         experimental setup, results with their numbers, and limitations that appear in this \
+         part, in at most 300 words.",
+    );
+    let reply: NotesReply = chat.complete_json(NOTES_SYSTEM_PROMPT, &prompt).await?;
+    Ok(format!("[Part {}/{}] {}", part, parts, reply.notes))
+}
+
+async fn merge_notes(chat: &ChatClient, title: &str, group: Vec<String>) -> AppResult<String> {
+    if group.len() == 1 {
+        return Ok(group.into_iter().next().unwrap_or_default());
+    }
+    let prompt = format!(
+        "Paper: {title}\nNotes on consecutive parts of the paper:\n\n{notes}\n\n\
+         Merge these notes into one set of notes. Respond with JSON of the form \
+         {{\"notes\": \"...\"}}. Keep every contribution, method detail, and reported number, \
+         in at most 400 words.",
+        notes = group.join("\n\n"),
+    );
+    let reply: NotesReply = chat.complete_json(NOTES_SYSTEM_PROMPT, &prompt).await?;
+    Ok(reply.notes)
+}
+
+/// Group consecutive notes so each group fits in `budget` tokens
+///
+/// Groups take at least two notes, so every merge round shortens the list.
+fn group_notes(notes: Vec<String>, budget: usize) -> Vec<Vec<String>> {
+    let mut groups: Vec<Vec<String>> = Vec::new();
+    let mut tokens = 0;
+    for note in notes {
+        let note_tokens = estimate_tokens(&note);
+        match groups.last_mut() {
+            Some(group) if group.len() < 2 || tokens + note_tokens <= budget => {
+                tokens += note_tokens;
+                group.push(note);
+            }
+            _ => {
+                tokens = note_tokens;
+                groups.push(vec![note]);
+            }
+        }
+    }
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_group_notes() {
+        let notes: Vec<String> = [
+            "a".repeat(40),
+            "b".repeat(40),
+            "c".repeat(40),
+            "d".repeat(200),
+        ]
+        .into_iter()
+        .collect();
+        // 40 characters is 10 tokens; a 100-token note still joins a group of one
+        let sizes: Vec<usize> = group_notes(notes, 25).iter().map(Vec::len).collect();
+        assert_eq!(sizes, vec![2, 2]);
+    }
+}
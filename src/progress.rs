@@ -0,0 +1,67 @@
+use rmcp::model::{ProgressNotificationParam, ProgressToken};
+use rmcp::service::{Peer, RoleServer};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+
+/// Reports the stages of a long-running tool call as MCP progress notifications
+///
+/// Cheap to clone; clones share one counter, so progress keeps increasing
+/// across the fetch and analysis steps of a single call. A reporter without a
+/// peer or progress token only logs.
+#[derive(Clone, Default)]
+pub struct Progress {
+    inner: Option<Arc<ProgressInner>>,
+}
+
+struct ProgressInner {
+    peer: Peer<RoleServer>,
+    token: ProgressToken,
+    step: AtomicU32,
+}
+
+impl Progress {
+    /// Report to `peer` when the caller supplied a progress token
+    pub fn new(peer: Option<Peer<RoleServer>>, token: Option<ProgressToken>) -> Self {
+        let inner = match (peer, token) {
+            (Some(peer), Some(token)) => Some(Arc::new(ProgressInner {
+                peer,
+                token,
+                step: AtomicU32::new(0),
+            })),
+            _ => None,
+        };
+        Self { inner }
+    }
+
+    /// A reporter that only logs
+    pub fn none() -> Self {
+        Self::default()
+    }
+
+    /// Announce that a stage has started
+    pub async fn stage(&self, stage: &str) {
+        tracing::debug!("Stage: {}", stage);
+        let Some(ref inner) = self.inner else {
+            return;
+        };
+
+        let progress = inner.step.fetch_add(1, Ordering::Relaxed) + 1;
+        let param = ProgressNotificationParam {
+            progress_token: inner.token.clone(),
+            progress,
+            total: None,
+        };
+        // A client that went away must not fail the call itself
+        if let Err(e) = inner.peer.notify_progress(param).await {
+            tracing::debug!("Failed to send progress notification: {}", e);
+        }
+    }
+}
+
+impl std::fmt::Debug for Progress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Progress")
+            .field("token", &self.inner.as_ref().map(|inner| &inner.token))
+            .finish()
+    }
+}
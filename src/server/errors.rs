@@ -0,0 +1,84 @@
+use crate::models::response::McpErrorDetail;
+use rmcp::Error as McpError;
+use shared::errors::AppError;
+
+/// Convert an application error into an MCP error
+///
+/// The error code, retryability, and the upstream message go in `data` as an
+/// [`McpErrorDetail`], so clients can tell a missing paper from a rate limit.
+pub(crate) fn mcp_error(error: AppError) -> McpError {
+    let message = error.to_string();
+    let detail = McpErrorDetail {
+        code: error.error_code().to_string(),
+        message: message.clone(),
+        retryable: error.is_retryable(),
+        retry_after_secs: error.retry_after_secs(),
+        details: upstream_details(&error),
+    };
+    let data = serde_json::to_value(&detail).ok();
+
+    match error {
+        AppError::PaperNotFound(_) => McpError::resource_not_found(message, data),
+        AppError::InvalidArxivId(_) | AppError::InvalidRequest(_) | AppError::LlmConfigError(_) => {
+            McpError::invalid_params(message, data)
+        }
+        _ => McpError::internal_error(message, data),
+    }
+}
+
+/// Error returned when the client cancelled the request mid-operation
+pub(crate) fn cancelled_error(operation: &str) -> McpError {
+    mcp_error(AppError::Cancelled(format!(
+        "{} cancelled by the client",
+        operation
+    )))
+}
+
+/// The upstream service's own message, without our variant prefix
+fn upstream_details(error: &AppError) -> Option<serde_json::Value> {
+    let upstream = match error {
+        AppError::PaperNotFound(m)
+        | AppError::InvalidArxivId(m)
+        | AppError::PdfFetchFailed(m)
+        | AppError::LlmError(m)
+        | AppError::NetworkError(m) => m,
+        AppError::RateLimitExceeded { message, .. } => message,
+        _ => return None,
+    };
+    Some(serde_json::json!({ "upstream": upstream }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rmcp::model::ErrorCode;
+
+    #[test]
+    fn test_mcp_error() {
+        let error = mcp_error(AppError::RateLimitExceeded {
+            message: "Semantic Scholar returned 429 Too Many Requests".to_string(),
+            retry_after_secs: Some(30),
+        });
+        assert_eq!(error.code, ErrorCode::INTERNAL_ERROR);
+        let data = error.data.unwrap();
+        assert_eq!(data["code"], "RATE_LIMIT");
+        assert_eq!(data["retryable"], true);
+        assert_eq!(data["retry_after_secs"], 30);
+        assert_eq!(
+            data["details"]["upstream"],
+            "Semantic Scholar returned 429 Too Many Requests"
+        );
+
+        let error = mcp_error(AppError::PaperNotFound("arXiv ID 9999.99999".to_string()));
+        assert_eq!(error.code, ErrorCode::RESOURCE_NOT_FOUND);
+        let data = error.data.unwrap();
+        assert_eq!(data["code"], "PAPER_NOT_FOUND");
+        assert_eq!(data["retryable"], false);
+        assert!(data.get("retry_after_secs").is_none());
+
+        let error = mcp_error(AppError::LlmConfigError(
+            "OPENAI_API_KEY not set".to_string(),
+        ));
+        assert_eq!(error.code, ErrorCode::INVALID_PARAMS);
+    }
+}
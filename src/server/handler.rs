@@ -1,22 +1,31 @@
+use crate::cache::{AnalysisCache, PaperCache};
 use crate::llm::LlmConfigResolver;
-use rmcp::model::{ServerCapabilities, ServerInfo};
-use rmcp::service::{Peer, RoleServer};
-use rmcp::tool;
-use rmcp::ServerHandler;
+use crate::server::prompts::prompt_list;
+use crate::server::resources::resource_templates;
+use rmcp::model::{
+    GetPromptRequestParam, GetPromptResult, ListPromptsResult, ListResourceTemplatesResult,
+    ListResourcesResult, PaginatedRequestParam, ReadResourceRequestParam, ReadResourceResult,
+    ServerCapabilities, ServerInfo,
+};
+use rmcp::service::{Peer, RequestContext, RoleServer};
+use rmcp::{Error as McpError, ServerHandler};
 use std::sync::Arc;
 
 /// Main MCP service handler
 #[derive(Clone)]
 pub struct PaperInterpreterService {
     llm_config_resolver: Arc<LlmConfigResolver>,
+    paper_cache: Arc<PaperCache>,
+    analysis_cache: Arc<AnalysisCache>,
     peer: Option<Peer<RoleServer>>,
 }
 
-#[tool(tool_box)]
 impl PaperInterpreterService {
     pub fn new() -> Self {
         Self {
             llm_config_resolver: Arc::new(LlmConfigResolver::new()),
+            paper_cache: Arc::new(PaperCache::from_env()),
+            analysis_cache: Arc::new(AnalysisCache::from_env()),
             peer: None,
         }
     }
@@ -24,6 +33,30 @@ impl PaperInterpreterService {
     pub fn llm_config_resolver(&self) -> &LlmConfigResolver {
         &self.llm_config_resolver
     }
+
+    pub fn paper_cache(&self) -> &PaperCache {
+        &self.paper_cache
+    }
+
+    pub fn analysis_cache(&self) -> &AnalysisCache {
+        &self.analysis_cache
+    }
+
+    // Tools are defined in `server::tools`, one impl block per file, so they are
+    // registered here explicitly rather than collected with `#[tool(tool_box)]`.
+    rmcp::tool_box!(PaperInterpreterService {
+        search_papers,
+        fetch_paper,
+        analyze_paper,
+        interpret_paper,
+        ask_paper,
+        compare_papers,
+        literature_review,
+        get_paper_section,
+        get_references,
+        get_citations,
+        recommend_similar,
+    } tool_box);
 }
 
 impl Default for PaperInterpreterService {
@@ -38,10 +71,16 @@ impl ServerHandler for PaperInterpreterService {
             instructions: Some(
                 "An MCP server for searching, fetching, and analyzing academic papers. \
                  Use interpret_paper for end-to-end analysis, or use search_papers, \
-                 fetch_paper, and analyze_paper for granular control."
+                 fetch_paper, and analyze_paper for granular control. Fetched papers and \
+                 stored analyses are also readable as paper:// and analysis:// resources, \
+                 and prompts cover common workflows such as peer review."
                     .into(),
             ),
-            capabilities: ServerCapabilities::builder().enable_tools().build(),
+            capabilities: ServerCapabilities::builder()
+                .enable_tools()
+                .enable_resources()
+                .enable_prompts()
+                .build(),
             ..Default::default()
         }
     }
@@ -54,5 +93,55 @@ impl ServerHandler for PaperInterpreterService {
         self.peer = Some(peer);
     }
 
+    async fn list_resources(
+        &self,
+        _request: PaginatedRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListResourcesResult, McpError> {
+        Ok(ListResourcesResult {
+            resources: self.list_paper_resources().await,
+            next_cursor: None,
+        })
+    }
+
+    async fn list_resource_templates(
+        &self,
+        _request: PaginatedRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListResourceTemplatesResult, McpError> {
+        Ok(ListResourceTemplatesResult {
+            resource_templates: resource_templates(),
+            next_cursor: None,
+        })
+    }
+
+    async fn read_resource(
+        &self,
+        request: ReadResourceRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> Result<ReadResourceResult, McpError> {
+        self.read_paper_resource(&request.uri, &context.ct).await
+    }
+
+    async fn list_prompts(
+        &self,
+        _request: PaginatedRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListPromptsResult, McpError> {
+        Ok(ListPromptsResult {
+            prompts: prompt_list(),
+            next_cursor: None,
+        })
+    }
+
+    async fn get_prompt(
+        &self,
+        request: GetPromptRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> Result<GetPromptResult, McpError> {
+        self.render_prompt(&request.name, request.arguments.as_ref(), &context.ct)
+            .await
+    }
+
     rmcp::tool_box!(@derive tool_box);
 }
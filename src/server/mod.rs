@@ -1,4 +1,7 @@
+pub(crate) mod errors;
 pub mod handler;
+pub(crate) mod prompts;
+pub(crate) mod resources;
 pub mod tools;
 pub mod transport;
 
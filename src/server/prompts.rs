@@ -0,0 +1,263 @@
+use crate::models::paper::Paper;
+use crate::pipeline::truncate_chars;
+use crate::progress::Progress;
+use crate::server::handler::PaperInterpreterService;
+use crate::server::tools::fetch::PaperIdentifier;
+use crate::sources::ids::extract_arxiv_id;
+use rmcp::model::{
+    GetPromptResult, JsonObject, Prompt, PromptArgument, PromptMessage, PromptMessageRole,
+};
+use rmcp::Error as McpError;
+use serde_json::json;
+use tokio_util::sync::CancellationToken;
+
+/// Characters of full text embedded per paper, enough for most papers while
+/// keeping the prompt within common context windows
+const MAX_PROMPT_CONTENT_CHARS: usize = 120_000;
+
+/// A built-in prompt: the instructions given after the embedded paper(s)
+struct PromptTemplate {
+    name: &'static str,
+    description: &'static str,
+    /// Second paper argument, if the prompt takes one
+    extra_argument: Option<(&'static str, &'static str)>,
+    instructions: &'static str,
+}
+
+const ARXIV_ID_ARGUMENT: &str = "arxiv_id";
+
+const PROMPTS: &[PromptTemplate] = &[
+    PromptTemplate {
+        name: "explain_to_newcomer",
+        description: "Explain a paper to someone new to the field",
+        extra_argument: None,
+        instructions: "Explain this paper to a newcomer to the field. Start with the problem \
+            it addresses and why it matters, define the background concepts and jargon it \
+            relies on, then walk through the main idea and results in plain language. \
+            Use analogies where they help, and finish with what to read next.",
+    },
+    PromptTemplate {
+        name: "peer_review",
+        description: "Write a critical peer review of a paper",
+        extra_argument: None,
+        instructions: "Write a critical peer review of this paper as a reviewer for a \
+            top venue in its field. Cover: a summary of the claims, strengths, weaknesses \
+            (soundness of the method, experimental design, baselines, statistical rigor, \
+            clarity), questions for the authors, missing related work, and an overall \
+            recommendation with a confidence level. Cite specific sections or results.",
+    },
+    PromptTemplate {
+        name: "compare_with_baseline",
+        description: "Compare a paper with a baseline method or paper",
+        extra_argument: Some((
+            "baseline",
+            "Baseline to compare with: an arXiv ID or URL (its text is embedded), or a method name",
+        )),
+        instructions: "Compare the first paper with the baseline. Explain what the paper \
+            changes relative to the baseline, whether the comparison in its experiments \
+            is fair (same data, compute, tuning effort), where it improves and where it \
+            does not, and under which conditions a practitioner should prefer either one.",
+    },
+    PromptTemplate {
+        name: "reproducibility_checklist",
+        description: "Extract a reproducibility checklist from a paper",
+        extra_argument: None,
+        instructions: "Extract a reproducibility checklist from this paper. List datasets \
+            (with versions and splits), preprocessing, model architecture details, \
+            hyperparameters, training budget and hardware, evaluation metrics and \
+            protocol, random seeds and variance reporting, and released code or \
+            checkpoints. Mark each item as specified, partially specified, or missing, \
+            quoting the paper where it is specified.",
+    },
+];
+
+/// Prompts advertised to clients
+pub(crate) fn prompt_list() -> Vec<Prompt> {
+    PROMPTS
+        .iter()
+        .map(|template| {
+            let mut arguments = vec![PromptArgument {
+                name: ARXIV_ID_ARGUMENT.to_string(),
+                description: Some("arXiv ID or URL of the paper".to_string()),
+                required: Some(true),
+            }];
+            if let Some((name, description)) = template.extra_argument {
+                arguments.push(PromptArgument {
+                    name: name.to_string(),
+                    description: Some(description.to_string()),
+                    required: Some(true),
+                });
+            }
+            Prompt::new(template.name, Some(template.description), Some(arguments))
+        })
+        .collect()
+}
+
+/// Read a required string argument
+fn required_argument<'a>(
+    arguments: Option<&'a JsonObject>,
+    name: &str,
+) -> Result<&'a str, McpError> {
+    arguments
+        .and_then(|args| args.get(name))
+        .and_then(|value| value.as_str())
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .ok_or_else(|| {
+            McpError::invalid_params(format!("Missing required argument: {}", name), None)
+        })
+}
+
+/// arXiv ID from a bare ID or an arXiv URL
+fn arxiv_identifier(value: &str) -> PaperIdentifier {
+    PaperIdentifier::Arxiv(extract_arxiv_id(value).unwrap_or_else(|| value.to_string()))
+}
+
+/// Paper metadata followed by its (truncated) full text
+fn paper_block(label: &str, paper: &Paper) -> String {
+    let mut block = format!(
+        "## {}: {}\nAuthors: {}\n",
+        label,
+        paper.title,
+        paper.authors.join(", ")
+    );
+    if let Some(ref id) = paper.arxiv_id {
+        block.push_str(&format!("arXiv: {}\n", id));
+    }
+    if let Some(ref date) = paper.published_date {
+        block.push_str(&format!("Published: {}\n", date));
+    }
+    block.push_str(&format!("\nAbstract:\n{}\n", paper.abstract_text));
+    match paper.content {
+        Some(ref content) => {
+            let text = truncate_chars(content, MAX_PROMPT_CONTENT_CHARS);
+            block.push_str(&format!("\nFull text:\n{}\n", text));
+            if text.len() < content.len() {
+                block.push_str("\n[Full text truncated]\n");
+            }
+        }
+        None => block.push_str("\n[Full text unavailable; only the abstract is included]\n"),
+    }
+    block
+}
+
+impl PaperInterpreterService {
+    /// Render a built-in prompt with the requested paper(s) embedded
+    pub(crate) async fn render_prompt(
+        &self,
+        name: &str,
+        arguments: Option<&JsonObject>,
+        ct: &CancellationToken,
+    ) -> Result<GetPromptResult, McpError> {
+        let template = PROMPTS.iter().find(|t| t.name == name).ok_or_else(|| {
+            McpError::invalid_params(
+                format!("Unknown prompt: {}", name),
+                Some(json!({ "available": PROMPTS.iter().map(|t| t.name).collect::<Vec<_>>() })),
+            )
+        })?;
+
+        let arxiv_id = required_argument(arguments, ARXIV_ID_ARGUMENT)?;
+        let baseline = template
+            .extra_argument
+            .map(|(argument, _)| required_argument(arguments, argument))
+            .transpose()?;
+        let paper = self
+            .load_paper(
+                &arxiv_identifier(arxiv_id),
+                true,
+                false,
+                &Progress::none(),
+                ct,
+            )
+            .await?;
+        let mut text = paper_block("Paper", &paper);
+
+        if let Some(baseline) = baseline {
+            // A baseline that is not an arXiv paper is compared from the model's own knowledge
+            match extract_arxiv_id(baseline) {
+                Some(id) => {
+                    let baseline_paper = self
+                        .load_paper(
+                            &PaperIdentifier::Arxiv(id),
+                            true,
+                            false,
+                            &Progress::none(),
+                            ct,
+                        )
+                        .await?;
+                    text.push('\n');
+                    text.push_str(&paper_block("Baseline", &baseline_paper));
+                }
+                None => text.push_str(&format!("\n## Baseline: {}\n", baseline)),
+            }
+        }
+
+        text.push_str("\n---\n\n");
+        text.push_str(template.instructions);
+
+        Ok(GetPromptResult {
+            description: Some(format!("{}: {}", template.description, paper.title)),
+            messages: vec![PromptMessage::new_text(PromptMessageRole::User, text)],
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prompt_list() {
+        let prompts = prompt_list();
+        assert_eq!(prompts.len(), PROMPTS.len());
+        for prompt in &prompts {
+            let arguments = prompt.arguments.as_ref().unwrap();
+            assert_eq!(arguments[0].name, ARXIV_ID_ARGUMENT);
+        }
+        let compare = prompts
+            .iter()
+            .find(|p| p.name == "compare_with_baseline")
+            .unwrap();
+        assert_eq!(compare.arguments.as_ref().unwrap()[1].name, "baseline");
+    }
+
+    #[test]
+    fn test_required_argument() {
+        let args: JsonObject = serde_json::from_value(json!({
+            "arxiv_id": " 1706.03762 ",
+            "baseline": "",
+        }))
+        .unwrap();
+
+        assert_eq!(
+            required_argument(Some(&args), "arxiv_id").unwrap(),
+            "1706.03762"
+        );
+        assert!(required_argument(Some(&args), "baseline").is_err());
+        assert!(required_argument(None, "arxiv_id").is_err());
+        assert_eq!(
+            arxiv_identifier("https://arxiv.org/abs/1706.03762v5"),
+            PaperIdentifier::Arxiv("1706.03762".to_string())
+        );
+    }
+
+    #[test]
+    fn test_paper_block_truncates_content() {
+        let paper = Paper {
+            title: "Attention Is All You Need".to_string(),
+            authors: vec!["Ashish Vaswani".to_string()],
+            abstract_text: "Abstract".to_string(),
+            arxiv_id: Some("1706.03762".to_string()),
+            ss_id: None,
+            categories: vec![],
+            published_date: None,
+            pdf_url: None,
+            content: Some("x".repeat(MAX_PROMPT_CONTENT_CHARS + 1)),
+            structure: None,
+        };
+
+        let block = paper_block("Paper", &paper);
+        assert!(block.starts_with("## Paper: Attention Is All You Need\n"));
+        assert!(block.ends_with("[Full text truncated]\n"));
+    }
+}
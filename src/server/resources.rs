@@ -0,0 +1,456 @@
+use crate::cache::PaperKey;
+use crate::models::request::ContentMode;
+use crate::pipeline::sections::split_sections;
+use crate::progress::Progress;
+use crate::server::handler::PaperInterpreterService;
+use crate::server::tools::fetch::{apply_content_mode, PaperIdentifier};
+use crate::sources::source_named;
+use rmcp::model::{
+    AnnotateAble, RawResource, RawResourceTemplate, ReadResourceResult, Resource, ResourceContents,
+    ResourceTemplate,
+};
+use rmcp::Error as McpError;
+use serde_json::json;
+use tokio_util::sync::CancellationToken;
+
+const JSON_MIME_TYPE: &str = "application/json";
+const TEXT_MIME_TYPE: &str = "text/plain";
+
+/// A parsed `paper://` or `analysis://` URI
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum ResourceUri {
+    /// `paper://{source}/{id}`: metadata and abstract as JSON
+    Paper(PaperIdentifier),
+    /// `paper://{source}/{id}/content`: full extracted text
+    Content(PaperIdentifier),
+    /// `paper://{source}/{id}/sections/{name}`: sections whose heading matches `name`
+    Section(PaperIdentifier, String),
+    /// `analysis://{source}/{id}/{model}`: latest stored analysis by `model`
+    Analysis(PaperIdentifier, String),
+}
+
+impl ResourceUri {
+    /// Parse a resource URI; `source` is `arxiv`, `s2`, or a paper source name such as `doi`
+    pub(crate) fn parse(uri: &str) -> Option<Self> {
+        if let Some(rest) = uri.strip_prefix("paper://") {
+            let (identifier, rest) = split_identifier(rest)?;
+            return match rest {
+                None => Some(Self::Paper(identifier)),
+                Some("content") => Some(Self::Content(identifier)),
+                Some(rest) => {
+                    let name = rest.strip_prefix("sections/")?;
+                    let name = percent_decode(name);
+                    (!name.is_empty()).then_some(Self::Section(identifier, name))
+                }
+            };
+        }
+
+        if let Some(rest) = uri.strip_prefix("analysis://") {
+            let (identifier, model) = split_identifier(rest)?;
+            let model = percent_decode(model?);
+            return (!model.is_empty()).then_some(Self::Analysis(identifier, model));
+        }
+
+        None
+    }
+}
+
+/// Split `{source}/{id}[/{rest}]`, allowing old-style arXiv IDs such as `hep-th/9901001`
+fn split_identifier(path: &str) -> Option<(PaperIdentifier, Option<&str>)> {
+    let (source, rest) = path.split_once('/')?;
+    let (id, rest) = match source {
+        "arxiv" => {
+            let segments = if is_old_style_archive(rest.split('/').next()?) {
+                2
+            } else {
+                1
+            };
+            split_segments(rest, segments)?
+        }
+        _ => split_segments(rest, 1)?,
+    };
+    let id = percent_decode(id);
+    if id.is_empty() {
+        return None;
+    }
+
+    let identifier = match source {
+        "arxiv" => PaperIdentifier::Arxiv(id),
+        "s2" => PaperIdentifier::SemanticScholar(id),
+        // IDs appear in their cache-key form, e.g., doi/10.1145_3442188.3445922
+        name => {
+            let source = source_named(name)?;
+            PaperIdentifier::Source(source.name(), source.id_from_key(&id))
+        }
+    };
+    Some((identifier, rest.filter(|r| !r.is_empty())))
+}
+
+/// Split off the first `n` path segments
+fn split_segments(path: &str, n: usize) -> Option<(&str, Option<&str>)> {
+    let mut end = 0;
+    for i in 0..n {
+        if i > 0 {
+            end += 1;
+        }
+        if end > path.len() {
+            return None;
+        }
+        end += path[end..].find('/').unwrap_or(path.len() - end);
+    }
+    match path.get(end + 1..) {
+        Some(rest) => Some((&path[..end], Some(rest))),
+        None => Some((&path[..end], None)),
+    }
+}
+
+/// Old-style arXiv archives are lowercase names like `hep-th` or `math`
+fn is_old_style_archive(segment: &str) -> bool {
+    !segment.is_empty() && segment.chars().all(|c| c.is_ascii_lowercase() || c == '-')
+}
+
+/// Decode `%XX` escapes (e.g., `%20` in section names); invalid escapes are kept as-is
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%'
+            && let Some(hex) = text.get(i + 1..i + 3)
+            && let Ok(byte) = u8::from_str_radix(hex, 16)
+        {
+            out.push(byte);
+            i += 3;
+            continue;
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// Encode every byte outside the URI unreserved set, so a segment decodes back to `text`
+fn percent_encode(text: &str) -> String {
+    text.bytes()
+        .map(|b| {
+            if b.is_ascii_alphanumeric() || matches!(b, b'-' | b'.' | b'_' | b'~') {
+                (b as char).to_string()
+            } else {
+                format!("%{:02X}", b)
+            }
+        })
+        .collect()
+}
+
+/// Base `source/id` path of a URI for a cached paper key
+fn uri_path(key: &PaperKey) -> String {
+    match key.source() {
+        // Cache keys store the '/' of old-style IDs as '_'
+        "arxiv" if key.id().contains('_') => {
+            let (archive, number) = key.id().split_once('_').unwrap_or((key.id(), ""));
+            format!(
+                "arxiv/{}/{}",
+                percent_encode(archive),
+                percent_encode(number)
+            )
+        }
+        source => format!("{}/{}", source, percent_encode(key.id())),
+    }
+}
+
+/// `analysis://` URI of the analysis of `key` by `model`
+///
+/// Model names often contain '/' or ':' (e.g., `meta-llama/Llama-3-8b`), so
+/// the name is encoded as a single segment.
+fn analysis_uri(key: &PaperKey, model: &str) -> String {
+    format!("analysis://{}/{}", uri_path(key), percent_encode(model))
+}
+
+/// Resource templates advertised to clients
+pub(crate) fn resource_templates() -> Vec<ResourceTemplate> {
+    let template = |uri_template: &str, name: &str, description: &str, mime_type: &str| {
+        RawResourceTemplate {
+            uri_template: uri_template.to_string(),
+            name: name.to_string(),
+            description: Some(description.to_string()),
+            mime_type: Some(mime_type.to_string()),
+        }
+        .no_annotation()
+    };
+
+    vec![
+        template(
+            "paper://arxiv/{id}",
+            "arXiv paper",
+            "Metadata and abstract of an arXiv paper",
+            JSON_MIME_TYPE,
+        ),
+        template(
+            "paper://arxiv/{id}/content",
+            "arXiv paper full text",
+            "Full text extracted from the paper's PDF",
+            TEXT_MIME_TYPE,
+        ),
+        template(
+            "paper://arxiv/{id}/sections/{name}",
+            "arXiv paper section",
+            "Sections whose heading contains {name} (case-insensitive, URL-encoded)",
+            TEXT_MIME_TYPE,
+        ),
+        template(
+            "analysis://arxiv/{id}/{model}",
+            "Paper analysis",
+            "Latest stored analyze_paper result for the paper by {model}",
+            JSON_MIME_TYPE,
+        ),
+        template(
+            "paper://s2/{id}",
+            "Semantic Scholar paper",
+            "Metadata and abstract of a paper by Semantic Scholar ID",
+            JSON_MIME_TYPE,
+        ),
+        template(
+            "paper://s2/{id}/content",
+            "Semantic Scholar paper full text",
+            "Full text extracted from the paper's PDF",
+            TEXT_MIME_TYPE,
+        ),
+        template(
+            "paper://{source}/{id}",
+            "Paper from another source",
+            "Paper from doi, openreview, acl, pubmed, or biorxiv; '/' in the ID is written as '_' (e.g., paper://doi/10.1145_3442188.3445922)",
+            JSON_MIME_TYPE,
+        ),
+    ]
+}
+
+impl PaperInterpreterService {
+    /// Resources for every cached paper and stored analysis
+    pub(crate) async fn list_paper_resources(&self) -> Vec<Resource> {
+        let mut resources = Vec::new();
+
+        for (key, listing) in self.paper_cache().listings().await {
+            let path = uri_path(&key);
+
+            let mut metadata = RawResource::new(format!("paper://{}", path), listing.title.clone());
+            metadata.description = Some("Paper metadata and abstract".to_string());
+            metadata.mime_type = Some(JSON_MIME_TYPE.to_string());
+            resources.push(metadata.no_annotation());
+
+            if let Some(content_len) = listing.content_len {
+                let mut full_text = RawResource::new(
+                    format!("paper://{}/content", path),
+                    format!("{} (full text)", listing.title),
+                );
+                full_text.description = Some("Full text extracted from the PDF".to_string());
+                full_text.mime_type = Some(TEXT_MIME_TYPE.to_string());
+                full_text.size = u32::try_from(content_len).ok();
+                resources.push(full_text.no_annotation());
+            }
+        }
+
+        for (key, model) in self.analysis_cache().latest_entries().await {
+            let mut analysis = RawResource::new(
+                analysis_uri(&key, &model),
+                format!("Analysis of {} by {}", key.id(), model),
+            );
+            analysis.mime_type = Some(JSON_MIME_TYPE.to_string());
+            resources.push(analysis.no_annotation());
+        }
+
+        resources
+    }
+
+    /// Read a `paper://` or `analysis://` resource, fetching the paper if needed
+    pub(crate) async fn read_paper_resource(
+        &self,
+        uri: &str,
+        ct: &CancellationToken,
+    ) -> Result<ReadResourceResult, McpError> {
+        let parsed = ResourceUri::parse(uri).ok_or_else(|| {
+            McpError::invalid_params(
+                format!(
+                    "Unsupported resource URI: {}. Expected paper://arxiv/{{id}}[/content|/sections/{{name}}] or analysis://arxiv/{{id}}/{{model}}",
+                    uri
+                ),
+                None,
+            )
+        })?;
+
+        let (text, mime_type) = match parsed {
+            ResourceUri::Paper(identifier) => {
+                let mut paper = self
+                    .load_paper(&identifier, false, false, &Progress::none(), ct)
+                    .await?;
+                paper.content = None;
+                paper.structure = None;
+                let json = serde_json::to_string_pretty(&paper).map_err(|e| {
+                    McpError::internal_error(format!("Serialization error: {}", e), None)
+                })?;
+                (json, JSON_MIME_TYPE)
+            }
+            ResourceUri::Content(identifier) => {
+                let paper = self
+                    .load_paper(&identifier, true, false, &Progress::none(), ct)
+                    .await?;
+                let content = paper.content.ok_or_else(|| {
+                    McpError::resource_not_found(
+                        format!("No full text available for {}", uri),
+                        None,
+                    )
+                })?;
+                (content, TEXT_MIME_TYPE)
+            }
+            ResourceUri::Section(identifier, name) => {
+                let mut paper = self
+                    .load_paper(&identifier, true, false, &Progress::none(), ct)
+                    .await?;
+                let headings: Vec<String> = split_sections(paper.content.as_deref().unwrap_or(""))
+                    .into_iter()
+                    .map(|s| s.heading)
+                    .collect();
+                apply_content_mode(&mut paper, &ContentMode::Sections(vec![name.clone()]));
+                match paper.content.filter(|c| !c.is_empty()) {
+                    Some(text) => (text, TEXT_MIME_TYPE),
+                    None => {
+                        return Err(McpError::resource_not_found(
+                            format!("No section matching \"{}\" in {}", name, uri),
+                            Some(json!({ "available_sections": headings })),
+                        ));
+                    }
+                }
+            }
+            ResourceUri::Analysis(identifier, model) => {
+                let stored = self
+                    .analysis_cache()
+                    .get_latest(&identifier.cache_key(), &model)
+                    .await
+                    .ok_or_else(|| {
+                        McpError::resource_not_found(
+                            format!(
+                                "No stored analysis for {} by {}; run analyze_paper with that model first",
+                                uri, model
+                            ),
+                            None,
+                        )
+                    })?;
+                let json = serde_json::to_string_pretty(&json!({
+                    "analysis": stored.value,
+                    "analyzed_at": stored.cached_at.to_rfc3339(),
+                }))
+                .map_err(|e| {
+                    McpError::internal_error(format!("Serialization error: {}", e), None)
+                })?;
+                (json, JSON_MIME_TYPE)
+            }
+        };
+
+        Ok(ReadResourceResult {
+            contents: vec![ResourceContents::TextResourceContents {
+                uri: uri.to_string(),
+                mime_type: Some(mime_type.to_string()),
+                text,
+            }],
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_resource_uri() {
+        let arxiv = |id: &str| PaperIdentifier::Arxiv(id.to_string());
+
+        assert_eq!(
+            ResourceUri::parse("paper://arxiv/1706.03762"),
+            Some(ResourceUri::Paper(arxiv("1706.03762")))
+        );
+        assert_eq!(
+            ResourceUri::parse("paper://arxiv/1706.03762v5/content"),
+            Some(ResourceUri::Content(arxiv("1706.03762v5")))
+        );
+        assert_eq!(
+            ResourceUri::parse("paper://arxiv/1706.03762/sections/Related%20Work"),
+            Some(ResourceUri::Section(
+                arxiv("1706.03762"),
+                "Related Work".to_string()
+            ))
+        );
+        assert_eq!(
+            ResourceUri::parse("paper://arxiv/hep-th/9901001/content"),
+            Some(ResourceUri::Content(arxiv("hep-th/9901001")))
+        );
+        assert_eq!(
+            ResourceUri::parse("analysis://arxiv/1706.03762/meta-llama/Llama-3"),
+            Some(ResourceUri::Analysis(
+                arxiv("1706.03762"),
+                "meta-llama/Llama-3".to_string()
+            ))
+        );
+        assert_eq!(
+            ResourceUri::parse("paper://s2/abc123def456"),
+            Some(ResourceUri::Paper(PaperIdentifier::SemanticScholar(
+                "abc123def456".to_string()
+            )))
+        );
+
+        assert_eq!(
+            ResourceUri::parse("paper://doi/10.1145_3442188.3445922/content"),
+            Some(ResourceUri::Content(PaperIdentifier::Source(
+                "doi",
+                "10.1145/3442188.3445922".to_string()
+            )))
+        );
+
+        assert_eq!(ResourceUri::parse("paper://arxiv/"), None);
+        assert_eq!(ResourceUri::parse("paper://unknown/123"), None);
+        assert_eq!(ResourceUri::parse("paper://arxiv/1706.03762/figures"), None);
+        assert_eq!(ResourceUri::parse("analysis://arxiv/1706.03762"), None);
+        assert_eq!(ResourceUri::parse("file:///etc/passwd"), None);
+    }
+
+    #[test]
+    fn test_uri_path() {
+        assert_eq!(uri_path(&PaperKey::arxiv("1706.03762")), "arxiv/1706.03762");
+        assert_eq!(
+            uri_path(&PaperKey::arxiv("hep-th/9901001")),
+            "arxiv/hep-th/9901001"
+        );
+    }
+
+    #[test]
+    fn test_analysis_uri_round_trip() {
+        let keys = [
+            PaperKey::arxiv("1706.03762"),
+            PaperKey::arxiv("hep-th/9901001"),
+            PaperKey::semantic_scholar("CorpusID:215416146"),
+            PaperKey::from_source("doi", "10.1145/3442188.3445922"),
+        ];
+        let models = [
+            "meta-llama/Llama-3-8b",
+            "llama3:8b",
+            "gpt-4o",
+            "my model %1",
+        ];
+
+        for key in &keys {
+            for model in models {
+                let uri = analysis_uri(key, model);
+                match ResourceUri::parse(&uri) {
+                    Some(ResourceUri::Analysis(identifier, parsed)) => {
+                        assert_eq!(identifier.cache_key(), *key, "{}", uri);
+                        assert_eq!(parsed, model, "{}", uri);
+                    }
+                    other => panic!("{} parsed as {:?}", uri, other),
+                }
+            }
+        }
+
+        assert_eq!(
+            analysis_uri(&PaperKey::arxiv("1706.03762"), "llama3:8b"),
+            "analysis://arxiv/1706.03762/llama3%3A8b"
+        );
+    }
+}
@@ -1,22 +1,61 @@
-use crate::llm::{create_analyzer, AnalyzerType};
-use crate::models::analysis::PaperAnalysis;
+use crate::cache::{AnalysisKey, PaperKey};
+use crate::llm::ChatClient;
+use crate::models::analysis::AnalysisType;
+use crate::models::paper::{Paper, Reference};
 use crate::models::request::AnalyzePaperRequest;
 use crate::models::response::AnalyzePaperResponse;
+use crate::pipeline::structure::add_structure;
+use crate::pipeline::{analyze_sections, compare_papers, summarize};
+use crate::progress::Progress;
+use crate::server::errors::mcp_error;
 use crate::server::handler::PaperInterpreterService;
-use academic_paper_interpreter::agents::AnalysisAgent;
-use academic_paper_interpreter::models::{AcademicPaper, Author};
-use chrono::Local;
+use chrono::Utc;
 use rmcp::model::{CallToolResult, Content};
 use rmcp::tool;
-use rmcp::Error as McpError;
+use rmcp::{Error as McpError, ServerHandler};
+use tokio_util::sync::CancellationToken;
 
 impl PaperInterpreterService {
-    #[tool(description = "Analyze a paper using an LLM to generate summary and insights")]
+    #[tool(
+        description = "Analyze a paper using an LLM. analysis_type: summary (default), detailed (per-section deep dive), or comparison (against the papers in compare_with)"
+    )]
     pub async fn analyze_paper(
         &self,
+        ct: CancellationToken,
         #[tool(aggr)] request: AnalyzePaperRequest,
     ) -> Result<CallToolResult, McpError> {
-        tracing::info!("Analyzing paper: {}", request.paper.title);
+        let progress = Progress::new(self.get_peer(), request.progress.progress_token.clone());
+        let response = self.analyze_paper_response(request, &progress, &ct).await?;
+
+        let json = serde_json::to_string_pretty(&response)
+            .map_err(|e| McpError::internal_error(format!("Serialization error: {}", e), None))?;
+
+        Ok(CallToolResult::success(vec![Content::text(json)]))
+    }
+}
+
+impl PaperInterpreterService {
+    /// analyze_paper without serializing the result, reporting stages to `progress`
+    pub(crate) async fn analyze_paper_response(
+        &self,
+        request: AnalyzePaperRequest,
+        progress: &Progress,
+        ct: &CancellationToken,
+    ) -> Result<AnalyzePaperResponse, McpError> {
+        tracing::info!(
+            "Analyzing paper ({:?}): {}",
+            request.analysis_type,
+            request.paper.title
+        );
+
+        if matches!(request.analysis_type, AnalysisType::Comparison)
+            && request.compare_with.is_empty()
+        {
+            return Err(McpError::invalid_params(
+                "compare_with must contain at least one paper for comparison analysis",
+                None,
+            ));
+        }
 
         // Resolve LLM configuration
         let config = self
@@ -26,71 +65,98 @@ impl PaperInterpreterService {
         // Validate API key availability
         self.llm_config_resolver()
             .validate_api_key(&config)
-            .map_err(|e| McpError::internal_error(e.to_string(), None))?;
+            .map_err(mcp_error)?;
 
-        // Create analyzer with the configured provider
-        let analyzer = create_analyzer(&config).map_err(|e| {
-            McpError::internal_error(format!("Failed to create analyzer: {}", e), None)
-        })?;
+        // Serve a stored analysis of the same content, model, and prompts
+        let key_papers: Vec<Paper> = match request.analysis_type {
+            AnalysisType::Comparison => std::iter::once(request.paper.clone())
+                .chain(request.compare_with.iter().cloned())
+                .collect(),
+            _ => vec![request.paper.clone()],
+        };
+        let cache_key = AnalysisKey::new(&key_papers, &config, &request.analysis_type);
+        if !request.force_refresh
+            && let Some(ref cache_key) = cache_key
+            && let Some(stored) = self.analysis_cache().get(cache_key).await
+        {
+            tracing::info!("Analysis cache hit for \"{}\"", request.paper.title);
+            return Ok(AnalyzePaperResponse {
+                analysis: stored.value,
+                cached: true,
+                analyzed_at: Some(stored.cached_at.to_rfc3339()),
+            });
+        }
 
-        // Convert our Paper to the library's AcademicPaper using the builder or new
-        let mut academic_paper = AcademicPaper::default();
-        academic_paper.title = request.paper.title.clone();
-        academic_paper.authors = request
-            .paper
-            .authors
-            .iter()
-            .map(|name| Author {
-                name: name.clone(),
-                ss_id: String::new(),
-                h_index: 0,
-                affiliations: vec![],
-                paper_count: 0,
-                citation_count: 0,
-            })
-            .collect();
-        academic_paper.abstract_text = request.paper.abstract_text.clone();
-        academic_paper.arxiv_id = request.paper.arxiv_id.clone().unwrap_or_default();
-        academic_paper.ss_id = request.paper.ss_id.clone().unwrap_or_default();
-        academic_paper.url = request.paper.pdf_url.clone().unwrap_or_default();
-        academic_paper.text = request.paper.content.clone().unwrap_or_default();
-        academic_paper.published_date = Local::now();
+        // Every pipeline talks to the provider and model from the resolved config
+        let chat = ChatClient::from_config(&config)
+            .map_err(mcp_error)?
+            .with_peer(self.get_peer())
+            .with_progress(progress.clone())
+            .with_cancellation(ct.clone());
 
-        // Execute analysis based on provider type
-        let lib_analysis = match analyzer {
-            AnalyzerType::OpenAi(a) => a.analyze(&academic_paper).await.map_err(|e| {
-                McpError::internal_error(format!("Analysis failed: {}", e), None)
-            })?,
-            AnalyzerType::Anthropic(a) => a.analyze(&academic_paper).await.map_err(|e| {
-                McpError::internal_error(format!("Analysis failed: {}", e), None)
-            })?,
-            AnalyzerType::Ollama(a) => a.analyze(&academic_paper).await.map_err(|e| {
-                McpError::internal_error(format!("Analysis failed: {}", e), None)
-            })?,
-        };
+        // Every analysis type starts from the summary
+        let mut analysis = summarize(&chat, &request.paper).await.map_err(mcp_error)?;
 
-        // Convert to our response format
-        let analysis = PaperAnalysis {
-            summary: lib_analysis.summary,
-            key_contributions: lib_analysis.key_contributions,
-            methodology: if lib_analysis.methodology.is_empty() {
-                None
-            } else {
-                Some(lib_analysis.methodology)
-            },
-            limitations: if lib_analysis.advantages_limitations_and_future_work.is_empty() {
-                vec![]
-            } else {
-                vec![lib_analysis.advantages_limitations_and_future_work]
-            },
-            related_work: vec![],
-        };
+        match request.analysis_type {
+            AnalysisType::Summary => {}
+            AnalysisType::Detailed => {
+                analysis.sections = analyze_sections(&chat, &request.paper)
+                    .await
+                    .map_err(mcp_error)?;
+            }
+            AnalysisType::Comparison => {
+                analysis.comparison = Some(
+                    compare_papers(&chat, &key_papers)
+                        .await
+                        .map_err(mcp_error)?,
+                );
+            }
+        }
 
-        let response = AnalyzePaperResponse { analysis };
+        // Related work is what the paper cites. Summaries list the parsed
+        // references as they are; the deeper analyses also match them to
+        // catalog papers, which costs Semantic Scholar calls
+        let mut paper = request.paper.clone();
+        if matches!(request.analysis_type, AnalysisType::Summary) {
+            add_structure(&mut paper);
+        } else {
+            self.resolve_paper_references(&mut paper, progress, ct)
+                .await?;
+        }
+        analysis.related_work = paper
+            .structure
+            .map(|s| s.references.iter().map(Reference::citation).collect())
+            .unwrap_or_default();
 
-        let json = serde_json::to_string_pretty(&response)
-            .map_err(|e| McpError::internal_error(format!("Serialization error: {}", e), None))?;
+        progress.stage("Merging and storing the analysis").await;
+        // Sampling results have no key, so they are neither stored nor indexed
+        let analyzed_at = match cache_key {
+            Some(ref cache_key) => self
+                .analysis_cache()
+                .put(cache_key, &analysis)
+                .await
+                .unwrap_or_else(|e| {
+                    tracing::warn!("Failed to store analysis: {}", e);
+                    Utc::now()
+                }),
+            None => Utc::now(),
+        };
 
-        Ok(CallToolResult::success(vec![Content::text(json)]))
+        // Index it for the analysis://{source}/{id}/{model} resource
+        if cache_key.is_some()
+            && let Some(paper_key) = PaperKey::for_paper(&request.paper)
+            && let Err(e) = self
+                .analysis_cache()
+                .put_latest(&paper_key, &config.effective_model(), &analysis)
+                .await
+        {
+            tracing::warn!("Failed to record latest analysis: {}", e);
+        }
+
+        Ok(AnalyzePaperResponse {
+            analysis,
+            cached: false,
+            analyzed_at: Some(analyzed_at.to_rfc3339()),
+        })
     }
 }
@@ -0,0 +1,72 @@
+use crate::llm::ChatClient;
+use crate::models::request::AskPaperRequest;
+use crate::models::response::AskPaperResponse;
+use crate::pipeline::answer_question;
+use crate::progress::Progress;
+use crate::server::errors::mcp_error;
+use crate::server::handler::PaperInterpreterService;
+use rmcp::model::{CallToolResult, Content};
+use rmcp::tool;
+use rmcp::{Error as McpError, ServerHandler};
+use tokio_util::sync::CancellationToken;
+
+/// Most passages ask_paper gives the LLM
+const MAX_PASSAGES: usize = 20;
+
+impl PaperInterpreterService {
+    #[tool(
+        description = "Answer a question about a paper using an LLM. Retrieves the passages of the full text most relevant to the question and returns the answer with verbatim evidence quotes, each with its section heading and character offsets in that section's text"
+    )]
+    pub async fn ask_paper(
+        &self,
+        ct: CancellationToken,
+        #[tool(aggr)] request: AskPaperRequest,
+    ) -> Result<CallToolResult, McpError> {
+        if request.question.trim().is_empty() {
+            return Err(McpError::invalid_params("question must not be empty", None));
+        }
+        let progress = Progress::new(self.get_peer(), request.progress.progress_token.clone());
+
+        let config = self
+            .llm_config_resolver()
+            .resolve(request.llm_config.as_ref());
+        self.llm_config_resolver()
+            .validate_api_key(&config)
+            .map_err(mcp_error)?;
+
+        let paper = self
+            .load_requested_paper(
+                &request.locator.fetch_request(request.force_refresh),
+                true,
+                &progress,
+                &ct,
+            )
+            .await?;
+        if paper.structure.is_none() {
+            tracing::warn!(
+                "No full text for \"{}\"; answering from the abstract",
+                paper.title
+            );
+        }
+
+        let chat = ChatClient::from_config(&config)
+            .map_err(mcp_error)?
+            .with_peer(self.get_peer())
+            .with_progress(progress.clone())
+            .with_cancellation(ct.clone());
+        let passages = request.passages.clamp(1, MAX_PASSAGES);
+        let answer = answer_question(&chat, &paper, &request.question, passages)
+            .await
+            .map_err(mcp_error)?;
+
+        let response = AskPaperResponse {
+            title: paper.title,
+            question: request.question,
+            answer,
+        };
+        let json = serde_json::to_string_pretty(&response)
+            .map_err(|e| McpError::internal_error(format!("Serialization error: {}", e), None))?;
+
+        Ok(CallToolResult::success(vec![Content::text(json)]))
+    }
+}
@@ -0,0 +1,77 @@
+use crate::llm::ChatClient;
+use crate::models::request::ComparePapersRequest;
+use crate::models::response::ComparePapersResponse;
+use crate::pipeline::comparison_matrix;
+use crate::progress::Progress;
+use crate::server::errors::mcp_error;
+use crate::server::handler::PaperInterpreterService;
+use futures::future::try_join_all;
+use rmcp::model::{CallToolResult, Content};
+use rmcp::tool;
+use rmcp::{Error as McpError, ServerHandler};
+use tokio_util::sync::CancellationToken;
+
+/// Fewest and most papers compare_papers accepts
+const MIN_PAPERS: usize = 2;
+const MAX_PAPERS: usize = 10;
+
+impl PaperInterpreterService {
+    #[tool(
+        description = "Compare 2 to 10 papers using an LLM. Fetches them concurrently and returns a matrix of task, method, datasets, metrics, reported results, assumptions, and limitations per paper, plus a narrative of where the papers agree and disagree"
+    )]
+    pub async fn compare_papers(
+        &self,
+        ct: CancellationToken,
+        #[tool(aggr)] request: ComparePapersRequest,
+    ) -> Result<CallToolResult, McpError> {
+        if !(MIN_PAPERS..=MAX_PAPERS).contains(&request.papers.len()) {
+            return Err(McpError::invalid_params(
+                format!(
+                    "papers must list {} to {} papers, got {}",
+                    MIN_PAPERS,
+                    MAX_PAPERS,
+                    request.papers.len()
+                ),
+                None,
+            ));
+        }
+        let progress = Progress::new(self.get_peer(), request.progress.progress_token.clone());
+
+        let config = self
+            .llm_config_resolver()
+            .resolve(request.llm_config.as_ref());
+        self.llm_config_resolver()
+            .validate_api_key(&config)
+            .map_err(mcp_error)?;
+
+        progress
+            .stage(&format!("Fetching {} papers", request.papers.len()))
+            .await;
+        let fetches = request.fetch_requests();
+        let cancel = &ct;
+        let papers = try_join_all(request.papers.iter().zip(&fetches).map(
+            |(identifier, fetch)| async move {
+                self.load_requested_paper(fetch, true, &Progress::none(), cancel)
+                    .await
+                    .map_err(|mut e| {
+                        e.message = format!("{}: {}", identifier, e.message).into();
+                        e
+                    })
+            },
+        ))
+        .await?;
+
+        let chat = ChatClient::from_config(&config)
+            .map_err(mcp_error)?
+            .with_peer(self.get_peer())
+            .with_progress(progress.clone())
+            .with_cancellation(ct.clone());
+        let comparison = comparison_matrix(&chat, &papers).await.map_err(mcp_error)?;
+
+        let response = ComparePapersResponse { comparison };
+        let json = serde_json::to_string_pretty(&response)
+            .map_err(|e| McpError::internal_error(format!("Serialization error: {}", e), None))?;
+
+        Ok(CallToolResult::success(vec![Content::text(json)]))
+    }
+}
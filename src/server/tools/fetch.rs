@@ -1,235 +1,433 @@
+use crate::cache::PaperKey;
 use crate::llm::create_paper_client;
 use crate::models::paper::Paper;
-use crate::models::request::FetchPaperRequest;
+use crate::models::request::{ContentMode, FetchPaperRequest};
 use crate::models::response::FetchPaperResponse;
+use crate::pipeline::sections::split_sections;
+use crate::pipeline::structure::add_structure;
+use crate::pipeline::truncate_chars;
+use crate::progress::Progress;
+use crate::server::errors::{cancelled_error, mcp_error};
 use crate::server::handler::PaperInterpreterService;
+use crate::sources::ids::{extract_arxiv_id, extract_ss_id};
+use crate::sources::pdf::{
+    download_pdf, extract_pdf_text, paper_from_pdf, read_pdf_file, PdfSource,
+};
+use crate::sources::{find_source, source_named, ArxivClient, SemanticScholarClient};
+use academic_paper_interpreter::models::AcademicPaper;
 use rmcp::model::{CallToolResult, Content};
 use rmcp::tool;
-use rmcp::Error as McpError;
+use rmcp::{Error as McpError, ServerHandler};
+use shared::errors::AppError;
+use tokio_util::sync::CancellationToken;
 
 impl PaperInterpreterService {
-    #[tool(description = "Fetch a paper's metadata and optionally its PDF content")]
+    #[tool(
+        description = "Fetch a paper's metadata and optionally its PDF content. Use content_mode to control how much text is returned"
+    )]
     pub async fn fetch_paper(
         &self,
+        ct: CancellationToken,
         #[tool(aggr)] request: FetchPaperRequest,
     ) -> Result<CallToolResult, McpError> {
+        let progress = Progress::new(self.get_peer(), request.progress.progress_token.clone());
+        let response = self.fetch_paper_response(request, &progress, &ct).await?;
+
+        let json = serde_json::to_string_pretty(&response)
+            .map_err(|e| McpError::internal_error(format!("Serialization error: {}", e), None))?;
+
+        Ok(CallToolResult::success(vec![Content::text(json)]))
+    }
+}
+
+impl PaperInterpreterService {
+    /// fetch_paper without serializing the result, reporting stages to `progress`
+    pub(crate) async fn fetch_paper_response(
+        &self,
+        request: FetchPaperRequest,
+        progress: &Progress,
+        ct: &CancellationToken,
+    ) -> Result<FetchPaperResponse, McpError> {
+        let content_mode = request
+            .effective_content_mode()
+            .map_err(|e| McpError::invalid_params(e, None))?;
+
+        let mut paper = self
+            .load_requested_paper(&request, content_mode.needs_pdf(), progress, ct)
+            .await?;
+
+        // Section text would repeat the content; get_paper_section serves it
+        let outline = paper
+            .structure
+            .take()
+            .filter(|_| content_mode.needs_pdf())
+            .map(|s| s.outline());
+        let total_content_chars = apply_content_mode(&mut paper, &content_mode);
+
+        Ok(FetchPaperResponse {
+            paper,
+            total_content_chars,
+            outline,
+        })
+    }
+
+    /// Load the paper named by a fetch request's identifiers, with its structure
+    pub(crate) async fn load_requested_paper(
+        &self,
+        request: &FetchPaperRequest,
+        with_content: bool,
+        progress: &Progress,
+        ct: &CancellationToken,
+    ) -> Result<Paper, McpError> {
         // Validate that at least one identifier is provided
-        if !request.has_identifier() {
+        if !request.locator.has_identifier() {
             return Err(McpError::invalid_params(
-                "Either arxiv_id or url must be provided",
+                "One of arxiv_id, url, pdf_url, or pdf_path must be provided",
                 None,
             ));
         }
 
-        let client = create_paper_client();
-
-        // Try to extract arxiv_id from various sources
-        let arxiv_id = request
+        // A catalog identifier wins over reading the PDF directly
+        let identifier = request
+            .locator
             .arxiv_id
             .clone()
-            .or_else(|| request.url.as_ref().and_then(|u| extract_arxiv_id(u)));
+            .map(PaperIdentifier::Arxiv)
+            .or_else(|| request.locator.url.as_deref().and_then(identify_url))
+            .or_else(|| request.locator.pdf_url.as_deref().and_then(identify_url));
 
-        let paper = if let Some(ref arxiv_id) = arxiv_id {
-            tracing::info!("Fetching paper by arXiv ID: {}", arxiv_id);
-
-            client
-                .fetch_by_arxiv_id(arxiv_id)
+        match (identifier, request.locator.pdf_source()) {
+            (Some(identifier), _) => {
+                self.load_paper(
+                    &identifier,
+                    with_content,
+                    request.force_refresh,
+                    progress,
+                    ct,
+                )
                 .await
-                .map_err(|e| McpError::internal_error(format!("Fetch failed: {}", e), None))?
-        } else if let Some(ref url) = request.url {
-            // Try to extract Semantic Scholar ID from URL
-            if let Some(ss_id) = extract_ss_id(url) {
-                tracing::info!("Fetching paper by Semantic Scholar ID: {}", ss_id);
-
-                client
-                    .fetch_by_ss_id(&ss_id)
-                    .await
-                    .map_err(|e| McpError::internal_error(format!("Fetch failed: {}", e), None))?
-            } else {
-                return Err(McpError::invalid_params(
-                    format!(
-                        "Unable to extract paper identifier from URL: {}. Supported URL formats: arXiv (arxiv.org/abs/*, arxiv.org/pdf/*), Semantic Scholar (semanticscholar.org/paper/*)",
-                        url
-                    ),
-                    None,
-                ));
             }
-        } else {
-            return Err(McpError::invalid_params("No identifier provided", None));
-        };
+            (None, Some(source)) => load_pdf_paper(&source, progress, ct).await,
+            (None, None) => Err(McpError::invalid_params(
+                format!(
+                    "Unable to extract paper identifier from URL: {}. Supported: arXiv, Semantic Scholar, DOI (doi.org or bare 10.xxxx/...), OpenReview forum, ACL Anthology, PubMed/PMC, and bioRxiv/medRxiv URLs. Use pdf_url for other PDF links",
+                    request.locator.url.as_deref().unwrap_or_default()
+                ),
+                None,
+            )),
+        }
+    }
 
-        let response = FetchPaperResponse {
-            paper: Paper {
-                title: paper.title,
-                authors: paper.authors.into_iter().map(|a| a.name).collect(),
-                abstract_text: paper.abstract_text,
-                arxiv_id: if paper.arxiv_id.is_empty() {
-                    None
-                } else {
-                    Some(paper.arxiv_id)
-                },
-                ss_id: if paper.ss_id.is_empty() {
-                    None
-                } else {
-                    Some(paper.ss_id)
-                },
-                categories: paper.categories,
-                published_date: Some(paper.published_date.to_rfc3339()),
-                pdf_url: if paper.url.is_empty() {
-                    None
-                } else {
-                    Some(paper.url)
-                },
-                content: paper.extracted_text.map(|t| t.plain_text),
-            },
+    /// Load a paper through the paper cache, fetching it on a miss
+    ///
+    /// A cached metadata-only entry does not satisfy `with_content`. The
+    /// fetch is abandoned, and nothing is cached, once `ct` is cancelled.
+    /// Papers with content come back with their structure detected.
+    pub(crate) async fn load_paper(
+        &self,
+        identifier: &PaperIdentifier,
+        with_content: bool,
+        force_refresh: bool,
+        progress: &Progress,
+        ct: &CancellationToken,
+    ) -> Result<Paper, McpError> {
+        let cache_key = identifier.cache_key();
+        let cached = if force_refresh {
+            None
+        } else {
+            self.paper_cache().get(&cache_key).await
         };
 
-        let json = serde_json::to_string_pretty(&response)
-            .map_err(|e| McpError::internal_error(format!("Serialization error: {}", e), None))?;
+        if let Some(mut paper) = cached
+            && (paper.content.is_some() || !with_content)
+        {
+            tracing::info!("Paper cache hit: {:?}", cache_key);
+            add_structure(&mut paper);
+            return Ok(paper);
+        }
 
-        Ok(CallToolResult::success(vec![Content::text(json)]))
+        // The paper client downloads the PDF and extracts its text in one call
+        let fetched = if with_content {
+            progress.stage("Downloading PDF and extracting text").await;
+            ct.run_until_cancelled(fetch_with_content(identifier, ct))
+                .await
+        } else {
+            progress.stage("Fetching metadata").await;
+            ct.run_until_cancelled(fetch_metadata(identifier, ct)).await
+        };
+        let mut paper = fetched.ok_or_else(|| cancelled_error("Fetch"))??;
+        add_structure(&mut paper);
+        if let Err(e) = self.paper_cache().put(&cache_key, &paper).await {
+            tracing::warn!("Failed to cache paper: {}", e);
+        }
+        Ok(paper)
     }
 }
 
-/// Extract arXiv ID from various URL formats
-/// Supports:
-/// - https://arxiv.org/abs/2301.00001
-/// - https://arxiv.org/pdf/2301.00001.pdf
-/// - http://arxiv.org/abs/2301.00001v1
-fn extract_arxiv_id(url: &str) -> Option<String> {
-    let url = url.trim();
-
-    // Pattern for arxiv.org URLs
-    if url.contains("arxiv.org") {
-        // Try /abs/ format
-        if let Some(pos) = url.find("/abs/") {
-            let id_part = &url[pos + 5..];
-            let id = id_part
-                .split(|c| c == '?' || c == '#' || c == '/')
-                .next()
-                .unwrap_or(id_part);
-            let id = strip_version_suffix(id);
-            if !id.is_empty() {
-                return Some(id.to_string());
-            }
+/// Build a paper from a PDF outside the catalogs
+///
+/// Such papers have no catalog ID, so they bypass the paper cache.
+pub(crate) async fn load_pdf_paper(
+    source: &PdfSource,
+    progress: &Progress,
+    ct: &CancellationToken,
+) -> Result<Paper, McpError> {
+    let bytes = match source {
+        PdfSource::Url(url) => {
+            tracing::info!("Fetching PDF directly: {}", url);
+            progress.stage("Downloading PDF").await;
+            download_pdf(url, ct).await
+        }
+        PdfSource::Path(path) => {
+            tracing::info!("Reading local PDF: {}", path);
+            progress.stage("Reading PDF").await;
+            read_pdf_file(path).await
         }
+    }
+    .map_err(mcp_error)?;
 
-        // Try /pdf/ format
-        if let Some(pos) = url.find("/pdf/") {
-            let id_part = &url[pos + 5..];
-            let id = id_part
-                .trim_end_matches(".pdf")
-                .split(|c| c == '?' || c == '#' || c == '/')
-                .next()
-                .unwrap_or(id_part);
-            let id = strip_version_suffix(id);
-            if !id.is_empty() {
-                return Some(id.to_string());
-            }
+    progress.stage("Extracting text").await;
+    let mut paper = ct
+        .run_until_cancelled(paper_from_pdf(bytes, source.url()))
+        .await
+        .ok_or_else(|| cancelled_error("Text extraction"))?
+        .map_err(mcp_error)?;
+    add_structure(&mut paper);
+    Ok(paper)
+}
+
+/// Catalog identifier of a paper
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum PaperIdentifier {
+    Arxiv(String),
+    SemanticScholar(String),
+    /// A registered [`PaperSource`](crate::sources::PaperSource) by name, with its ID
+    Source(&'static str, String),
+}
+
+impl PaperIdentifier {
+    pub(crate) fn cache_key(&self) -> PaperKey {
+        match self {
+            PaperIdentifier::Arxiv(id) => PaperKey::arxiv(id),
+            PaperIdentifier::SemanticScholar(id) => PaperKey::semantic_scholar(id),
+            PaperIdentifier::Source(source, id) => PaperKey::from_source(source, id),
         }
     }
+}
 
-    // Check if it's just an arxiv ID (e.g., "2301.00001")
-    if url.chars().all(|c| c.is_ascii_digit() || c == '.') && url.contains('.') {
-        return Some(url.to_string());
+/// Identify the paper behind a URL or identifier, trying arXiv, Semantic
+/// Scholar, then every registered [`PaperSource`](crate::sources::PaperSource)
+pub(crate) fn identify_url(url: &str) -> Option<PaperIdentifier> {
+    extract_arxiv_id(url)
+        .map(PaperIdentifier::Arxiv)
+        .or_else(|| extract_ss_id(url).map(PaperIdentifier::SemanticScholar))
+        .or_else(|| find_source(url).map(|(source, id)| PaperIdentifier::Source(source.name(), id)))
+}
+
+/// Fetch metadata and extracted PDF text through the library client
+async fn fetch_with_content(
+    identifier: &PaperIdentifier,
+    ct: &CancellationToken,
+) -> Result<Paper, McpError> {
+    let client = create_paper_client();
+
+    let paper = match identifier {
+        PaperIdentifier::Arxiv(arxiv_id) => {
+            tracing::info!("Fetching paper by arXiv ID: {}", arxiv_id);
+            client.fetch_by_arxiv_id(arxiv_id).await
+        }
+        PaperIdentifier::SemanticScholar(ss_id) => {
+            tracing::info!("Fetching paper by Semantic Scholar ID: {}", ss_id);
+            client.fetch_by_ss_id(ss_id).await
+        }
+        PaperIdentifier::Source(source, id) => {
+            return fetch_from_source(source, id, true, ct).await;
+        }
     }
+    .map_err(mcp_error)?;
 
-    None
+    Ok(paper_from_academic(paper))
 }
 
-/// Strip version suffix like "v1", "v2" from arxiv ID
-fn strip_version_suffix(id: &str) -> &str {
-    // Look for pattern like "v" followed by digits at the end
-    if let Some(v_pos) = id.rfind('v') {
-        let after_v = &id[v_pos + 1..];
-        // Check if everything after 'v' is digits
-        if !after_v.is_empty() && after_v.chars().all(|c| c.is_ascii_digit()) {
-            return &id[..v_pos];
+/// Fetch metadata only, skipping PDF download and extraction
+async fn fetch_metadata(
+    identifier: &PaperIdentifier,
+    ct: &CancellationToken,
+) -> Result<Paper, McpError> {
+    let summary = match identifier {
+        PaperIdentifier::Arxiv(arxiv_id) => {
+            tracing::info!("Fetching metadata by arXiv ID: {}", arxiv_id);
+            ArxivClient::new().fetch_by_id(arxiv_id).await
+        }
+        PaperIdentifier::SemanticScholar(ss_id) => {
+            tracing::info!("Fetching metadata by Semantic Scholar ID: {}", ss_id);
+            SemanticScholarClient::new().fetch_paper(ss_id).await
+        }
+        PaperIdentifier::Source(source, id) => {
+            return fetch_from_source(source, id, false, ct).await;
         }
     }
-    id
+    .map_err(mcp_error)?;
+
+    Ok(summary.into())
 }
 
-/// Extract Semantic Scholar paper ID from URL
-/// Supports:
-/// - https://www.semanticscholar.org/paper/Title-Name/abc123def456
-/// - https://api.semanticscholar.org/CorpusID:12345678
-fn extract_ss_id(url: &str) -> Option<String> {
-    let url = url.trim();
-
-    // Pattern for semanticscholar.org paper URLs
-    if url.contains("semanticscholar.org/paper/") {
-        // The ID is typically the last path segment
-        let parts: Vec<&str> = url
-            .trim_end_matches('/')
-            .split('/')
-            .filter(|s| !s.is_empty())
-            .collect();
-        if let Some(id) = parts.last() {
-            // Clean up query params
-            let id = id.split('?').next().unwrap_or(id);
-            if !id.is_empty() && id.len() >= 8 {
-                return Some(id.to_string());
+/// Fetch a paper from a registered source, adding the PDF's text when asked
+///
+/// Publisher PDFs are often paywalled, so a failed download leaves the
+/// paper with metadata only instead of failing the fetch.
+async fn fetch_from_source(
+    name: &str,
+    id: &str,
+    with_content: bool,
+    ct: &CancellationToken,
+) -> Result<Paper, McpError> {
+    let source = source_named(name)
+        .ok_or_else(|| McpError::invalid_params(format!("Unknown paper source: {}", name), None))?;
+    tracing::info!("Fetching paper from {}: {}", name, id);
+    let mut paper = source.fetch(id).await.map_err(mcp_error)?;
+
+    if with_content {
+        let Some(pdf_url) = paper.pdf_url.clone() else {
+            tracing::warn!("{} lists no PDF for {}; returning metadata only", name, id);
+            return Ok(paper);
+        };
+        let content = match download_pdf(&pdf_url, ct).await {
+            Ok(bytes) => extract_pdf_text(bytes).await,
+            Err(e) => Err(e),
+        };
+        match content {
+            Ok(text) => paper.content = Some(text),
+            Err(AppError::Cancelled(message)) => {
+                return Err(mcp_error(AppError::Cancelled(message)));
             }
+            Err(e) => tracing::warn!("No text for {} {}: {}", name, id, e),
         }
     }
+    Ok(paper)
+}
 
-    // Pattern for CorpusID
-    if url.contains("CorpusID:") {
-        if let Some(pos) = url.find("CorpusID:") {
-            let id_part = &url[pos + 9..];
-            let id = id_part
-                .split(|c: char| !c.is_ascii_digit())
-                .next()
-                .unwrap_or(id_part);
-            if !id.is_empty() {
-                return Some(id.to_string());
+/// Convert the library's AcademicPaper to our Paper
+fn paper_from_academic(paper: AcademicPaper) -> Paper {
+    Paper {
+        title: paper.title,
+        authors: paper.authors.into_iter().map(|a| a.name).collect(),
+        abstract_text: paper.abstract_text,
+        arxiv_id: if paper.arxiv_id.is_empty() {
+            None
+        } else {
+            Some(paper.arxiv_id)
+        },
+        ss_id: if paper.ss_id.is_empty() {
+            None
+        } else {
+            Some(paper.ss_id)
+        },
+        categories: paper.categories,
+        published_date: Some(paper.published_date.to_rfc3339()),
+        pdf_url: if paper.url.is_empty() {
+            None
+        } else {
+            Some(paper.url)
+        },
+        content: paper.extracted_text.map(|t| t.plain_text),
+        structure: None,
+    }
+}
+
+/// Cut the paper's content down to what the mode asks for
+///
+/// Returns the length of the full text when it was cut down.
+pub(crate) fn apply_content_mode(paper: &mut Paper, mode: &ContentMode) -> Option<usize> {
+    let total_chars = paper.content.as_ref().map(|c| c.chars().count());
+
+    match mode {
+        ContentMode::None => {
+            paper.abstract_text.clear();
+            paper.content = None;
+            None
+        }
+        ContentMode::AbstractOnly => {
+            paper.content = None;
+            None
+        }
+        ContentMode::Full => None,
+        ContentMode::Truncated { max_chars } => {
+            let content = paper.content.as_mut()?;
+            let truncated = truncate_chars(content, *max_chars);
+            if truncated.len() == content.len() {
+                return None;
             }
+            *content = format!("{}\n[... truncated]", truncated);
+            total_chars
+        }
+        ContentMode::Sections(names) => {
+            let content = paper.content.as_mut()?;
+            let wanted: Vec<String> = names.iter().map(|n| n.to_lowercase()).collect();
+            let selected: Vec<String> = split_sections(content)
+                .into_iter()
+                .filter(|s| {
+                    let heading = s.heading.to_lowercase();
+                    wanted.iter().any(|w| heading.contains(w.as_str()))
+                })
+                .map(|s| format!("{}\n{}", s.heading, s.text))
+                .collect();
+            *content = selected.join("\n\n");
+            total_chars
         }
     }
-
-    None
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_extract_arxiv_id() {
-        assert_eq!(
-            extract_arxiv_id("https://arxiv.org/abs/2301.00001"),
-            Some("2301.00001".to_string())
-        );
-        assert_eq!(
-            extract_arxiv_id("https://arxiv.org/abs/2301.00001v2"),
-            Some("2301.00001".to_string())
-        );
-        assert_eq!(
-            extract_arxiv_id("https://arxiv.org/pdf/2301.00001.pdf"),
-            Some("2301.00001".to_string())
-        );
-        assert_eq!(
-            extract_arxiv_id("http://arxiv.org/abs/1706.03762"),
-            Some("1706.03762".to_string())
-        );
-        assert_eq!(
-            extract_arxiv_id("2301.00001"),
-            Some("2301.00001".to_string())
-        );
-        assert_eq!(extract_arxiv_id("https://example.com"), None);
+    fn paper_with_content(content: &str) -> Paper {
+        Paper {
+            title: "Title".to_string(),
+            authors: vec![],
+            abstract_text: "Abstract".to_string(),
+            arxiv_id: None,
+            ss_id: None,
+            categories: vec![],
+            published_date: None,
+            pdf_url: None,
+            content: Some(content.to_string()),
+            structure: None,
+        }
     }
 
     #[test]
-    fn test_extract_ss_id() {
+    fn test_apply_content_mode() {
+        let text = "1 Introduction\nWe study X.\n2 Method\nWe do Y.\n3 Results\nIt works.";
+
+        let mut paper = paper_with_content(text);
+        assert_eq!(apply_content_mode(&mut paper, &ContentMode::Full), None);
+        assert_eq!(paper.content.as_deref(), Some(text));
+
+        let mut paper = paper_with_content(text);
+        apply_content_mode(&mut paper, &ContentMode::AbstractOnly);
+        assert_eq!(paper.content, None);
+        assert_eq!(paper.abstract_text, "Abstract");
+
+        let mut paper = paper_with_content(text);
+        apply_content_mode(&mut paper, &ContentMode::None);
+        assert_eq!(paper.content, None);
+        assert!(paper.abstract_text.is_empty());
+
+        let mut paper = paper_with_content(text);
+        let total = apply_content_mode(&mut paper, &ContentMode::Truncated { max_chars: 14 });
+        assert_eq!(total, Some(text.len()));
         assert_eq!(
-            extract_ss_id("https://www.semanticscholar.org/paper/Attention-Is-All-You-Need/abc123def456"),
-            Some("abc123def456".to_string())
+            paper.content.as_deref(),
+            Some("1 Introduction\n[... truncated]")
         );
-        assert_eq!(
-            extract_ss_id("https://api.semanticscholar.org/CorpusID:12345678"),
-            Some("12345678".to_string())
+
+        let mut paper = paper_with_content(text);
+        apply_content_mode(
+            &mut paper,
+            &ContentMode::Sections(vec!["method".to_string()]),
         );
-        assert_eq!(extract_ss_id("https://example.com"), None);
+        assert_eq!(paper.content.as_deref(), Some("2 Method\nWe do Y."));
     }
 }
@@ -0,0 +1,470 @@
+use crate::models::paper::PaperSummary;
+use crate::models::request::{CitationGraphRequest, RecommendSimilarRequest, SortOrder};
+use crate::models::response::{
+    CitationGraph, CitationGraphResponse, GraphEdge, GraphNode, NeighborPaper,
+    RecommendSimilarResponse,
+};
+use crate::progress::Progress;
+use crate::server::errors::{cancelled_error, mcp_error};
+use crate::server::handler::PaperInterpreterService;
+use crate::server::tools::fetch::{identify_url, PaperIdentifier};
+use crate::server::tools::search::sort_by_paper;
+use crate::sources::semantic_scholar::{CitationDirection, Neighbor, MAX_NEIGHBORS};
+use crate::sources::SemanticScholarClient;
+use futures::future::join_all;
+use rmcp::model::{CallToolResult, Content};
+use rmcp::tool;
+use rmcp::{Error as McpError, ServerHandler};
+use shared::errors::AppResult;
+use std::collections::HashSet;
+use tokio_util::sync::CancellationToken;
+
+/// Largest page the graph tools return
+const MAX_PAGE_SIZE: usize = 100;
+
+/// Most papers expanded at depth 2, and most neighbors added for each
+const MAX_EXPAND: usize = 20;
+
+/// Recommendations fetched before sorting and paging
+const RECOMMENDATION_CANDIDATES: u32 = 100;
+
+impl PaperInterpreterService {
+    #[tool(
+        description = "List the papers citing a paper, from Semantic Scholar's citation graph. Paginated with offset and limit; sort by citations or date, or keep influential citations only. depth 2 also returns a graph (nodes and edges) of the papers citing the first citing papers"
+    )]
+    pub async fn get_citations(
+        &self,
+        ct: CancellationToken,
+        #[tool(aggr)] request: CitationGraphRequest,
+    ) -> Result<CallToolResult, McpError> {
+        let progress = Progress::new(self.get_peer(), request.progress.progress_token.clone());
+        let response =
+            citation_graph(&request, CitationDirection::Citations, &progress, &ct).await?;
+        let json = serde_json::to_string_pretty(&response)
+            .map_err(|e| McpError::internal_error(format!("Serialization error: {}", e), None))?;
+
+        Ok(CallToolResult::success(vec![Content::text(json)]))
+    }
+
+    #[tool(
+        description = "Recommend papers similar to a paper, from Semantic Scholar's recommendations. Paginated with offset and limit; sort by citations or date"
+    )]
+    pub async fn recommend_similar(
+        &self,
+        ct: CancellationToken,
+        #[tool(aggr)] request: RecommendSimilarRequest,
+    ) -> Result<CallToolResult, McpError> {
+        let progress = Progress::new(self.get_peer(), request.progress.progress_token.clone());
+        let paper_id = s2_paper_id(
+            request.arxiv_id.as_deref(),
+            request.ss_id.as_deref(),
+            request.url.as_deref(),
+        )?;
+
+        progress.stage("Fetching recommendations").await;
+        let mut papers = ct
+            .run_until_cancelled(SemanticScholarClient::new().recommendations(
+                &paper_id,
+                request.pool,
+                RECOMMENDATION_CANDIDATES,
+            ))
+            .await
+            .ok_or_else(|| cancelled_error("Recommendation lookup"))?
+            .map_err(mcp_error)?;
+        sort_by_paper(&mut papers, request.sort, |p| p);
+
+        let total = papers.len();
+        let limit = request.limit.clamp(1, MAX_PAGE_SIZE);
+        let papers: Vec<PaperSummary> = papers
+            .into_iter()
+            .skip(request.offset)
+            .take(limit)
+            .collect();
+        let end = request.offset + papers.len();
+
+        let response = RecommendSimilarResponse {
+            paper_id,
+            total,
+            offset: request.offset,
+            papers,
+            next_offset: (end < total).then_some(end),
+        };
+        let json = serde_json::to_string_pretty(&response)
+            .map_err(|e| McpError::internal_error(format!("Serialization error: {}", e), None))?;
+
+        Ok(CallToolResult::success(vec![Content::text(json)]))
+    }
+}
+
+/// One page of a paper's citations or references, with a depth-2 graph if asked
+pub(crate) async fn citation_graph(
+    request: &CitationGraphRequest,
+    direction: CitationDirection,
+    progress: &Progress,
+    ct: &CancellationToken,
+) -> Result<CitationGraphResponse, McpError> {
+    let paper_id = s2_paper_id(
+        request.arxiv_id.as_deref(),
+        request.ss_id.as_deref(),
+        request.url.as_deref(),
+    )?;
+    if !(1..=2).contains(&request.depth) {
+        return Err(McpError::invalid_params("depth must be 1 or 2", None));
+    }
+    let client = SemanticScholarClient::new();
+    let limit = request.limit.clamp(1, MAX_PAGE_SIZE);
+    let order = NeighborOrder {
+        sort: request.sort,
+        influential_only: request.influential_only,
+    };
+
+    progress
+        .stage(&format!("Fetching {}", direction.as_str()))
+        .await;
+    let page = ct
+        .run_until_cancelled(neighbor_page(
+            &client,
+            &paper_id,
+            direction,
+            request.offset,
+            limit,
+            order,
+        ))
+        .await
+        .ok_or_else(|| cancelled_error("Citation lookup"))?
+        .map_err(mcp_error)?;
+
+    let graph = if request.depth == 2 {
+        progress.stage("Expanding the citation graph").await;
+        let expand = request.expand_limit.clamp(1, MAX_EXPAND);
+        let graph = ct
+            .run_until_cancelled(expand_graph(
+                &client,
+                &paper_id,
+                direction,
+                &page.neighbors,
+                expand,
+                order,
+            ))
+            .await
+            .ok_or_else(|| cancelled_error("Citation graph expansion"))?
+            .map_err(mcp_error)?;
+        Some(graph)
+    } else {
+        None
+    };
+
+    Ok(CitationGraphResponse {
+        paper_id,
+        direction: direction.as_str().to_string(),
+        offset: request.offset,
+        papers: page
+            .neighbors
+            .into_iter()
+            .map(|n| NeighborPaper {
+                paper: n.paper,
+                is_influential: n.is_influential,
+            })
+            .collect(),
+        total: page.total,
+        next_offset: page.next_offset,
+        graph,
+    })
+}
+
+/// The ID Semantic Scholar looks a paper up by, from whichever identifier was given
+fn s2_paper_id(
+    arxiv_id: Option<&str>,
+    ss_id: Option<&str>,
+    url: Option<&str>,
+) -> Result<String, McpError> {
+    let identifier = if let Some(id) = arxiv_id {
+        PaperIdentifier::Arxiv(id.to_string())
+    } else if let Some(id) = ss_id {
+        PaperIdentifier::SemanticScholar(id.to_string())
+    } else if let Some(url) = url {
+        identify_url(url).ok_or_else(|| {
+            McpError::invalid_params(format!("Unrecognized paper URL: {}", url), None)
+        })?
+    } else {
+        return Err(McpError::invalid_params(
+            "One of arxiv_id, ss_id, or url must be provided",
+            None,
+        ));
+    };
+    s2_id_for(&identifier).ok_or_else(|| {
+        McpError::invalid_params(
+            "Semantic Scholar cannot look up OpenReview papers; use the arXiv ID or DOI",
+            None,
+        )
+    })
+}
+
+/// Semantic Scholar's prefixed form of a catalog identifier
+fn s2_id_for(identifier: &PaperIdentifier) -> Option<String> {
+    match identifier {
+        PaperIdentifier::Arxiv(id) => Some(format!("arXiv:{}", id)),
+        PaperIdentifier::SemanticScholar(id) if id.chars().all(|c| c.is_ascii_digit()) => {
+            Some(format!("CorpusId:{}", id))
+        }
+        PaperIdentifier::SemanticScholar(id) => Some(id.clone()),
+        PaperIdentifier::Source("doi" | "biorxiv", doi) => Some(format!("DOI:{}", doi)),
+        PaperIdentifier::Source("acl", id) => Some(format!("ACL:{}", id)),
+        PaperIdentifier::Source("pubmed", id) => Some(match id.strip_prefix("PMC") {
+            Some(pmcid) => format!("PMCID:{}", pmcid),
+            None => format!("PMID:{}", id),
+        }),
+        PaperIdentifier::Source(_, _) => None,
+    }
+}
+
+/// How neighbors are filtered and ordered
+#[derive(Debug, Clone, Copy)]
+struct NeighborOrder {
+    sort: SortOrder,
+    influential_only: bool,
+}
+
+impl NeighborOrder {
+    /// Whether Semantic Scholar's own paging returns pages in this order
+    fn is_native(&self) -> bool {
+        self.sort == SortOrder::Relevance && !self.influential_only
+    }
+}
+
+struct Page {
+    neighbors: Vec<Neighbor>,
+    total: Option<usize>,
+    next_offset: Option<usize>,
+}
+
+/// A page of neighbors in the requested order
+///
+/// Semantic Scholar pages only in its own order, so filtered or sorted pages
+/// come from the first thousand neighbors, sorted here.
+async fn neighbor_page(
+    client: &SemanticScholarClient,
+    id: &str,
+    direction: CitationDirection,
+    offset: usize,
+    limit: usize,
+    order: NeighborOrder,
+) -> AppResult<Page> {
+    if order.is_native() {
+        let page = client
+            .neighbors(id, direction, offset as u32, limit as u32)
+            .await?;
+        return Ok(Page {
+            neighbors: page.neighbors,
+            total: None,
+            next_offset: page.next.map(|next| next as usize),
+        });
+    }
+
+    let mut neighbors = client
+        .neighbors(id, direction, 0, MAX_NEIGHBORS)
+        .await?
+        .neighbors;
+    if order.influential_only {
+        neighbors.retain(|n| n.is_influential);
+    }
+    sort_by_paper(&mut neighbors, order.sort, |n| &n.paper);
+    let total = neighbors.len();
+    let neighbors: Vec<Neighbor> = neighbors.into_iter().skip(offset).take(limit).collect();
+    let end = offset + neighbors.len();
+    Ok(Page {
+        neighbors,
+        total: Some(total),
+        next_offset: (end < total).then_some(end),
+    })
+}
+
+/// Fetch the neighbors of the first `expand` neighbors and build the graph
+///
+/// A paper whose neighbors cannot be fetched is left unexpanded.
+async fn expand_graph(
+    client: &SemanticScholarClient,
+    paper_id: &str,
+    direction: CitationDirection,
+    neighbors: &[Neighbor],
+    expand: usize,
+    order: NeighborOrder,
+) -> AppResult<CitationGraph> {
+    let root = client.fetch_paper(paper_id).await?;
+    let expanded: Vec<&str> = neighbors
+        .iter()
+        .filter_map(|n| n.paper.ss_id.as_deref())
+        .take(expand)
+        .collect();
+    let pages = join_all(
+        expanded
+            .iter()
+            .map(|id| neighbor_page(client, id, direction, 0, expand, order)),
+    )
+    .await;
+
+    let second: Vec<(&str, Vec<Neighbor>)> = expanded
+        .into_iter()
+        .zip(pages)
+        .filter_map(|(id, page)| match page {
+            Ok(page) => Some((id, page.neighbors)),
+            Err(e) => {
+                tracing::warn!("Failed to expand {} of {}: {}", direction.as_str(), id, e);
+                None
+            }
+        })
+        .collect();
+    Ok(build_graph(&root, paper_id, direction, neighbors, &second))
+}
+
+/// Nodes for the paper and its neighbors two steps out, with citing → cited edges
+fn build_graph(
+    root: &PaperSummary,
+    root_id: &str,
+    direction: CitationDirection,
+    first: &[Neighbor],
+    second: &[(&str, Vec<Neighbor>)],
+) -> CitationGraph {
+    let mut graph = CitationGraph::default();
+    let mut seen = HashSet::new();
+    let root_id = root.ss_id.as_deref().unwrap_or(root_id);
+    add_node(&mut graph, &mut seen, root_id, root, 0);
+
+    add_layer(&mut graph, &mut seen, direction, root_id, first, 1);
+    for (id, neighbors) in second {
+        add_layer(&mut graph, &mut seen, direction, id, neighbors, 2);
+    }
+    graph
+}
+
+/// Add a paper's neighbors and the edges between them and the paper
+fn add_layer(
+    graph: &mut CitationGraph,
+    seen: &mut HashSet<String>,
+    direction: CitationDirection,
+    node_id: &str,
+    neighbors: &[Neighbor],
+    depth: u8,
+) {
+    for neighbor in neighbors {
+        let Some(id) = neighbor.paper.ss_id.as_deref() else {
+            continue;
+        };
+        add_node(graph, seen, id, &neighbor.paper, depth);
+        let (from, to) = match direction {
+            CitationDirection::Citations => (id, node_id),
+            CitationDirection::References => (node_id, id),
+        };
+        graph.edges.push(GraphEdge {
+            from: from.to_string(),
+            to: to.to_string(),
+            influential: neighbor.is_influential,
+        });
+    }
+}
+
+/// Add a node unless the paper is already in the graph at a smaller depth
+fn add_node(
+    graph: &mut CitationGraph,
+    seen: &mut HashSet<String>,
+    id: &str,
+    paper: &PaperSummary,
+    depth: u8,
+) {
+    if !seen.insert(id.to_string()) {
+        return;
+    }
+    graph.nodes.push(GraphNode {
+        id: id.to_string(),
+        title: paper.title.clone(),
+        arxiv_id: paper.arxiv_id.clone(),
+        year: paper
+            .published_date
+            .as_deref()
+            .and_then(|d| d.get(..4))
+            .and_then(|y| y.parse().ok()),
+        citation_count: paper.citation_count,
+        depth,
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn neighbor(id: &str, year: &str, influential: bool) -> Neighbor {
+        Neighbor {
+            paper: PaperSummary {
+                title: format!("Paper {}", id),
+                authors: vec![],
+                abstract_text: String::new(),
+                arxiv_id: None,
+                ss_id: Some(id.to_string()),
+                categories: vec![],
+                published_date: Some(format!("{}-01-01", year)),
+                citation_count: Some(1),
+                pdf_url: None,
+            },
+            is_influential: influential,
+        }
+    }
+
+    #[test]
+    fn test_build_citation_graph() {
+        let root = neighbor("root", "2017", false).paper;
+        let first = vec![neighbor("a", "2018", true), neighbor("b", "2019", false)];
+        // "b" also cites "a", so it must not be added again at depth 2
+        let second = vec![(
+            "a",
+            vec![neighbor("b", "2019", false), neighbor("c", "2020", false)],
+        )];
+
+        let graph = build_graph(
+            &root,
+            "arXiv:1706.03762",
+            CitationDirection::Citations,
+            &first,
+            &second,
+        );
+
+        let nodes: Vec<(&str, u8)> = graph
+            .nodes
+            .iter()
+            .map(|n| (n.id.as_str(), n.depth))
+            .collect();
+        assert_eq!(nodes, vec![("root", 0), ("a", 1), ("b", 1), ("c", 2)]);
+        assert_eq!(graph.nodes[3].year, Some(2020));
+        let edges: Vec<(&str, &str)> = graph
+            .edges
+            .iter()
+            .map(|e| (e.from.as_str(), e.to.as_str()))
+            .collect();
+        assert_eq!(
+            edges,
+            vec![("a", "root"), ("b", "root"), ("b", "a"), ("c", "a")]
+        );
+        assert!(graph.edges[0].influential);
+    }
+
+    #[test]
+    fn test_s2_id_for() {
+        let id = |identifier| s2_id_for(&identifier);
+        assert_eq!(
+            id(PaperIdentifier::Arxiv("1706.03762".into())).unwrap(),
+            "arXiv:1706.03762"
+        );
+        assert_eq!(
+            id(PaperIdentifier::SemanticScholar("215416146".into())).unwrap(),
+            "CorpusId:215416146"
+        );
+        assert_eq!(
+            id(PaperIdentifier::Source("pubmed", "PMC2323736".into())).unwrap(),
+            "PMCID:2323736"
+        );
+        assert_eq!(
+            id(PaperIdentifier::Source("doi", "10.1145/3292500".into())).unwrap(),
+            "DOI:10.1145/3292500"
+        );
+        assert!(id(PaperIdentifier::Source("openreview", "abc".into())).is_none());
+    }
+}
@@ -1,15 +1,27 @@
 use crate::models::analysis::AnalysisType;
-use crate::models::request::{AnalyzePaperRequest, FetchPaperRequest, InterpretPaperRequest};
-use crate::models::response::{AnalyzePaperResponse, FetchPaperResponse, InterpretPaperResponse};
+use crate::models::paper::Paper;
+use crate::models::request::{
+    AnalyzePaperRequest, FetchPaperRequest, InterpretPaperRequest, PaperLocator, PaperQuery,
+    ProgressOptions,
+};
+use crate::models::response::InterpretPaperResponse;
+use crate::progress::Progress;
+use crate::server::errors::{cancelled_error, mcp_error};
 use crate::server::handler::PaperInterpreterService;
+use crate::server::tools::fetch::{identify_url, load_pdf_paper};
+use crate::sources::pdf::PdfSource;
+use crate::sources::{resolve_title, TitleMatch};
 use rmcp::model::{CallToolResult, Content};
 use rmcp::tool;
-use rmcp::Error as McpError;
+use rmcp::{Error as McpError, ServerHandler};
+use serde_json::json;
+use tokio_util::sync::CancellationToken;
 
 impl PaperInterpreterService {
     #[tool(description = "Search, fetch, and analyze a paper in one operation")]
     pub async fn interpret_paper(
         &self,
+        ct: CancellationToken,
         #[tool(aggr)] request: InterpretPaperRequest,
     ) -> Result<CallToolResult, McpError> {
         let query = &request.query;
@@ -17,60 +29,43 @@ impl PaperInterpreterService {
         // Validate query has at least one identifier
         if !query.has_identifier() {
             return Err(McpError::invalid_params(
-                "At least one query parameter (title, url, pdf_url, or arxiv_id) is required",
+                "At least one query parameter (title, url, pdf_url, pdf_path, or arxiv_id) is required",
                 None,
             ));
         }
 
         tracing::info!("Interpreting paper with query: {:?}", query);
+        let progress = Progress::new(self.get_peer(), request.progress.progress_token.clone());
 
-        // Step 1: Fetch the paper
-        let fetch_request = FetchPaperRequest {
-            arxiv_id: query.arxiv_id.clone(),
-            url: query.url.clone(),
-            include_pdf_content: true,
-        };
-
-        let fetch_result = self.fetch_paper(fetch_request).await?;
-
-        // Parse the fetch response to extract paper
-        let fetch_content = fetch_result
-            .content
-            .first()
-            .and_then(|c| c.as_text())
-            .map(|t| t.text.as_str())
-            .ok_or_else(|| McpError::internal_error("Failed to extract fetch result", None))?;
-
-        let fetch_response: FetchPaperResponse = serde_json::from_str(fetch_content).map_err(
-            |e| McpError::internal_error(format!("Failed to parse fetch response: {}", e), None),
-        )?;
+        // Step 1: Resolve and fetch the paper
+        let paper = self
+            .resolve_paper(query, request.force_refresh, &progress, &ct)
+            .await?;
 
         // Step 2: Analyze the paper
         let analyze_request = AnalyzePaperRequest {
-            paper: fetch_response.paper.clone(),
+            paper: paper.clone(),
             llm_config: request.llm_config.clone(),
             analysis_type: AnalysisType::Summary,
+            compare_with: vec![],
+            force_refresh: request.force_refresh,
+            progress: ProgressOptions::default(),
         };
 
-        let analyze_result = self.analyze_paper(analyze_request).await?;
-
-        // Parse analysis response
-        let analyze_content = analyze_result
-            .content
-            .first()
-            .and_then(|c| c.as_text())
-            .map(|t| t.text.as_str())
-            .ok_or_else(|| McpError::internal_error("Failed to extract analysis result", None))?;
-
-        let analyze_response: AnalyzePaperResponse =
-            serde_json::from_str(analyze_content).map_err(|e| {
-                McpError::internal_error(format!("Failed to parse analysis response: {}", e), None)
-            })?;
+        let analyze_response = self
+            .analyze_paper_response(analyze_request, &progress, &ct)
+            .await?;
 
         // Combine results
+        // The structure repeats the content section by section
         let response = InterpretPaperResponse {
-            paper: fetch_response.paper,
+            paper: Paper {
+                structure: None,
+                ..paper
+            },
             analysis: analyze_response.analysis,
+            cached: analyze_response.cached,
+            analyzed_at: analyze_response.analyzed_at,
         };
 
         let json = serde_json::to_string_pretty(&response)
@@ -78,4 +73,119 @@ impl PaperInterpreterService {
 
         Ok(CallToolResult::success(vec![Content::text(json)]))
     }
+
+    /// Resolve a query to a paper with content
+    ///
+    /// A local PDF path comes first, then catalog identifiers (arXiv ID, or
+    /// a URL or identifier any paper source recognizes), then a direct PDF
+    /// URL, then a title search.
+    async fn resolve_paper(
+        &self,
+        query: &PaperQuery,
+        force_refresh: bool,
+        progress: &Progress,
+        ct: &CancellationToken,
+    ) -> Result<Paper, McpError> {
+        progress.stage("Resolving the paper").await;
+
+        let url_has_id = query
+            .locator
+            .url
+            .as_deref()
+            .is_some_and(|u| identify_url(u).is_some());
+        if let Some(ref pdf_path) = query.locator.pdf_path {
+            return load_pdf_paper(&PdfSource::Path(pdf_path.clone()), progress, ct).await;
+        }
+
+        if query.locator.arxiv_id.is_some() || url_has_id {
+            return self
+                .fetch_full_paper(
+                    query.locator.arxiv_id.clone(),
+                    query.locator.url.clone(),
+                    force_refresh,
+                    progress,
+                    ct,
+                )
+                .await;
+        }
+
+        if let Some(ref pdf_url) = query.locator.pdf_url {
+            // e.g., an arXiv or OpenReview PDF link
+            if identify_url(pdf_url).is_some() {
+                return self
+                    .fetch_full_paper(None, Some(pdf_url.clone()), force_refresh, progress, ct)
+                    .await;
+            }
+
+            return load_pdf_paper(&PdfSource::Url(pdf_url.clone()), progress, ct).await;
+        }
+
+        if let Some(ref title) = query.title {
+            tracing::info!("Resolving paper by title: {}", title);
+            let resolved = ct
+                .run_until_cancelled(resolve_title(title))
+                .await
+                .ok_or_else(|| cancelled_error("Title search"))?
+                .map_err(mcp_error)?;
+
+            return match resolved {
+                TitleMatch::Found(candidate) => {
+                    tracing::info!(
+                        "Matched title \"{}\" (score {:.2})",
+                        candidate.paper.title,
+                        candidate.score
+                    );
+                    let paper = candidate.paper;
+                    let url = paper
+                        .ss_id
+                        .map(|id| format!("https://www.semanticscholar.org/paper/{}", id));
+                    self.fetch_full_paper(paper.arxiv_id, url, force_refresh, progress, ct)
+                        .await
+                }
+                TitleMatch::Ambiguous(candidates) => Err(McpError::invalid_params(
+                    format!(
+                        "Title \"{}\" matches several papers; retry with arxiv_id or url",
+                        title
+                    ),
+                    Some(json!({ "candidates": candidates })),
+                )),
+                TitleMatch::NotFound(candidates) => Err(McpError::invalid_params(
+                    format!("No paper found with a title matching \"{}\"", title),
+                    Some(json!({ "candidates": candidates })),
+                )),
+            };
+        }
+
+        Err(McpError::invalid_params(
+            "Unable to identify the paper: url is not from a supported paper source and no title or pdf_url was given",
+            None,
+        ))
+    }
+
+    /// Fetch a paper with its full content the way fetch_paper does
+    async fn fetch_full_paper(
+        &self,
+        arxiv_id: Option<String>,
+        url: Option<String>,
+        force_refresh: bool,
+        progress: &Progress,
+        ct: &CancellationToken,
+    ) -> Result<Paper, McpError> {
+        let fetch_request = FetchPaperRequest {
+            locator: PaperLocator {
+                arxiv_id,
+                url,
+                ..Default::default()
+            },
+            include_pdf_content: true,
+            content_mode: None,
+            force_refresh,
+            progress: ProgressOptions::default(),
+        };
+
+        let fetch_response = self
+            .fetch_paper_response(fetch_request, progress, ct)
+            .await?;
+        Ok(fetch_response.paper)
+    }
 }
@@ -1,4 +1,10 @@
 pub mod analyze;
+pub mod ask;
+pub mod compare;
 pub mod fetch;
+pub mod graph;
 pub mod interpret;
+pub mod references;
+pub mod review;
 pub mod search;
+pub mod section;
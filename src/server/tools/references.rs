@@ -0,0 +1,136 @@
+use crate::cache::PaperKey;
+use crate::models::paper::Paper;
+use crate::models::request::{GetReferencesRequest, ReferenceSource};
+use crate::models::response::GetReferencesResponse;
+use crate::pipeline::structure::add_structure;
+use crate::progress::Progress;
+use crate::server::errors::cancelled_error;
+use crate::server::handler::PaperInterpreterService;
+use crate::server::tools::graph::citation_graph;
+use crate::sources::resolve_references;
+use crate::sources::semantic_scholar::CitationDirection;
+use rmcp::model::{CallToolResult, Content};
+use rmcp::tool;
+use rmcp::{Error as McpError, ServerHandler};
+use tokio_util::sync::CancellationToken;
+
+/// Largest page get_references returns
+const MAX_PAGE_SIZE: usize = 100;
+
+impl PaperInterpreterService {
+    #[tool(
+        description = "List the entries of a paper's reference list, parsed into authors, title, venue, year, and arXiv ID or DOI, and matched to Semantic Scholar papers. Paginated with offset and limit. With source semantic_scholar, list the cited papers from Semantic Scholar's citation graph instead, with sorting, influential-only filtering, and a depth-2 graph as in get_citations"
+    )]
+    pub async fn get_references(
+        &self,
+        ct: CancellationToken,
+        #[tool(aggr)] request: GetReferencesRequest,
+    ) -> Result<CallToolResult, McpError> {
+        let progress = Progress::new(self.get_peer(), request.progress.progress_token.clone());
+        if request.source == ReferenceSource::SemanticScholar {
+            let response = citation_graph(
+                &request.graph_request(),
+                CitationDirection::References,
+                &progress,
+                &ct,
+            )
+            .await?;
+            let json = serde_json::to_string_pretty(&response).map_err(|e| {
+                McpError::internal_error(format!("Serialization error: {}", e), None)
+            })?;
+            return Ok(CallToolResult::success(vec![Content::text(json)]));
+        }
+
+        let mut paper = self
+            .load_requested_paper(
+                &request.locator.fetch_request(request.force_refresh),
+                true,
+                &progress,
+                &ct,
+            )
+            .await?;
+        if paper.structure.is_none() {
+            return Err(McpError::resource_not_found(
+                format!("No full text available for \"{}\"", paper.title),
+                None,
+            ));
+        }
+        let already_resolved = paper
+            .structure
+            .as_ref()
+            .is_some_and(|s| s.references_resolved);
+        if request.resolve && !already_resolved {
+            self.resolve_paper_references(&mut paper, &progress, &ct)
+                .await?;
+            // Later pages and analyses of the cached paper reuse the matches
+            if let Some(key) = PaperKey::for_paper(&paper)
+                && let Err(e) = self.paper_cache().put(&key, &paper).await
+            {
+                tracing::warn!("Failed to cache resolved references: {}", e);
+            }
+        }
+
+        let structure = paper.structure.unwrap_or_default();
+        let total = structure.references.len();
+        let limit = request.limit.clamp(1, MAX_PAGE_SIZE);
+        let references: Vec<_> = structure
+            .references
+            .into_iter()
+            .skip(request.offset)
+            .take(limit)
+            .collect();
+        let end = request.offset + references.len();
+
+        let response = GetReferencesResponse {
+            title: paper.title,
+            total,
+            offset: request.offset,
+            references,
+            next_offset: (end < total).then_some(end),
+            resolved: structure.references_resolved,
+        };
+        let json = serde_json::to_string_pretty(&response)
+            .map_err(|e| McpError::internal_error(format!("Serialization error: {}", e), None))?;
+
+        Ok(CallToolResult::success(vec![Content::text(json)]))
+    }
+}
+
+impl PaperInterpreterService {
+    /// Match the paper's parsed references to Semantic Scholar, unless already done
+    ///
+    /// A failed lookup keeps the references as parsed.
+    pub(crate) async fn resolve_paper_references(
+        &self,
+        paper: &mut Paper,
+        progress: &Progress,
+        ct: &CancellationToken,
+    ) -> Result<(), McpError> {
+        add_structure(paper);
+        let citing_id = paper
+            .ss_id
+            .clone()
+            .or_else(|| paper.arxiv_id.as_ref().map(|id| format!("arXiv:{}", id)));
+        let Some(structure) = paper.structure.as_mut() else {
+            return Ok(());
+        };
+        if structure.references_resolved || structure.references.is_empty() {
+            return Ok(());
+        }
+
+        progress.stage("Resolving references").await;
+        let resolved = ct
+            .run_until_cancelled(resolve_references(
+                &mut structure.references,
+                citing_id.as_deref(),
+            ))
+            .await
+            .ok_or_else(|| cancelled_error("Reference resolution"))?;
+        if let Err(e) = resolved {
+            tracing::warn!("Failed to resolve references of \"{}\": {}", paper.title, e);
+            return Ok(());
+        }
+        structure.references_resolved = true;
+        Ok(())
+    }
+}
@@ -0,0 +1,182 @@
+use crate::cache::AnalysisKey;
+use crate::llm::ChatClient;
+use crate::models::analysis::{AnalysisType, PaperAnalysis};
+use crate::models::llm_config::LlmConfig;
+use crate::models::paper::{Paper, PaperSummary};
+use crate::models::request::LiteratureReviewRequest;
+use crate::models::response::LiteratureReviewResponse;
+use crate::pipeline::{rank_candidates, summarize, write_review};
+use crate::progress::Progress;
+use crate::server::errors::{cancelled_error, mcp_error};
+use crate::server::handler::PaperInterpreterService;
+use crate::server::tools::fetch::PaperIdentifier;
+use crate::server::tools::search::search;
+use futures::stream::{self, StreamExt};
+use rmcp::model::{CallToolResult, Content};
+use rmcp::tool;
+use rmcp::{Error as McpError, ServerHandler};
+use tokio_util::sync::CancellationToken;
+
+/// Most papers a review covers
+const MAX_REVIEW_PAPERS: usize = 20;
+
+/// Search results fetched per reviewed paper, to rank from
+const CANDIDATES_PER_PAPER: usize = 3;
+
+/// Papers analyzed at once
+const ANALYSIS_CONCURRENCY: usize = 4;
+
+impl PaperInterpreterService {
+    #[tool(
+        description = "Write a literature review on a topic using an LLM. Searches for papers, ranks the top candidates, analyzes each, groups them into themes, and returns a review with inline [n] citations that map to the reviewed papers' metadata"
+    )]
+    pub async fn literature_review(
+        &self,
+        ct: CancellationToken,
+        #[tool(aggr)] request: LiteratureReviewRequest,
+    ) -> Result<CallToolResult, McpError> {
+        if request.query.trim().is_empty() {
+            return Err(McpError::invalid_params("query must not be empty", None));
+        }
+        let progress = Progress::new(self.get_peer(), request.progress.progress_token.clone());
+
+        let config = self
+            .llm_config_resolver()
+            .resolve(request.llm_config.as_ref());
+        self.llm_config_resolver()
+            .validate_api_key(&config)
+            .map_err(mcp_error)?;
+
+        progress.stage("Searching for papers").await;
+        let max_papers = request.max_papers.clamp(1, MAX_REVIEW_PAPERS);
+        let search_request = request.search_request((max_papers * CANDIDATES_PER_PAPER) as u32);
+        let candidates = ct
+            .run_until_cancelled(search(&search_request))
+            .await
+            .ok_or_else(|| cancelled_error("Paper search"))??;
+        if candidates.is_empty() {
+            return Err(McpError::resource_not_found(
+                format!("No papers found for \"{}\"", request.query),
+                None,
+            ));
+        }
+        let candidate_count = candidates.len();
+        let ranked = rank_candidates(&request.query, candidates, max_papers);
+
+        let chat = ChatClient::from_config(&config)
+            .map_err(mcp_error)?
+            .with_peer(self.get_peer())
+            .with_progress(progress.clone())
+            .with_cancellation(ct.clone());
+        progress
+            .stage(&format!("Analyzing {} papers", ranked.len()))
+            .await;
+        let results: Vec<(PaperSummary, Result<PaperAnalysis, McpError>)> = stream::iter(ranked)
+            .map(|summary| {
+                let (chat, config, ct) = (&chat, &config, &ct);
+                async move {
+                    let analysis = self
+                        .review_analysis(&summary, request.full_text, config, chat, ct)
+                        .await;
+                    (summary, analysis)
+                }
+            })
+            .buffered(ANALYSIS_CONCURRENCY)
+            .collect()
+            .await;
+        if ct.is_cancelled() {
+            return Err(cancelled_error("Literature review"));
+        }
+
+        // A paper that fails to analyze is left out rather than failing the review
+        let mut analyzed = Vec::new();
+        let mut last_error = None;
+        for (summary, result) in results {
+            match result {
+                Ok(analysis) => analyzed.push((summary, analysis)),
+                Err(e) => {
+                    tracing::warn!(
+                        "Leaving \"{}\" out of the review: {}",
+                        summary.title,
+                        e.message
+                    );
+                    last_error = Some(e);
+                }
+            }
+        }
+        if analyzed.is_empty()
+            && let Some(e) = last_error
+        {
+            return Err(e);
+        }
+
+        let review = write_review(&chat, &request.query, analyzed)
+            .await
+            .map_err(mcp_error)?;
+        let response = LiteratureReviewResponse {
+            review,
+            candidates: candidate_count,
+        };
+        let json = serde_json::to_string_pretty(&response)
+            .map_err(|e| McpError::internal_error(format!("Serialization error: {}", e), None))?;
+
+        Ok(CallToolResult::success(vec![Content::text(json)]))
+    }
+}
+
+impl PaperInterpreterService {
+    /// Summary analysis of one search result, reusing a stored analysis of the same paper
+    ///
+    /// With `full_text`, the paper's PDF is read when it can be; otherwise, and
+    /// when it cannot, the abstract is analyzed.
+    async fn review_analysis(
+        &self,
+        summary: &PaperSummary,
+        full_text: bool,
+        config: &LlmConfig,
+        chat: &ChatClient,
+        ct: &CancellationToken,
+    ) -> Result<PaperAnalysis, McpError> {
+        let identifier = summary
+            .arxiv_id
+            .clone()
+            .map(PaperIdentifier::Arxiv)
+            .or_else(|| summary.ss_id.clone().map(PaperIdentifier::SemanticScholar));
+        let paper = match identifier {
+            Some(identifier) if full_text => {
+                match self
+                    .load_paper(&identifier, true, false, &Progress::none(), ct)
+                    .await
+                {
+                    Ok(paper) => paper,
+                    Err(e) => {
+                        tracing::warn!(
+                            "Reviewing \"{}\" from its abstract: {}",
+                            summary.title,
+                            e.message
+                        );
+                        Paper::from(summary.clone())
+                    }
+                }
+            }
+            _ => Paper::from(summary.clone()),
+        };
+
+        let key = AnalysisKey::new(std::slice::from_ref(&paper), config, &AnalysisType::Summary);
+        if let Some(ref key) = key
+            && let Some(stored) = self.analysis_cache().get(key).await
+        {
+            return Ok(stored.value);
+        }
+        let analysis = summarize(chat, &paper).await.map_err(mcp_error)?;
+        // analyze_paper stores full-text summaries with their related work,
+        // which is only empty for abstracts
+        if paper.content.is_none()
+            && let Some(ref key) = key
+            && let Err(e) = self.analysis_cache().put(key, &analysis).await
+        {
+            tracing::warn!("Failed to store analysis: {}", e);
+        }
+        Ok(analysis)
+    }
+}
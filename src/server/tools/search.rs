@@ -1,54 +1,31 @@
-use crate::llm::create_paper_client;
 use crate::models::paper::PaperSummary;
-use crate::models::request::SearchPapersRequest;
+use crate::models::request::{SearchPapersRequest, SearchSource, SortOrder};
 use crate::models::response::SearchPapersResponse;
+use crate::server::errors::mcp_error;
 use crate::server::handler::PaperInterpreterService;
+use crate::sources::semantic_scholar::field_of_study_for_category;
+use crate::sources::{ArxivClient, ArxivQuery, SemanticScholarClient, SemanticScholarQuery};
+use chrono::NaiveDate;
 use rmcp::model::{CallToolResult, Content};
 use rmcp::tool;
 use rmcp::Error as McpError;
 
+/// Upper bound on results requested from a backend before post-filtering
+const MAX_FETCH: u32 = 100;
+
 impl PaperInterpreterService {
-    #[tool(description = "Search for academic papers by keywords, author, or category")]
+    #[tool(
+        description = "Search for academic papers by keywords, with optional author, category, and date filters and sorting by relevance, date, or citations"
+    )]
     pub async fn search_papers(
         &self,
         #[tool(aggr)] request: SearchPapersRequest,
     ) -> Result<CallToolResult, McpError> {
-        tracing::info!("Searching papers with query: {}", request.query);
-
-        let client = create_paper_client();
-
-        // Build search params
-        let mut params = academic_paper_interpreter::client::SearchParams::default();
-        params.query = Some(request.query.clone());
-
-        // Execute search
-        let search_result = client
-            .search(params)
-            .await
-            .map_err(|e| McpError::internal_error(format!("Search failed: {}", e), None))?;
-
-        // Convert to response format - SearchResult has a papers field
-        let summaries: Vec<PaperSummary> = search_result
-            .papers
-            .into_iter()
-            .take(request.max_results as usize)
-            .map(|p| PaperSummary {
-                title: p.title,
-                authors: p.authors.into_iter().map(|a| a.name).collect(),
-                abstract_text: p.abstract_text,
-                arxiv_id: if p.arxiv_id.is_empty() {
-                    None
-                } else {
-                    Some(p.arxiv_id)
-                },
-                published_date: Some(p.published_date.to_rfc3339()),
-                pdf_url: if p.url.is_empty() { None } else { Some(p.url) },
-            })
-            .collect();
+        let papers = search(&request).await?;
 
         let response = SearchPapersResponse {
-            total_count: summaries.len() as u32,
-            papers: summaries,
+            total_count: papers.len() as u32,
+            papers,
         };
 
         let json = serde_json::to_string_pretty(&response)
@@ -57,3 +34,250 @@ impl PaperInterpreterService {
         Ok(CallToolResult::success(vec![Content::text(json)]))
     }
 }
+
+/// Run a search_papers request, returning the filtered and sorted results
+pub(crate) async fn search(request: &SearchPapersRequest) -> Result<Vec<PaperSummary>, McpError> {
+    tracing::info!("Searching papers with query: {}", request.query);
+
+    let published_after = parse_date("published_after", request.published_after.as_deref())?;
+    let published_before = parse_date("published_before", request.published_before.as_deref())?;
+
+    let source = match (request.source, request.sort_by) {
+        (Some(SearchSource::Arxiv), SortOrder::Citations) => {
+            return Err(McpError::invalid_params(
+                "Sorting by citations requires source semantic_scholar (arXiv has no citation counts)",
+                None,
+            ));
+        }
+        (Some(source), _) => source,
+        (None, SortOrder::Citations) => SearchSource::SemanticScholar,
+        (None, _) => SearchSource::Arxiv,
+    };
+
+    // Over-fetch when results are filtered or re-sorted locally
+    let needs_post_processing = request.author.is_some()
+        || request.category.is_some()
+        || (source == SearchSource::SemanticScholar && request.sort_by != SortOrder::Relevance);
+    let fetch_limit = fetch_limit(request.max_results, needs_post_processing);
+
+    // Push filters down to the backend where its query syntax supports them
+    let mut papers = match source {
+        SearchSource::Arxiv => {
+            let query = ArxivQuery {
+                keywords: request.query.clone(),
+                author: request.author.clone(),
+                category: request.category.clone(),
+                published_after,
+                published_before,
+                sort_by: request.sort_by,
+                max_results: fetch_limit,
+            };
+            ArxivClient::new().search(&query).await
+        }
+        SearchSource::SemanticScholar => {
+            let query = SemanticScholarQuery {
+                keywords: request.query.clone(),
+                fields_of_study: request
+                    .category
+                    .as_deref()
+                    .and_then(field_of_study_for_category)
+                    .map(str::to_string),
+                published_after,
+                published_before,
+                limit: fetch_limit,
+            };
+            SemanticScholarClient::new().search(&query).await
+        }
+    }
+    .map_err(mcp_error)?;
+
+    // Post-filter what the backend could not express, then sort
+    papers.retain(|p| {
+        matches_filters(
+            p,
+            request.author.as_deref(),
+            request.category.as_deref(),
+            published_after,
+            published_before,
+        )
+    });
+    sort_papers(&mut papers, request.sort_by);
+    papers.truncate(request.max_results as usize);
+    Ok(papers)
+}
+
+/// Number of results to request from the backend, at most MAX_FETCH
+fn fetch_limit(max_results: u32, over_fetch: bool) -> u32 {
+    let limit = max_results.min(MAX_FETCH);
+    if over_fetch {
+        limit.saturating_mul(3).min(MAX_FETCH)
+    } else {
+        limit
+    }
+}
+
+/// Parse a YYYY-MM-DD request parameter
+fn parse_date(name: &str, value: Option<&str>) -> Result<Option<NaiveDate>, McpError> {
+    value
+        .map(|v| {
+            NaiveDate::parse_from_str(v.trim(), "%Y-%m-%d").map_err(|_| {
+                McpError::invalid_params(format!("{} must be YYYY-MM-DD, got {}", name, v), None)
+            })
+        })
+        .transpose()
+}
+
+/// Leading YYYY-MM-DD of an ISO 8601 date or timestamp
+fn published_on(paper: &PaperSummary) -> Option<NaiveDate> {
+    let date = paper.published_date.as_deref()?;
+    NaiveDate::parse_from_str(date.get(..10)?, "%Y-%m-%d").ok()
+}
+
+/// Check a search result against the request filters
+///
+/// Filters that a result carries no data for (e.g., arXiv categories on
+/// Semantic Scholar results) are not applied to that result.
+fn matches_filters(
+    paper: &PaperSummary,
+    author: Option<&str>,
+    category: Option<&str>,
+    published_after: Option<NaiveDate>,
+    published_before: Option<NaiveDate>,
+) -> bool {
+    if let Some(author) = author {
+        let needle = author.to_lowercase();
+        if !paper
+            .authors
+            .iter()
+            .any(|a| a.to_lowercase().contains(&needle))
+        {
+            return false;
+        }
+    }
+
+    if let Some(category) = category
+        && !paper.categories.is_empty()
+        && !paper
+            .categories
+            .iter()
+            .any(|c| c.eq_ignore_ascii_case(category))
+    {
+        return false;
+    }
+
+    if let Some(date) = published_on(paper)
+        && (published_after.is_some_and(|after| date < after)
+            || published_before.is_some_and(|before| date > before))
+    {
+        return false;
+    }
+
+    true
+}
+
+/// Sort results in place; relevance keeps the backend's ranking
+fn sort_papers(papers: &mut [PaperSummary], order: SortOrder) {
+    sort_by_paper(papers, order, |p| p);
+}
+
+/// Sort items holding papers in place, newest or most cited first
+pub(crate) fn sort_by_paper<T>(
+    items: &mut [T],
+    order: SortOrder,
+    paper: impl Fn(&T) -> &PaperSummary,
+) {
+    match order {
+        SortOrder::Relevance => {}
+        SortOrder::Date => items.sort_by_key(|item| std::cmp::Reverse(published_on(paper(item)))),
+        SortOrder::Citations => {
+            items.sort_by_key(|item| std::cmp::Reverse(paper(item).citation_count.unwrap_or(0)))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary(authors: &[&str], categories: &[&str], date: &str, citations: u32) -> PaperSummary {
+        PaperSummary {
+            title: "Paper".to_string(),
+            authors: authors.iter().map(|a| a.to_string()).collect(),
+            abstract_text: String::new(),
+            arxiv_id: None,
+            ss_id: None,
+            categories: categories.iter().map(|c| c.to_string()).collect(),
+            published_date: Some(date.to_string()),
+            citation_count: Some(citations),
+            pdf_url: None,
+        }
+    }
+
+    #[test]
+    fn test_matches_filters() {
+        let paper = summary(&["Ashish Vaswani"], &["cs.CL"], "2017-06-12T17:57:34Z", 0);
+        let after = NaiveDate::from_ymd_opt(2017, 1, 1);
+        let before = NaiveDate::from_ymd_opt(2017, 12, 31);
+
+        assert!(matches_filters(
+            &paper,
+            Some("vaswani"),
+            Some("cs.CL"),
+            after,
+            before
+        ));
+        assert!(!matches_filters(&paper, Some("Hinton"), None, None, None));
+        assert!(!matches_filters(&paper, None, Some("cs.CV"), None, None));
+        assert!(!matches_filters(
+            &paper,
+            None,
+            None,
+            NaiveDate::from_ymd_opt(2018, 1, 1),
+            None
+        ));
+
+        // Results without categories (Semantic Scholar) are not dropped by the category filter
+        let s2_paper = summary(&["Ashish Vaswani"], &[], "2017-06-12", 0);
+        assert!(matches_filters(&s2_paper, None, Some("cs.CL"), None, None));
+    }
+
+    #[test]
+    fn test_sort_papers() {
+        let mut papers = vec![
+            summary(&[], &[], "2019-01-01", 5),
+            summary(&[], &[], "2021-01-01", 1),
+            summary(&[], &[], "2020-01-01", 9),
+        ];
+
+        sort_papers(&mut papers, SortOrder::Citations);
+        let citations: Vec<_> = papers.iter().map(|p| p.citation_count.unwrap()).collect();
+        assert_eq!(citations, vec![9, 5, 1]);
+
+        sort_papers(&mut papers, SortOrder::Date);
+        let dates: Vec<_> = papers
+            .iter()
+            .map(|p| p.published_date.clone().unwrap())
+            .collect();
+        assert_eq!(dates, vec!["2021-01-01", "2020-01-01", "2019-01-01"]);
+    }
+
+    #[test]
+    fn test_fetch_limit() {
+        assert_eq!(fetch_limit(10, false), 10);
+        assert_eq!(fetch_limit(10, true), 30);
+        assert_eq!(fetch_limit(50, true), MAX_FETCH);
+        assert_eq!(fetch_limit(150, false), MAX_FETCH);
+        assert_eq!(fetch_limit(150, true), MAX_FETCH);
+        assert_eq!(fetch_limit(u32::MAX, true), MAX_FETCH);
+    }
+
+    #[test]
+    fn test_parse_date() {
+        assert_eq!(
+            parse_date("published_after", Some("2023-01-31")).unwrap(),
+            NaiveDate::from_ymd_opt(2023, 1, 31)
+        );
+        assert_eq!(parse_date("published_after", None).unwrap(), None);
+        assert!(parse_date("published_after", Some("31/01/2023")).is_err());
+    }
+}
@@ -0,0 +1,160 @@
+use crate::models::paper::{Caption, PaperSection, PaperStructure};
+use crate::models::request::GetPaperSectionRequest;
+use crate::models::response::GetPaperSectionResponse;
+use crate::progress::Progress;
+use crate::server::handler::PaperInterpreterService;
+use rmcp::model::{CallToolResult, Content};
+use rmcp::tool;
+use rmcp::{Error as McpError, ServerHandler};
+use serde_json::json;
+use tokio_util::sync::CancellationToken;
+
+impl PaperInterpreterService {
+    #[tool(
+        description = "Return one section of a paper's full text (e.g., method or experiments) instead of the whole text, with the captions of the figures and tables it mentions. fetch_paper's outline lists the sections"
+    )]
+    pub async fn get_paper_section(
+        &self,
+        ct: CancellationToken,
+        #[tool(aggr)] request: GetPaperSectionRequest,
+    ) -> Result<CallToolResult, McpError> {
+        let progress = Progress::new(self.get_peer(), request.progress.progress_token.clone());
+        let paper = self
+            .load_requested_paper(
+                &request.locator.fetch_request(request.force_refresh),
+                true,
+                &progress,
+                &ct,
+            )
+            .await?;
+
+        let structure = paper.structure.ok_or_else(|| {
+            McpError::resource_not_found(
+                format!("No full text available for \"{}\"", paper.title),
+                None,
+            )
+        })?;
+        let sections = select_sections(&structure, &request.section, request.include_subsections);
+        if sections.is_empty() {
+            let available: Vec<&str> = structure
+                .sections
+                .iter()
+                .map(|s| s.heading.as_str())
+                .collect();
+            return Err(McpError::invalid_params(
+                format!("No section matching \"{}\"", request.section),
+                Some(json!({ "available_sections": available })),
+            ));
+        }
+
+        let response = GetPaperSectionResponse {
+            title: paper.title,
+            captions: mentioned_captions(&structure.captions, &sections),
+            sections,
+        };
+        let json = serde_json::to_string_pretty(&response)
+            .map_err(|e| McpError::internal_error(format!("Serialization error: {}", e), None))?;
+
+        Ok(CallToolResult::success(vec![Content::text(json)]))
+    }
+}
+
+/// Sections matching `query` as a section kind ("methods") or else as part of a heading
+fn select_sections(
+    structure: &PaperStructure,
+    query: &str,
+    include_subsections: bool,
+) -> Vec<PaperSection> {
+    let query = query.trim().to_lowercase();
+    let kind_query = query.replace([' ', '-'], "_");
+    let singular = |s: &str| s.trim_end_matches('s').to_string();
+
+    let by_kind: Vec<&PaperSection> = structure
+        .sections
+        .iter()
+        .filter(|s| singular(s.kind.as_str()) == singular(&kind_query))
+        .collect();
+    if !by_kind.is_empty() {
+        let top_level = by_kind.iter().map(|s| s.level).min().unwrap_or(1);
+        return by_kind
+            .into_iter()
+            .filter(|s| include_subsections || s.level == top_level)
+            .cloned()
+            .collect();
+    }
+
+    let mut selected = Vec::new();
+    let mut matched_level: Option<u8> = None;
+    for section in &structure.sections {
+        if include_subsections && matched_level.is_some_and(|level| section.level > level) {
+            selected.push(section.clone());
+            continue;
+        }
+        matched_level = None;
+        if section.heading.to_lowercase().contains(&query) {
+            matched_level = Some(section.level);
+            selected.push(section.clone());
+        }
+    }
+    selected
+}
+
+/// Captions whose label ("Figure 2", or "Fig. 2") appears in the sections' text
+fn mentioned_captions(captions: &[Caption], sections: &[PaperSection]) -> Vec<Caption> {
+    captions
+        .iter()
+        .filter(|caption| {
+            let short = caption.label.replacen("Figure", "Fig.", 1);
+            sections.iter().any(|s| {
+                [&caption.label, &short].iter().any(|label| {
+                    s.text.match_indices(label.as_str()).any(|(i, _)| {
+                        // "Figure 1" must not match inside "Figure 12"
+                        !s.text[i + label.len()..].starts_with(|c: char| c.is_ascii_digit())
+                    })
+                })
+            })
+        })
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pipeline::structure::paper_structure;
+
+    #[test]
+    fn test_select_sections() {
+        let structure = paper_structure(
+            "1 Introduction\nWe study X.\n3 Method\nOverview in Figure 1.\n\
+             Figure 1: Overview of the model.\n\n\
+             3.1 Encoder\nSix layers, see Figure 12.\n3.2 Decoder\nAlso six.\n\
+             4 Experiments\nIt works.",
+        );
+
+        let headings = |sections: Vec<PaperSection>| -> Vec<String> {
+            sections.into_iter().map(|s| s.heading).collect()
+        };
+        assert_eq!(
+            headings(select_sections(&structure, "methods", true)),
+            vec!["3 Method", "3.1 Encoder", "3.2 Decoder"]
+        );
+        assert_eq!(
+            headings(select_sections(&structure, "method", false)),
+            vec!["3 Method"]
+        );
+        assert_eq!(
+            headings(select_sections(&structure, "encoder", true)),
+            vec!["3.1 Encoder"]
+        );
+        assert!(select_sections(&structure, "proofs", true).is_empty());
+
+        let encoder = select_sections(&structure, "encoder", true);
+        assert!(mentioned_captions(&structure.captions, &encoder).is_empty());
+        let method = select_sections(&structure, "method", false);
+        assert_eq!(
+            mentioned_captions(&structure.captions, &method)[0].label,
+            "Figure 1"
+        );
+    }
+}
@@ -0,0 +1,925 @@
+use crate::server::transport::message::parse_client_message;
+use crate::server::PaperInterpreterService;
+use crate::sources::http::env_parse;
+use axum::body::Bytes;
+use axum::extract::{Query, Request, State};
+use axum::http::{header, HeaderMap, HeaderValue, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::Router;
+use futures::{SinkExt, Stream, StreamExt};
+use rmcp::model::{
+    ClientJsonRpcMessage, ClientRequest, JsonRpcError, JsonRpcMessage, JsonRpcNotification,
+    JsonRpcRequest, JsonRpcResponse, ProgressToken, RequestId, ServerJsonRpcMessage,
+    ServerNotification,
+};
+use rmcp::ServiceExt;
+use serde::Deserialize;
+use shared::errors::{AppError, AppResult};
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::{mpsc, RwLock};
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_util::sync::{CancellationToken, PollSender};
+
+/// Header carrying the session ID in the Streamable HTTP transport
+const SESSION_ID_HEADER: &str = "mcp-session-id";
+
+/// Streamable HTTP endpoint
+const MCP_PATH: &str = "/mcp";
+
+/// Legacy HTTP+SSE endpoints
+const SSE_PATH: &str = "/sse";
+const MESSAGE_PATH: &str = "/message";
+
+const CHANNEL_CAPACITY: usize = 64;
+
+const DEFAULT_MAX_SESSIONS: usize = 100;
+const DEFAULT_SESSION_IDLE_SECS: u64 = 1800;
+
+/// How often idle sessions are looked for
+const REAP_INTERVAL: Duration = Duration::from_secs(60);
+
+type SessionId = Arc<str>;
+type SessionStore = Arc<RwLock<HashMap<SessionId, Arc<Session>>>>;
+
+/// A single MCP session backed by its own `PaperInterpreterService` instance
+struct Session {
+    /// Messages from the client to the service
+    to_service: mpsc::Sender<ClientJsonRpcMessage>,
+    /// Open POST streams waiting for the response to a given request
+    pending: Mutex<HashMap<RequestId, PendingRequest>>,
+    /// Standalone stream (GET /mcp or legacy /sse) for server-initiated messages
+    standalone: Mutex<Option<mpsc::Sender<ServerJsonRpcMessage>>>,
+    /// Stops the session's service and dispatcher tasks
+    ///
+    /// The dispatcher holds the session, and with it the service's input
+    /// sender, so dropping the session from the store alone never ends them.
+    cancel: CancellationToken,
+    /// Last time the client sent a request for this session
+    last_active: Mutex<Instant>,
+}
+
+/// A request whose POST stream is still open
+struct PendingRequest {
+    tx: mpsc::Sender<ServerJsonRpcMessage>,
+    /// Progress notifications carrying this token belong to the request
+    progress_token: Option<ProgressToken>,
+}
+
+impl Session {
+    fn new(to_service: mpsc::Sender<ClientJsonRpcMessage>) -> Self {
+        Self {
+            to_service,
+            pending: Mutex::new(HashMap::new()),
+            standalone: Mutex::new(None),
+            cancel: CancellationToken::new(),
+            last_active: Mutex::new(Instant::now()),
+        }
+    }
+
+    fn touch(&self) {
+        *self.last_active.lock().expect("last_active lock poisoned") = Instant::now();
+    }
+
+    /// No open stream to the client and no request for `timeout`
+    fn is_idle(&self, timeout: Duration) -> bool {
+        let streaming = self
+            .standalone
+            .lock()
+            .expect("standalone lock poisoned")
+            .as_ref()
+            .is_some_and(|tx| !tx.is_closed())
+            || self
+                .pending
+                .lock()
+                .expect("pending lock poisoned")
+                .values()
+                .any(|request| !request.tx.is_closed());
+        !streaming
+            && self
+                .last_active
+                .lock()
+                .expect("last_active lock poisoned")
+                .elapsed()
+                >= timeout
+    }
+
+    /// Route a message emitted by the service to the stream that should carry it
+    ///
+    /// Responses go to the POST stream of their request and progress
+    /// notifications to the stream of the request that supplied the token.
+    /// Anything else is only sent on the standalone stream, never on another
+    /// request's stream.
+    async fn dispatch(&self, message: ServerJsonRpcMessage) {
+        let target = {
+            let mut pending = self.pending.lock().expect("pending lock poisoned");
+            let routed = match &message {
+                JsonRpcMessage::Response(JsonRpcResponse { id, .. })
+                | JsonRpcMessage::Error(JsonRpcError { id, .. }) => {
+                    pending.remove(id).map(|request| request.tx)
+                }
+                JsonRpcMessage::Notification(JsonRpcNotification {
+                    notification: ServerNotification::ProgressNotification(progress),
+                    ..
+                }) => pending
+                    .values()
+                    .find(|request| {
+                        request.progress_token.as_ref() == Some(&progress.params.progress_token)
+                    })
+                    .map(|request| request.tx.clone()),
+                _ => None,
+            };
+            routed.or_else(|| {
+                self.standalone
+                    .lock()
+                    .expect("standalone lock poisoned")
+                    .clone()
+            })
+        };
+
+        match target {
+            Some(tx) => {
+                if tx.send(message).await.is_err() {
+                    tracing::debug!("Client stream closed before message was delivered");
+                }
+            }
+            None => tracing::debug!("No open stream for server message, dropping it"),
+        }
+    }
+}
+
+/// Checks applied to every HTTP request before it reaches a handler
+#[derive(Debug, Default)]
+struct AccessPolicy {
+    /// Bearer token clients must send in `Authorization` (`MCP_AUTH_TOKEN`)
+    auth_token: Option<String>,
+    /// Browser origins accepted besides loopback ones (`MCP_ALLOWED_ORIGINS`)
+    allowed_origins: Vec<String>,
+    /// Reject `Host` headers that do not name the loopback interface
+    ///
+    /// Set when listening on loopback, so a page that rebinds its own domain
+    /// to 127.0.0.1 cannot reach the server from the browser.
+    loopback_hosts_only: bool,
+}
+
+impl AccessPolicy {
+    /// Build the policy for a bind address from the environment
+    ///
+    /// Listening on anything other than loopback requires `MCP_AUTH_TOKEN`,
+    /// since the server spends its LLM keys on behalf of whoever connects.
+    fn from_env(bind: SocketAddr) -> AppResult<Self> {
+        let auth_token = std::env::var("MCP_AUTH_TOKEN")
+            .ok()
+            .filter(|token| !token.trim().is_empty());
+        let loopback_hosts_only = bind.ip().is_loopback();
+        if !loopback_hosts_only && auth_token.is_none() {
+            return Err(AppError::InternalAppError(format!(
+                "Refusing to listen on {} without MCP_AUTH_TOKEN; set it or bind 127.0.0.1",
+                bind
+            )));
+        }
+
+        let allowed_origins = std::env::var("MCP_ALLOWED_ORIGINS")
+            .map(|origins| {
+                origins
+                    .split(',')
+                    .map(|o| o.trim().trim_end_matches('/').to_string())
+                    .filter(|o| !o.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Ok(Self {
+            auth_token,
+            allowed_origins,
+            loopback_hosts_only,
+        })
+    }
+
+    fn check(&self, headers: &HeaderMap) -> Result<(), AccessDenied> {
+        if let Some(origin) = headers.get(header::ORIGIN) {
+            let origin = origin.to_str().unwrap_or_default().trim_end_matches('/');
+            let allowed = origin_authority(origin).is_some_and(is_loopback_authority)
+                || self.allowed_origins.iter().any(|o| o == origin);
+            if !allowed {
+                return Err(AccessDenied::Origin);
+            }
+        }
+
+        if self.loopback_hosts_only {
+            let host = headers
+                .get(header::HOST)
+                .and_then(|h| h.to_str().ok())
+                .unwrap_or_default();
+            if !is_loopback_authority(host) {
+                return Err(AccessDenied::Host);
+            }
+        }
+
+        if let Some(token) = &self.auth_token {
+            let presented = headers
+                .get(header::AUTHORIZATION)
+                .and_then(|h| h.to_str().ok())
+                .and_then(|h| h.strip_prefix("Bearer "))
+                .unwrap_or_default();
+            if !constant_time_eq(presented.trim().as_bytes(), token.as_bytes()) {
+                return Err(AccessDenied::Token);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Reason a request was refused by the access policy
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum AccessDenied {
+    Origin,
+    Host,
+    Token,
+}
+
+impl IntoResponse for AccessDenied {
+    fn into_response(self) -> Response {
+        match self {
+            AccessDenied::Origin => error_response(StatusCode::FORBIDDEN, "Origin not allowed"),
+            AccessDenied::Host => error_response(StatusCode::FORBIDDEN, "Host not allowed"),
+            AccessDenied::Token => {
+                let mut response = error_response(StatusCode::UNAUTHORIZED, "Invalid bearer token");
+                response
+                    .headers_mut()
+                    .insert(header::WWW_AUTHENTICATE, HeaderValue::from_static("Bearer"));
+                response
+            }
+        }
+    }
+}
+
+/// Host and port part of an `Origin` header (`scheme://host[:port]`)
+fn origin_authority(origin: &str) -> Option<&str> {
+    let (scheme, authority) = origin.split_once("://")?;
+    matches!(scheme, "http" | "https").then_some(authority)
+}
+
+/// Whether a `host[:port]` authority names the loopback interface
+fn is_loopback_authority(authority: &str) -> bool {
+    let host = match authority.strip_prefix('[') {
+        Some(rest) => rest.split(']').next().unwrap_or_default(),
+        None => authority.split(':').next().unwrap_or_default(),
+    };
+    host.eq_ignore_ascii_case("localhost")
+        || host.parse::<IpAddr>().is_ok_and(|ip| ip.is_loopback())
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Bounds on the sessions a server keeps alive
+#[derive(Debug, Clone, Copy)]
+struct SessionLimits {
+    /// Sessions allowed at once (`MCP_MAX_SESSIONS`)
+    max_sessions: usize,
+    /// Sessions without open streams are closed after this long without a
+    /// request (`MCP_SESSION_IDLE_SECS`), for clients that never send DELETE
+    idle_timeout: Duration,
+}
+
+impl SessionLimits {
+    fn from_env() -> Self {
+        Self {
+            max_sessions: env_parse("MCP_MAX_SESSIONS").unwrap_or(DEFAULT_MAX_SESSIONS),
+            idle_timeout: Duration::from_secs(
+                env_parse("MCP_SESSION_IDLE_SECS")
+                    .unwrap_or(DEFAULT_SESSION_IDLE_SECS)
+                    .max(1),
+            ),
+        }
+    }
+}
+
+impl Default for SessionLimits {
+    fn default() -> Self {
+        Self {
+            max_sessions: DEFAULT_MAX_SESSIONS,
+            idle_timeout: Duration::from_secs(DEFAULT_SESSION_IDLE_SECS),
+        }
+    }
+}
+
+#[derive(Clone)]
+struct AppState {
+    sessions: SessionStore,
+    policy: Arc<AccessPolicy>,
+    limits: SessionLimits,
+}
+
+impl AppState {
+    /// Create a session and spawn a service instance to serve it
+    ///
+    /// Returns `None` when the server already holds `max_sessions` sessions.
+    async fn create_session(&self) -> Option<(SessionId, Arc<Session>)> {
+        let session_id: SessionId = Arc::from(uuid::Uuid::new_v4().simple().to_string());
+
+        let (to_service_tx, to_service_rx) = mpsc::channel(CHANNEL_CAPACITY);
+        let (from_service_tx, mut from_service_rx) = mpsc::channel(CHANNEL_CAPACITY);
+
+        let session = Arc::new(Session::new(to_service_tx));
+        {
+            let mut sessions = self.sessions.write().await;
+            if sessions.len() >= self.limits.max_sessions {
+                tracing::warn!("Session limit ({}) reached", self.limits.max_sessions);
+                return None;
+            }
+            sessions.insert(session_id.clone(), session.clone());
+        }
+
+        // Forward everything the service emits to the client streams
+        let dispatcher_session = session.clone();
+        tokio::spawn(async move {
+            let cancel = dispatcher_session.cancel.clone();
+            while let Some(Some(message)) = cancel.run_until_cancelled(from_service_rx.recv()).await
+            {
+                dispatcher_session.dispatch(message).await;
+            }
+        });
+
+        // Run the service until the client disconnects or the session is deleted
+        let sessions = self.sessions.clone();
+        let id = session_id.clone();
+        let cancel = session.cancel.clone();
+        tokio::spawn(async move {
+            let sink = PollSender::new(from_service_tx).sink_map_err(std::io::Error::other);
+            let stream = ReceiverStream::new(to_service_rx);
+            let serve =
+                PaperInterpreterService::new().serve_with_ct((sink, stream), cancel.clone());
+            match cancel.run_until_cancelled(serve).await {
+                Some(Ok(server)) => {
+                    if let Err(e) = server.waiting().await {
+                        tracing::error!(session = %id, "Session task failed: {}", e);
+                    }
+                }
+                Some(Err(e)) => tracing::error!(session = %id, "Failed to start session: {}", e),
+                None => {}
+            }
+            cancel.cancel();
+            sessions.write().await.remove(&id);
+            tracing::info!(session = %id, "Session closed");
+        });
+
+        tracing::info!(session = %session_id, "Session created");
+        Some((session_id, session))
+    }
+
+    /// Cancel and remove sessions that have gone idle
+    async fn reap_idle_sessions(&self) -> usize {
+        let mut sessions = self.sessions.write().await;
+        let before = sessions.len();
+        sessions.retain(|id, session| {
+            let idle = session.is_idle(self.limits.idle_timeout);
+            if idle {
+                session.cancel.cancel();
+                tracing::info!(session = %id, "Session expired after inactivity");
+            }
+            !idle
+        });
+        before - sessions.len()
+    }
+
+    async fn get_session(&self, session_id: &str) -> Option<Arc<Session>> {
+        self.sessions.read().await.get(session_id).cloned()
+    }
+}
+
+/// Run the MCP server with Streamable HTTP transport (and legacy SSE fallback)
+pub async fn run_http_server(bind: SocketAddr) -> AppResult<()> {
+    tracing::info!("Starting MCP server with HTTP transport on {}", bind);
+
+    let state = AppState {
+        sessions: Arc::new(RwLock::new(HashMap::new())),
+        policy: Arc::new(AccessPolicy::from_env(bind)?),
+        limits: SessionLimits::from_env(),
+    };
+
+    let reaper = state.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(REAP_INTERVAL.min(reaper.limits.idle_timeout));
+        loop {
+            interval.tick().await;
+            reaper.reap_idle_sessions().await;
+        }
+    });
+
+    let router = Router::new()
+        .route(
+            MCP_PATH,
+            post(streamable_post_handler)
+                .get(streamable_get_handler)
+                .delete(streamable_delete_handler),
+        )
+        .route(SSE_PATH, get(sse_handler))
+        .route(MESSAGE_PATH, post(message_handler))
+        .layer(middleware::from_fn_with_state(state.clone(), access_guard))
+        .with_state(state);
+
+    let listener = tokio::net::TcpListener::bind(bind)
+        .await
+        .map_err(|e| AppError::InternalAppError(format!("Failed to bind {}: {}", bind, e)))?;
+
+    tracing::info!(
+        "MCP server listening on http://{} (Streamable HTTP: {}, SSE: {})",
+        bind,
+        MCP_PATH,
+        SSE_PATH
+    );
+
+    axum::serve(listener, router)
+        .with_graceful_shutdown(async {
+            let _ = tokio::signal::ctrl_c().await;
+            tracing::info!("Shutting down HTTP server");
+        })
+        .await
+        .map_err(|e| AppError::InternalAppError(format!("Server error: {}", e)))?;
+
+    Ok(())
+}
+
+/// Reject requests that fail the access policy
+async fn access_guard(State(state): State<AppState>, request: Request, next: Next) -> Response {
+    match state.policy.check(request.headers()) {
+        Ok(()) => next.run(request).await,
+        Err(denied) => denied.into_response(),
+    }
+}
+
+/// Parse a POST body into one or more JSON-RPC messages (batches are allowed)
+fn parse_messages(body: &[u8]) -> Result<Vec<ClientJsonRpcMessage>, serde_json::Error> {
+    match serde_json::from_slice::<serde_json::Value>(body)? {
+        serde_json::Value::Array(items) => items.into_iter().map(parse_client_message).collect(),
+        value => Ok(vec![parse_client_message(value)?]),
+    }
+}
+
+fn is_initialize(message: &ClientJsonRpcMessage) -> bool {
+    matches!(
+        message,
+        JsonRpcMessage::Request(JsonRpcRequest {
+            request: ClientRequest::InitializeRequest(_),
+            ..
+        })
+    )
+}
+
+/// Progress token of a tool call, already lifted into its arguments
+fn progress_token(request: &ClientRequest) -> Option<ProgressToken> {
+    let ClientRequest::CallToolRequest(call) = request else {
+        return None;
+    };
+    let token = call.params.arguments.as_ref()?.get("progress_token")?;
+    serde_json::from_value(token.clone()).ok()
+}
+
+fn session_id_from_headers(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get(SESSION_ID_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string)
+}
+
+fn error_response(status: StatusCode, message: impl Into<String>) -> Response {
+    (status, message.into()).into_response()
+}
+
+fn too_many_sessions() -> Response {
+    error_response(StatusCode::SERVICE_UNAVAILABLE, "Too many sessions")
+}
+
+fn event_stream(
+    rx: mpsc::Receiver<ServerJsonRpcMessage>,
+) -> impl Stream<Item = Result<Event, Infallible>> {
+    ReceiverStream::new(rx).filter_map(|message| async move {
+        match serde_json::to_string(&message) {
+            Ok(json) => Some(Ok(Event::default().event("message").data(json))),
+            Err(e) => {
+                tracing::error!("Failed to serialize server message: {}", e);
+                None
+            }
+        }
+    })
+}
+
+fn with_session_header(mut response: Response, session_id: &str) -> Response {
+    if let Ok(value) = HeaderValue::from_str(session_id) {
+        response.headers_mut().insert(SESSION_ID_HEADER, value);
+    }
+    response
+}
+
+async fn forward(session: &Session, messages: Vec<ClientJsonRpcMessage>) -> Result<(), Response> {
+    for message in messages {
+        if session.to_service.send(message).await.is_err() {
+            return Err(error_response(StatusCode::GONE, "Session has been closed"));
+        }
+    }
+    Ok(())
+}
+
+/// POST /mcp: deliver client messages, streaming back responses as SSE
+async fn streamable_post_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    let messages = match parse_messages(&body) {
+        Ok(messages) if !messages.is_empty() => messages,
+        Ok(_) => return error_response(StatusCode::BAD_REQUEST, "Empty JSON-RPC batch"),
+        Err(e) => {
+            return error_response(StatusCode::BAD_REQUEST, format!("Invalid JSON-RPC: {}", e));
+        }
+    };
+
+    let (session_id, session) = match session_id_from_headers(&headers) {
+        Some(id) => match state.get_session(&id).await {
+            Some(session) => (Arc::from(id), session),
+            None => return error_response(StatusCode::NOT_FOUND, "Unknown session"),
+        },
+        None if messages.iter().any(is_initialize) => match state.create_session().await {
+            Some(created) => created,
+            None => return too_many_sessions(),
+        },
+        None => {
+            return error_response(
+                StatusCode::BAD_REQUEST,
+                format!("Missing {} header", SESSION_ID_HEADER),
+            );
+        }
+    };
+
+    session.touch();
+
+    let requests: Vec<(RequestId, Option<ProgressToken>)> = messages
+        .iter()
+        .filter_map(|m| match m {
+            JsonRpcMessage::Request(JsonRpcRequest { id, request, .. }) => {
+                Some((id.clone(), progress_token(request)))
+            }
+            _ => None,
+        })
+        .collect();
+
+    // Notifications and responses only: acknowledge without a stream
+    if requests.is_empty() {
+        if let Err(response) = forward(&session, messages).await {
+            return response;
+        }
+        return with_session_header(StatusCode::ACCEPTED.into_response(), &session_id);
+    }
+
+    // The stream closes once every request in this POST has been answered
+    let (tx, rx) = mpsc::channel(CHANNEL_CAPACITY);
+    {
+        let mut pending = session.pending.lock().expect("pending lock poisoned");
+        for (id, progress_token) in requests {
+            let tx = tx.clone();
+            pending.insert(id, PendingRequest { tx, progress_token });
+        }
+    }
+    drop(tx);
+
+    if let Err(response) = forward(&session, messages).await {
+        return response;
+    }
+
+    let response = Sse::new(event_stream(rx))
+        .keep_alive(KeepAlive::default())
+        .into_response();
+    with_session_header(response, &session_id)
+}
+
+/// GET /mcp: open a standalone stream for server-initiated messages
+async fn streamable_get_handler(State(state): State<AppState>, headers: HeaderMap) -> Response {
+    let Some(session_id) = session_id_from_headers(&headers) else {
+        return error_response(
+            StatusCode::BAD_REQUEST,
+            format!("Missing {} header", SESSION_ID_HEADER),
+        );
+    };
+    let Some(session) = state.get_session(&session_id).await else {
+        return error_response(StatusCode::NOT_FOUND, "Unknown session");
+    };
+    session.touch();
+
+    let (tx, rx) = mpsc::channel(CHANNEL_CAPACITY);
+    *session.standalone.lock().expect("standalone lock poisoned") = Some(tx);
+
+    Sse::new(event_stream(rx))
+        .keep_alive(KeepAlive::default())
+        .into_response()
+}
+
+/// DELETE /mcp: terminate a session
+async fn streamable_delete_handler(State(state): State<AppState>, headers: HeaderMap) -> Response {
+    let Some(session_id) = session_id_from_headers(&headers) else {
+        return error_response(
+            StatusCode::BAD_REQUEST,
+            format!("Missing {} header", SESSION_ID_HEADER),
+        );
+    };
+
+    match state.sessions.write().await.remove(session_id.as_str()) {
+        Some(session) => {
+            session.cancel.cancel();
+            StatusCode::NO_CONTENT.into_response()
+        }
+        None => error_response(StatusCode::NOT_FOUND, "Unknown session"),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MessageQuery {
+    session_id: String,
+}
+
+/// GET /sse: legacy HTTP+SSE transport, one stream per session
+///
+/// The session lives as long as the stream: once the client disconnects,
+/// its service is cancelled and the session removes itself from the store.
+async fn sse_handler(State(state): State<AppState>) -> Response {
+    let Some((session_id, session)) = state.create_session().await else {
+        return too_many_sessions();
+    };
+
+    let (tx, rx) = mpsc::channel(CHANNEL_CAPACITY);
+    *session.standalone.lock().expect("standalone lock poisoned") = Some(tx);
+    let guard = session.cancel.clone().drop_guard();
+
+    let endpoint = futures::stream::once(async move {
+        Ok(Event::default()
+            .event("endpoint")
+            .data(format!("{}?sessionId={}", MESSAGE_PATH, session_id)))
+    });
+
+    let events = endpoint.chain(event_stream(rx)).map(move |event| {
+        let _session = &guard;
+        event
+    });
+    Sse::new(events)
+        .keep_alive(KeepAlive::default())
+        .into_response()
+}
+
+/// POST /message: legacy HTTP+SSE transport, responses are delivered on the SSE stream
+async fn message_handler(
+    State(state): State<AppState>,
+    Query(MessageQuery { session_id }): Query<MessageQuery>,
+    body: Bytes,
+) -> Response {
+    let Some(session) = state.get_session(&session_id).await else {
+        return error_response(StatusCode::NOT_FOUND, "Unknown session");
+    };
+    session.touch();
+
+    let messages = match parse_messages(&body) {
+        Ok(messages) => messages,
+        Err(e) => {
+            return error_response(StatusCode::BAD_REQUEST, format!("Invalid JSON-RPC: {}", e));
+        }
+    };
+
+    match forward(&session, messages).await {
+        Ok(()) => StatusCode::ACCEPTED.into_response(),
+        Err(response) => response,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_messages() {
+        let single = br#"{"jsonrpc":"2.0","id":1,"method":"ping"}"#;
+        assert_eq!(parse_messages(single).unwrap().len(), 1);
+
+        let batch = br#"[{"jsonrpc":"2.0","id":1,"method":"ping"},{"jsonrpc":"2.0","method":"notifications/initialized"}]"#;
+        assert_eq!(parse_messages(batch).unwrap().len(), 2);
+
+        assert!(parse_messages(b"not json").is_err());
+    }
+
+    #[test]
+    fn test_is_initialize() {
+        let init = br#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"protocolVersion":"2024-11-05","capabilities":{},"clientInfo":{"name":"test","version":"0.1.0"}}}"#;
+        let messages = parse_messages(init).unwrap();
+        assert!(is_initialize(&messages[0]));
+
+        let ping = br#"{"jsonrpc":"2.0","id":2,"method":"ping"}"#;
+        let messages = parse_messages(ping).unwrap();
+        assert!(!is_initialize(&messages[0]));
+    }
+
+    fn request_headers(pairs: &[(header::HeaderName, &str)]) -> HeaderMap {
+        pairs
+            .iter()
+            .map(|(name, value)| (name.clone(), HeaderValue::from_str(value).unwrap()))
+            .collect()
+    }
+
+    #[test]
+    fn test_access_policy() {
+        let policy = AccessPolicy {
+            auth_token: None,
+            allowed_origins: vec!["https://app.example.com".to_string()],
+            loopback_hosts_only: true,
+        };
+        let check = |pairs: &[(header::HeaderName, &str)]| policy.check(&request_headers(pairs));
+
+        assert_eq!(check(&[(header::HOST, "127.0.0.1:18080")]), Ok(()));
+        assert_eq!(
+            check(&[
+                (header::HOST, "localhost:18080"),
+                (header::ORIGIN, "http://localhost:3000")
+            ]),
+            Ok(())
+        );
+        assert_eq!(
+            check(&[
+                (header::HOST, "[::1]:18080"),
+                (header::ORIGIN, "https://app.example.com/")
+            ]),
+            Ok(())
+        );
+        // DNS rebinding: the browser sends the attacker's domain as Host and Origin
+        assert_eq!(
+            check(&[(header::HOST, "evil.example:18080")]),
+            Err(AccessDenied::Host)
+        );
+        assert_eq!(
+            check(&[
+                (header::HOST, "localhost:18080"),
+                (header::ORIGIN, "http://evil.example")
+            ]),
+            Err(AccessDenied::Origin)
+        );
+        assert_eq!(
+            check(&[(header::HOST, "localhost:18080"), (header::ORIGIN, "null")]),
+            Err(AccessDenied::Origin)
+        );
+
+        let policy = AccessPolicy {
+            auth_token: Some("secret".to_string()),
+            allowed_origins: Vec::new(),
+            loopback_hosts_only: false,
+        };
+        let check = |pairs: &[(header::HeaderName, &str)]| policy.check(&request_headers(pairs));
+        assert_eq!(
+            check(&[(header::HOST, "mcp.internal")]),
+            Err(AccessDenied::Token)
+        );
+        assert_eq!(
+            check(&[
+                (header::HOST, "mcp.internal"),
+                (header::AUTHORIZATION, "Bearer wrong")
+            ]),
+            Err(AccessDenied::Token)
+        );
+        assert_eq!(
+            check(&[
+                (header::HOST, "mcp.internal"),
+                (header::AUTHORIZATION, "Bearer secret")
+            ]),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_non_loopback_bind_requires_token() {
+        // MCP_AUTH_TOKEN is not set in the test environment
+        assert!(AccessPolicy::from_env("0.0.0.0:18080".parse().unwrap()).is_err());
+        assert!(AccessPolicy::from_env("127.0.0.1:18080".parse().unwrap()).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_delete_ends_session() {
+        let state = AppState {
+            sessions: SessionStore::default(),
+            policy: Arc::default(),
+            limits: SessionLimits::default(),
+        };
+        let (session_id, session) = state.create_session().await.unwrap();
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            SESSION_ID_HEADER,
+            HeaderValue::from_str(&session_id).unwrap(),
+        );
+        let response = streamable_delete_handler(State(state.clone()), headers).await;
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+        // The service drops its input stream once the session is cancelled
+        tokio::time::timeout(
+            std::time::Duration::from_secs(5),
+            session.to_service.closed(),
+        )
+        .await
+        .expect("session service still running after DELETE");
+        assert!(state.get_session(&session_id).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_session_limits() {
+        let state = AppState {
+            sessions: SessionStore::default(),
+            policy: Arc::default(),
+            limits: SessionLimits {
+                max_sessions: 1,
+                idle_timeout: Duration::ZERO,
+            },
+        };
+        let (session_id, session) = state.create_session().await.unwrap();
+        assert!(state.create_session().await.is_none());
+
+        // A session with an open stream is kept however long it has been quiet
+        let (tx, _rx) = mpsc::channel(1);
+        *session.standalone.lock().unwrap() = Some(tx);
+        assert_eq!(state.reap_idle_sessions().await, 0);
+
+        *session.standalone.lock().unwrap() = None;
+        assert_eq!(state.reap_idle_sessions().await, 1);
+        tokio::time::timeout(Duration::from_secs(5), session.to_service.closed())
+            .await
+            .expect("session service still running after expiry");
+        assert!(state.get_session(&session_id).await.is_none());
+        assert!(state.create_session().await.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_routing() {
+        let (to_service, _to_service_rx) = mpsc::channel(1);
+        let session = Session::new(to_service);
+        let (first_tx, mut first_rx) = mpsc::channel(4);
+        let (second_tx, mut second_rx) = mpsc::channel(4);
+        {
+            let mut pending = session.pending.lock().unwrap();
+            pending.insert(
+                RequestId::Number(1),
+                PendingRequest {
+                    tx: first_tx,
+                    progress_token: Some(ProgressToken::String("t1".into())),
+                },
+            );
+            pending.insert(
+                RequestId::Number(2),
+                PendingRequest {
+                    tx: second_tx,
+                    progress_token: None,
+                },
+            );
+        }
+        let message = |value: serde_json::Value| -> ServerJsonRpcMessage {
+            serde_json::from_value(value).unwrap()
+        };
+        let progress = |token: &str| {
+            message(serde_json::json!({
+                "jsonrpc": "2.0",
+                "method": "notifications/progress",
+                "params": { "progressToken": token, "progress": 1 }
+            }))
+        };
+
+        session.dispatch(progress("t1")).await;
+        assert!(first_rx.try_recv().is_ok());
+        assert!(second_rx.try_recv().is_err());
+
+        // Without a standalone stream, unrouted messages are dropped
+        session.dispatch(progress("unknown")).await;
+        assert!(first_rx.try_recv().is_err());
+        assert!(second_rx.try_recv().is_err());
+
+        let (standalone_tx, mut standalone_rx) = mpsc::channel(4);
+        *session.standalone.lock().unwrap() = Some(standalone_tx);
+        session.dispatch(progress("unknown")).await;
+        assert!(standalone_rx.try_recv().is_ok());
+        assert!(first_rx.try_recv().is_err());
+        assert!(second_rx.try_recv().is_err());
+
+        session
+            .dispatch(message(
+                serde_json::json!({ "jsonrpc": "2.0", "id": 2, "result": {} }),
+            ))
+            .await;
+        assert!(second_rx.try_recv().is_ok());
+        assert!(!session
+            .pending
+            .lock()
+            .unwrap()
+            .contains_key(&RequestId::Number(2)));
+    }
+}
@@ -0,0 +1,93 @@
+use rmcp::model::ClientJsonRpcMessage;
+use serde_json::{Map, Value};
+
+/// Parse a client message, first copying a tool call's `_meta.progressToken`
+/// into its `progress_token` argument
+///
+/// rmcp drops `_meta` when it parses `tools/call` parameters, so tools read
+/// the token from their arguments. A `progress_token` argument the client
+/// passed explicitly is kept.
+pub(crate) fn parse_client_message(mut value: Value) -> serde_json::Result<ClientJsonRpcMessage> {
+    lift_progress_token(&mut value);
+    serde_json::from_value(value)
+}
+
+fn lift_progress_token(value: &mut Value) {
+    if value.get("method").and_then(Value::as_str) != Some("tools/call") {
+        return;
+    }
+    let Some(params) = value.get_mut("params").and_then(Value::as_object_mut) else {
+        return;
+    };
+    let Some(token) = params
+        .get("_meta")
+        .and_then(|meta| meta.get("progressToken"))
+        .cloned()
+    else {
+        return;
+    };
+    let arguments = params
+        .entry("arguments")
+        .or_insert_with(|| Value::Object(Map::new()));
+    if let Some(arguments) = arguments.as_object_mut() {
+        arguments.entry("progress_token").or_insert(token);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rmcp::model::{ClientRequest, JsonRpcMessage, JsonRpcRequest};
+    use serde_json::json;
+
+    fn tool_arguments(message: Value) -> Option<Map<String, Value>> {
+        match parse_client_message(message).unwrap() {
+            JsonRpcMessage::Request(JsonRpcRequest {
+                request: ClientRequest::CallToolRequest(call),
+                ..
+            }) => call.params.arguments,
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_lift_progress_token() {
+        let arguments = tool_arguments(json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "tools/call",
+            "params": {
+                "name": "fetch_paper",
+                "arguments": { "arxiv_id": "1706.03762" },
+                "_meta": { "progressToken": "abc" }
+            }
+        }))
+        .unwrap();
+        assert_eq!(arguments["progress_token"], "abc");
+        assert_eq!(arguments["arxiv_id"], "1706.03762");
+
+        // Calls without arguments still get the token
+        let arguments = tool_arguments(json!({
+            "jsonrpc": "2.0",
+            "id": 2,
+            "method": "tools/call",
+            "params": { "name": "search_papers", "_meta": { "progressToken": 7 } }
+        }))
+        .unwrap();
+        assert_eq!(arguments["progress_token"], 7);
+
+        // An explicit argument wins
+        let arguments = tool_arguments(json!({
+            "jsonrpc": "2.0",
+            "id": 3,
+            "method": "tools/call",
+            "params": {
+                "name": "fetch_paper",
+                "arguments": { "progress_token": "explicit" },
+                "_meta": { "progressToken": "meta" }
+            }
+        }))
+        .unwrap();
+        assert_eq!(arguments["progress_token"], "explicit");
+    }
+}
@@ -1,3 +1,6 @@
+pub mod http;
+mod message;
 pub mod stdio;
 
+pub use http::run_http_server;
 pub use stdio::run_stdio_server;
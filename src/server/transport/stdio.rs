@@ -1,26 +1,158 @@
+use crate::server::transport::message::parse_client_message;
 use crate::server::PaperInterpreterService;
-use rmcp::transport::stdio;
+use futures::{SinkExt, Stream, StreamExt};
+use rmcp::model::{ClientJsonRpcMessage, ErrorCode, ErrorData, ServerJsonRpcMessage};
+use rmcp::transport::io::from_async_write;
 use rmcp::ServiceExt;
+use serde_json::{json, Value};
 use shared::errors::{AppError, AppResult};
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+use tokio::sync::mpsc;
+use tokio_util::sync::PollSender;
+
+const CHANNEL_CAPACITY: usize = 64;
 
 /// Run the MCP server with stdio transport
 pub async fn run_stdio_server() -> AppResult<()> {
     tracing::info!("Starting MCP server with stdio transport");
 
-    let transport = stdio();
-    let service = PaperInterpreterService::new();
+    // One writer for stdout, shared by the service and the error replies to
+    // lines the service never sees
+    let (out_tx, mut out_rx) = mpsc::channel::<Value>(CHANNEL_CAPACITY);
+    let writer = tokio::spawn(async move {
+        let mut stdout = from_async_write::<Value, _>(tokio::io::stdout());
+        while let Some(message) = out_rx.recv().await {
+            if let Err(e) = stdout.send(message).await {
+                tracing::error!("Failed to write to stdout: {}", e);
+                break;
+            }
+        }
+    });
+
+    let replies = out_tx.clone();
+    let incoming = read_lines(tokio::io::stdin()).filter_map(move |line| {
+        let replies = replies.clone();
+        async move {
+            match parse_line(&line) {
+                Ok(message) => Some(message),
+                Err(reply) => {
+                    if let Some(reply) = reply {
+                        let _ = replies.send(reply).await;
+                    }
+                    None
+                }
+            }
+        }
+    });
+    let outgoing = PollSender::new(out_tx)
+        .sink_map_err(std::io::Error::other)
+        .with(|message: ServerJsonRpcMessage| {
+            futures::future::ready(serde_json::to_value(message).map_err(std::io::Error::other))
+        });
+
+    let served = async {
+        let service = PaperInterpreterService::new();
+        let server = service.serve((outgoing, incoming)).await.map_err(|e| {
+            AppError::InternalAppError(format!("Failed to start stdio server: {}", e))
+        })?;
+
+        tracing::info!("MCP server running on stdio");
+
+        server
+            .waiting()
+            .await
+            .map_err(|e| AppError::InternalAppError(format!("Server error: {}", e)))?;
+        Ok(())
+    }
+    .await;
+
+    // Flush replies still queued for stdout, whether or not the service failed
+    let _ = writer.await;
+    served
+}
+
+/// Non-empty lines of `reader`, ending at EOF or the first read error
+fn read_lines(reader: impl AsyncRead + Unpin) -> impl Stream<Item = String> {
+    futures::stream::unfold(BufReader::new(reader).lines(), |mut lines| async move {
+        match lines.next_line().await {
+            Ok(Some(line)) => Some((line, lines)),
+            Ok(None) => None,
+            Err(e) => {
+                tracing::error!("Error reading from stdin: {}", e);
+                None
+            }
+        }
+    })
+    .filter(|line| futures::future::ready(!line.trim().is_empty()))
+}
+
+/// Parse one line from stdin into a client message
+///
+/// Messages are read as JSON first so tool calls keep `_meta.progressToken`.
+/// On failure, returns the JSON-RPC error to send back, if any: -32700 for a
+/// line that is not JSON, -32600 for a request that is not a valid message.
+/// Notifications and responses from the client are never answered.
+fn parse_line(line: &str) -> Result<ClientJsonRpcMessage, Option<Value>> {
+    let value = match serde_json::from_str::<Value>(line) {
+        Ok(value) => value,
+        Err(e) => {
+            tracing::error!("Invalid JSON on stdin: {}", e);
+            return Err(Some(error_reply(
+                Value::Null,
+                ErrorCode::PARSE_ERROR,
+                format!("Parse error: {}", e),
+            )));
+        }
+    };
+
+    let id = value
+        .get("id")
+        .filter(|_| value.get("result").is_none() && value.get("error").is_none())
+        .cloned();
+    parse_client_message(value).map_err(|e| {
+        tracing::error!("Invalid message on stdin: {}", e);
+        id.map(|id| {
+            error_reply(
+                id,
+                ErrorCode::INVALID_REQUEST,
+                format!("Invalid request: {}", e),
+            )
+        })
+    })
+}
+
+fn error_reply(id: Value, code: ErrorCode, message: String) -> Value {
+    let error = ErrorData {
+        code,
+        message: message.into(),
+        data: None,
+    };
+    json!({ "jsonrpc": "2.0", "id": id, "error": error })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    let server = service
-        .serve(transport)
-        .await
-        .map_err(|e| AppError::InternalAppError(format!("Failed to start stdio server: {}", e)))?;
+    #[test]
+    fn test_parse_line() {
+        assert!(parse_line(r#"{"jsonrpc":"2.0","id":1,"method":"ping"}"#).is_ok());
 
-    tracing::info!("MCP server running on stdio");
+        let reply = parse_line("{not json").unwrap_err().unwrap();
+        assert_eq!(reply["id"], Value::Null);
+        assert_eq!(reply["error"]["code"], -32700);
 
-    server
-        .waiting()
-        .await
-        .map_err(|e| AppError::InternalAppError(format!("Server error: {}", e)))?;
+        let reply = parse_line(r#"{"jsonrpc":"2.0","id":7,"method":"tools/call","params":3}"#)
+            .unwrap_err()
+            .unwrap();
+        assert_eq!(reply["id"], 7);
+        assert_eq!(reply["error"]["code"], -32600);
 
-    Ok(())
+        // Invalid notifications get no reply
+        assert!(
+            parse_line(r#"{"jsonrpc":"2.0","method":"notifications/bogus","params":3}"#)
+                .unwrap_err()
+                .is_none()
+        );
+    }
 }
@@ -0,0 +1,249 @@
+use crate::models::paper::Paper;
+use crate::sources::http::{send_checked, web_url};
+use crate::sources::paper_source::PaperSource;
+use futures::future::BoxFuture;
+use shared::errors::{AppError, AppResult};
+
+/// ACL Anthology site; every paper has `{id}.bib` and `{id}.pdf`
+pub const ACL_ANTHOLOGY_URL: &str = "https://aclanthology.org";
+
+/// Papers in the ACL Anthology, with metadata from their BibTeX entries
+pub struct AclAnthologySource;
+
+impl AclAnthologySource {
+    async fn fetch_paper(&self, id: &str) -> AppResult<Paper> {
+        let response = send_checked(
+            reqwest::Client::new().get(format!("{}/{}.bib", ACL_ANTHOLOGY_URL, id)),
+            "ACL Anthology",
+        )
+        .await?;
+        let bib = response
+            .text()
+            .await
+            .map_err(|e| AppError::NetworkError(format!("ACL Anthology response error: {}", e)))?;
+
+        paper_from_bibtex(&bib, id)
+    }
+}
+
+impl PaperSource for AclAnthologySource {
+    fn name(&self) -> &'static str {
+        "acl"
+    }
+
+    fn parse_id(&self, input: &str) -> Option<String> {
+        parse_acl_id(input)
+    }
+
+    fn fetch<'a>(&'a self, id: &'a str) -> BoxFuture<'a, AppResult<Paper>> {
+        Box::pin(self.fetch_paper(id))
+    }
+}
+
+/// Anthology ID from an aclanthology.org (or old aclweb.org) URL, or a bare ID
+///
+/// IDs are new-style (`2020.acl-main.1`) or old-style (`P19-1001`).
+pub fn parse_acl_id(input: &str) -> Option<String> {
+    let input = input.trim();
+    let candidate = match web_url(input) {
+        Some(url) => {
+            let host = url.host_str()?;
+            let path = match host {
+                "aclanthology.org" | "www.aclanthology.org" => url.path(),
+                "aclweb.org" | "www.aclweb.org" => url.path().strip_prefix("/anthology")?,
+                _ => return None,
+            };
+            let segment = path.trim_matches('/').split('/').next()?;
+            segment
+                .strip_suffix(".pdf")
+                .or_else(|| segment.strip_suffix(".bib"))
+                .unwrap_or(segment)
+                .to_string()
+        }
+        None => input.to_string(),
+    };
+    is_acl_id(&candidate).then_some(candidate)
+}
+
+fn is_acl_id(id: &str) -> bool {
+    let parts: Vec<&str> = id.split('.').collect();
+    match parts.as_slice() {
+        // 2020.acl-main.1
+        [year, volume, number] => {
+            year.len() == 4
+                && year.chars().all(|c| c.is_ascii_digit())
+                && !volume.is_empty()
+                && volume
+                    .chars()
+                    .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+                && !number.is_empty()
+                && number.chars().all(|c| c.is_ascii_digit())
+        }
+        // P19-1001
+        [old] => {
+            let bytes = old.as_bytes();
+            bytes.len() == 8
+                && bytes[0].is_ascii_uppercase()
+                && bytes[1..3].iter().all(u8::is_ascii_digit)
+                && bytes[3] == b'-'
+                && bytes[4..].iter().all(u8::is_ascii_digit)
+        }
+        _ => false,
+    }
+}
+
+/// Map the Anthology's BibTeX entry for `id` to a Paper
+fn paper_from_bibtex(bib: &str, id: &str) -> AppResult<Paper> {
+    let title = bibtex_field(bib, "title")
+        .ok_or_else(|| AppError::PaperNotFound(format!("ACL Anthology entry {}", id)))?;
+
+    let published_date = bibtex_field(bib, "year").map(|year| {
+        match bibtex_field(bib, "month").and_then(|m| month_number(&m)) {
+            Some(month) => format!("{}-{:02}", year, month),
+            None => year,
+        }
+    });
+
+    Ok(Paper {
+        title,
+        authors: bibtex_field(bib, "author")
+            .map(|authors| authors.split(" and ").map(bibtex_name).collect())
+            .unwrap_or_default(),
+        abstract_text: bibtex_field(bib, "abstract").unwrap_or_default(),
+        arxiv_id: None,
+        ss_id: None,
+        categories: vec![],
+        published_date,
+        pdf_url: Some(format!("{}/{}.pdf", ACL_ANTHOLOGY_URL, id)),
+        content: None,
+        structure: None,
+    })
+}
+
+/// Value of `name = {...}` or `name = "..."`, with braces removed and whitespace collapsed
+fn bibtex_field(bib: &str, name: &str) -> Option<String> {
+    let mut search_from = 0;
+    let value_start = loop {
+        let pos = search_from + bib[search_from..].find(name)?;
+        search_from = pos + name.len();
+        let preceded_ok = bib[..pos]
+            .chars()
+            .next_back()
+            .is_none_or(|c| c.is_whitespace() || c == ',');
+        let rest = bib[search_from..].trim_start();
+        if preceded_ok && let Some(value) = rest.strip_prefix('=') {
+            break value.trim_start();
+        }
+    };
+
+    let raw = match value_start.chars().next()? {
+        '{' => {
+            let mut depth = 0;
+            let end = value_start.char_indices().find_map(|(i, c)| {
+                match c {
+                    '{' => depth += 1,
+                    '}' => depth -= 1,
+                    _ => {}
+                }
+                (depth == 0).then_some(i)
+            })?;
+            &value_start[1..end]
+        }
+        '"' => {
+            let end = value_start[1..].find('"')? + 1;
+            &value_start[1..end]
+        }
+        // Bare values, e.g., month = jun
+        _ => value_start
+            .split([',', '\n', '}'])
+            .next()
+            .unwrap_or_default(),
+    };
+
+    let value = raw
+        .replace(['{', '}'], "")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    (!value.is_empty()).then_some(value)
+}
+
+/// "Vaswani, Ashish" to "Ashish Vaswani"
+fn bibtex_name(name: &str) -> String {
+    match name.split_once(',') {
+        Some((last, first)) => format!("{} {}", first.trim(), last.trim()),
+        None => name.trim().to_string(),
+    }
+}
+
+fn month_number(month: &str) -> Option<u32> {
+    const MONTHS: [&str; 12] = [
+        "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+    ];
+    let month = month.trim().to_lowercase();
+    month
+        .parse()
+        .ok()
+        .filter(|m| (1..=12).contains(m))
+        .or_else(|| {
+            MONTHS
+                .iter()
+                .position(|m| month.starts_with(m))
+                .map(|i| i as u32 + 1)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_acl_id() {
+        assert_eq!(
+            parse_acl_id("https://aclanthology.org/2020.acl-main.1.pdf"),
+            Some("2020.acl-main.1".to_string())
+        );
+        assert_eq!(
+            parse_acl_id("https://www.aclweb.org/anthology/P19-1001/"),
+            Some("P19-1001".to_string())
+        );
+        assert_eq!(
+            parse_acl_id("2023.findings-emnlp.12"),
+            Some("2023.findings-emnlp.12".to_string())
+        );
+        assert_eq!(parse_acl_id("2301.00001"), None);
+        assert_eq!(
+            parse_acl_id("https://aclanthology.org/events/acl-2020/"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_paper_from_bibtex() {
+        let bib = r#"@inproceedings{devlin-etal-2019-bert,
+    title = "{BERT}: Pre-training of Deep Bidirectional Transformers for Language Understanding",
+    author = "Devlin, Jacob  and
+      Chang, Ming-Wei",
+    booktitle = "Proceedings of NAACL",
+    month = jun,
+    year = "2019",
+    abstract = {We introduce a new language representation model called {BERT}.},
+}"#;
+        let paper = paper_from_bibtex(bib, "N19-1423").unwrap();
+
+        assert_eq!(
+            paper.title,
+            "BERT: Pre-training of Deep Bidirectional Transformers for Language Understanding"
+        );
+        assert_eq!(paper.authors, vec!["Jacob Devlin", "Ming-Wei Chang"]);
+        assert_eq!(
+            paper.abstract_text,
+            "We introduce a new language representation model called BERT."
+        );
+        assert_eq!(paper.published_date.as_deref(), Some("2019-06"));
+        assert_eq!(
+            paper.pdf_url.as_deref(),
+            Some("https://aclanthology.org/N19-1423.pdf")
+        );
+    }
+}
@@ -0,0 +1,261 @@
+use crate::models::paper::PaperSummary;
+use crate::models::request::SortOrder;
+use crate::sources::http::{retry_after_secs, send_with_retry};
+use crate::sources::ids::extract_arxiv_id;
+use chrono::NaiveDate;
+use serde::Deserialize;
+use shared::errors::{AppError, AppResult};
+
+/// arXiv export API endpoint
+pub const ARXIV_API_URL: &str = "http://export.arxiv.org/api/query";
+
+/// Search query for the arXiv API
+#[derive(Debug, Clone, Default)]
+pub struct ArxivQuery {
+    pub keywords: String,
+    pub author: Option<String>,
+    pub category: Option<String>,
+    pub published_after: Option<NaiveDate>,
+    pub published_before: Option<NaiveDate>,
+    pub sort_by: SortOrder,
+    pub max_results: u32,
+}
+
+impl ArxivQuery {
+    /// Build the `search_query` parameter using arXiv field prefixes
+    pub fn search_query(&self) -> String {
+        let mut clauses: Vec<String> = self
+            .keywords
+            .split_whitespace()
+            .map(|term| format!("all:{}", term))
+            .collect();
+
+        // arXiv matches authors by surname; the full name is post-filtered by the caller
+        if let Some(surname) = self
+            .author
+            .as_deref()
+            .and_then(|a| a.split_whitespace().last())
+        {
+            clauses.push(format!("au:{}", surname));
+        }
+
+        if let Some(ref category) = self.category {
+            clauses.push(format!("cat:{}", category));
+        }
+
+        if self.published_after.is_some() || self.published_before.is_some() {
+            let from = self
+                .published_after
+                .map(|d| d.format("%Y%m%d0000").to_string())
+                .unwrap_or_else(|| "199101010000".to_string());
+            let to = self
+                .published_before
+                .map(|d| d.format("%Y%m%d2359").to_string())
+                .unwrap_or_else(|| "299912312359".to_string());
+            clauses.push(format!("submittedDate:[{} TO {}]", from, to));
+        }
+
+        clauses.join(" AND ")
+    }
+
+    /// arXiv has no citation counts, so citation order falls back to relevance
+    fn sort_by_param(&self) -> &'static str {
+        match self.sort_by {
+            SortOrder::Date => "submittedDate",
+            SortOrder::Relevance | SortOrder::Citations => "relevance",
+        }
+    }
+}
+
+/// Client for the arXiv export API
+pub struct ArxivClient {
+    http: reqwest::Client,
+    base_url: String,
+}
+
+impl ArxivClient {
+    pub fn new() -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            base_url: ARXIV_API_URL.to_string(),
+        }
+    }
+
+    /// Search arXiv and return summaries in the order arXiv ranks them
+    pub async fn search(&self, query: &ArxivQuery) -> AppResult<Vec<PaperSummary>> {
+        let search_query = query.search_query();
+        tracing::debug!("arXiv search_query: {}", search_query);
+
+        self.query(&[
+            ("search_query", search_query.as_str()),
+            ("start", "0"),
+            ("max_results", &query.max_results.to_string()),
+            ("sortBy", query.sort_by_param()),
+            ("sortOrder", "descending"),
+        ])
+        .await
+    }
+
+    /// Fetch metadata for a single paper by arXiv ID (no PDF download)
+    pub async fn fetch_by_id(&self, arxiv_id: &str) -> AppResult<PaperSummary> {
+        self.query(&[("id_list", arxiv_id), ("max_results", "1")])
+            .await?
+            .into_iter()
+            .next()
+            .ok_or_else(|| AppError::PaperNotFound(format!("arXiv ID {}", arxiv_id)))
+    }
+
+    async fn query(&self, params: &[(&str, &str)]) -> AppResult<Vec<PaperSummary>> {
+        let response = send_with_retry(self.http.get(&self.base_url).query(params))
+            .await
+            .map_err(|e| AppError::NetworkError(format!("arXiv request failed: {}", e)))?;
+
+        let status = response.status();
+        if status.as_u16() == 429 || status.as_u16() == 503 {
+            return Err(AppError::RateLimitExceeded {
+                message: format!("arXiv returned {}", status),
+                retry_after_secs: retry_after_secs(response.headers()),
+            });
+        }
+        if !status.is_success() {
+            return Err(AppError::NetworkError(format!("arXiv returned {}", status)));
+        }
+
+        let body = response
+            .text()
+            .await
+            .map_err(|e| AppError::NetworkError(format!("arXiv response error: {}", e)))?;
+
+        parse_feed(&body)
+    }
+}
+
+impl Default for ArxivClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct Feed {
+    #[serde(rename = "entry", default)]
+    entries: Vec<Entry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Entry {
+    id: String,
+    title: String,
+    #[serde(default)]
+    summary: String,
+    #[serde(default)]
+    published: Option<String>,
+    #[serde(rename = "author", default)]
+    authors: Vec<EntryAuthor>,
+    #[serde(rename = "category", default)]
+    categories: Vec<EntryCategory>,
+    #[serde(rename = "link", default)]
+    links: Vec<EntryLink>,
+}
+
+#[derive(Debug, Deserialize)]
+struct EntryAuthor {
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct EntryCategory {
+    #[serde(rename = "@term")]
+    term: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct EntryLink {
+    #[serde(rename = "@href")]
+    href: String,
+    #[serde(rename = "@title", default)]
+    title: Option<String>,
+}
+
+/// Parse an arXiv Atom feed into paper summaries
+fn parse_feed(xml: &str) -> AppResult<Vec<PaperSummary>> {
+    let feed: Feed = quick_xml::de::from_str(xml)
+        .map_err(|e| AppError::InternalAppError(format!("Invalid arXiv feed: {}", e)))?;
+
+    Ok(feed
+        .entries
+        .into_iter()
+        .map(|entry| PaperSummary {
+            title: normalize_whitespace(&entry.title),
+            authors: entry.authors.into_iter().map(|a| a.name).collect(),
+            abstract_text: normalize_whitespace(&entry.summary),
+            arxiv_id: extract_arxiv_id(&entry.id),
+            ss_id: None,
+            categories: entry.categories.into_iter().map(|c| c.term).collect(),
+            published_date: entry.published,
+            citation_count: None,
+            pdf_url: entry
+                .links
+                .into_iter()
+                .find(|l| l.title.as_deref() == Some("pdf"))
+                .map(|l| l.href),
+        })
+        .collect())
+}
+
+fn normalize_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_search_query() {
+        let query = ArxivQuery {
+            keywords: "attention mechanism".to_string(),
+            author: Some("Ashish Vaswani".to_string()),
+            category: Some("cs.CL".to_string()),
+            published_after: NaiveDate::from_ymd_opt(2017, 1, 1),
+            ..Default::default()
+        };
+        assert_eq!(
+            query.search_query(),
+            "all:attention AND all:mechanism AND au:Vaswani AND cat:cs.CL \
+             AND submittedDate:[201701010000 TO 299912312359]"
+        );
+    }
+
+    #[test]
+    fn test_parse_feed() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom" xmlns:arxiv="http://arxiv.org/schemas/atom">
+  <title>ArXiv Query</title>
+  <entry>
+    <id>http://arxiv.org/abs/1706.03762v7</id>
+    <published>2017-06-12T17:57:34Z</published>
+    <title>Attention Is All
+      You Need</title>
+    <summary>  The dominant sequence transduction models...  </summary>
+    <author><name>Ashish Vaswani</name></author>
+    <author><name>Noam Shazeer</name></author>
+    <link href="http://arxiv.org/abs/1706.03762v7" rel="alternate" type="text/html"/>
+    <link title="pdf" href="http://arxiv.org/pdf/1706.03762v7" rel="related" type="application/pdf"/>
+    <arxiv:primary_category term="cs.CL" scheme="http://arxiv.org/schemas/atom"/>
+    <category term="cs.CL" scheme="http://arxiv.org/schemas/atom"/>
+    <category term="cs.LG" scheme="http://arxiv.org/schemas/atom"/>
+  </entry>
+</feed>"#;
+        let papers = parse_feed(xml).unwrap();
+        assert_eq!(papers.len(), 1);
+        assert_eq!(papers[0].title, "Attention Is All You Need");
+        assert_eq!(papers[0].arxiv_id.as_deref(), Some("1706.03762"));
+        assert_eq!(papers[0].authors, vec!["Ashish Vaswani", "Noam Shazeer"]);
+        assert_eq!(papers[0].categories, vec!["cs.CL", "cs.LG"]);
+        assert_eq!(
+            papers[0].pdf_url.as_deref(),
+            Some("http://arxiv.org/pdf/1706.03762v7")
+        );
+    }
+}
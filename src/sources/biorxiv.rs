@@ -0,0 +1,220 @@
+use crate::models::paper::Paper;
+use crate::sources::http::{send_checked, web_url};
+use crate::sources::paper_source::{doi_from_key, PaperSource};
+use futures::future::BoxFuture;
+use serde::Deserialize;
+use shared::errors::{AppError, AppResult};
+
+/// bioRxiv/medRxiv details API; `{server}/{doi}` returns every version of a preprint
+pub const BIORXIV_API_URL: &str = "https://api.biorxiv.org/details";
+
+/// Preprint servers sharing the 10.1101 DOI prefix, tried in this order
+const SERVERS: [&str; 2] = ["biorxiv", "medrxiv"];
+
+/// bioRxiv and medRxiv preprints, identified by DOI
+pub struct BiorxivSource;
+
+impl BiorxivSource {
+    async fn fetch_paper(&self, doi: &str) -> AppResult<Paper> {
+        for server in SERVERS {
+            let response = send_checked(
+                reqwest::Client::new().get(format!("{}/{}/{}", BIORXIV_API_URL, server, doi)),
+                "bioRxiv",
+            )
+            .await?;
+            let details: DetailsResponse = response
+                .json()
+                .await
+                .map_err(|e| AppError::NetworkError(format!("Invalid bioRxiv response: {}", e)))?;
+
+            // Versions are listed oldest first
+            if let Some(preprint) = details.collection.into_iter().last() {
+                return Ok(preprint.into());
+            }
+        }
+        Err(AppError::PaperNotFound(format!(
+            "bioRxiv/medRxiv DOI {}",
+            doi
+        )))
+    }
+}
+
+impl PaperSource for BiorxivSource {
+    fn name(&self) -> &'static str {
+        "biorxiv"
+    }
+
+    fn parse_id(&self, input: &str) -> Option<String> {
+        parse_biorxiv_doi(input)
+    }
+
+    fn id_from_key(&self, key_id: &str) -> String {
+        doi_from_key(key_id)
+    }
+
+    fn fetch<'a>(&'a self, id: &'a str) -> BoxFuture<'a, AppResult<Paper>> {
+        Box::pin(self.fetch_paper(id))
+    }
+}
+
+/// Preprint DOI from a biorxiv.org/medrxiv.org content URL or a bare 10.1101 DOI
+///
+/// Only preprint-shaped DOIs (`10.1101/2020.03.09.983247` or `10.1101/123456`)
+/// are claimed; other 10.1101 DOIs belong to Cold Spring Harbor journals.
+pub fn parse_biorxiv_doi(input: &str) -> Option<String> {
+    let input = input.trim();
+    let doi = match web_url(input) {
+        Some(url) => {
+            let host = url.host_str()?.trim_start_matches("www.");
+            if host != "biorxiv.org" && host != "medrxiv.org" {
+                return None;
+            }
+            let path = url.path().strip_prefix("/content/")?;
+            let mut parts = path.splitn(3, '/');
+            let (prefix, suffix) = (parts.next()?, parts.next()?);
+            // 2020.03.09.983247v1.full.pdf -> 2020.03.09.983247
+            let suffix = suffix.split(".full").next()?;
+            let suffix = strip_version(suffix);
+            format!("{}/{}", prefix, suffix)
+        }
+        None => input.strip_prefix("doi:").unwrap_or(input).to_string(),
+    };
+
+    let suffix = doi.strip_prefix("10.1101/")?;
+    let parts: Vec<&str> = suffix.split('.').collect();
+    let all_digits = |s: &&str| !s.is_empty() && s.chars().all(|c| c.is_ascii_digit());
+    let preprint = match parts.as_slice() {
+        [year, month, day, number] => {
+            year.len() == 4
+                && month.len() == 2
+                && day.len() == 2
+                && parts.iter().all(all_digits)
+                && number.len() >= 6
+        }
+        [number] => all_digits(number) && number.len() == 6,
+        _ => false,
+    };
+    preprint.then_some(doi)
+}
+
+/// Drop a trailing version (`v2`)
+fn strip_version(id: &str) -> &str {
+    match id.rsplit_once('v') {
+        Some((base, version))
+            if !base.is_empty()
+                && !version.is_empty()
+                && version.chars().all(|c| c.is_ascii_digit()) =>
+        {
+            base
+        }
+        _ => id,
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct DetailsResponse {
+    #[serde(default)]
+    collection: Vec<Preprint>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Preprint {
+    doi: String,
+    #[serde(default)]
+    title: String,
+    /// "Last, F.; Last, G."
+    #[serde(default)]
+    authors: String,
+    #[serde(default, rename = "abstract")]
+    abstract_text: String,
+    #[serde(default)]
+    date: Option<String>,
+    #[serde(default)]
+    version: Option<String>,
+    #[serde(default)]
+    category: Option<String>,
+    #[serde(default)]
+    server: Option<String>,
+}
+
+impl From<Preprint> for Paper {
+    fn from(preprint: Preprint) -> Self {
+        let server = preprint
+            .server
+            .as_deref()
+            .map(str::to_lowercase)
+            .unwrap_or_else(|| "biorxiv".to_string());
+        let pdf_url = format!(
+            "https://www.{}.org/content/{}v{}.full.pdf",
+            server,
+            preprint.doi,
+            preprint.version.as_deref().unwrap_or("1")
+        );
+
+        Paper {
+            title: preprint.title.trim().to_string(),
+            authors: preprint
+                .authors
+                .split(';')
+                .map(|name| match name.split_once(',') {
+                    Some((last, first)) => format!("{} {}", first.trim(), last.trim()),
+                    None => name.trim().to_string(),
+                })
+                .filter(|name| !name.is_empty())
+                .collect(),
+            abstract_text: preprint.abstract_text.trim().to_string(),
+            arxiv_id: None,
+            ss_id: None,
+            categories: preprint.category.into_iter().collect(),
+            published_date: preprint.date,
+            pdf_url: Some(pdf_url),
+            content: None,
+            structure: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_biorxiv_doi() {
+        assert_eq!(
+            parse_biorxiv_doi(
+                "https://www.biorxiv.org/content/10.1101/2020.03.09.983247v2.full.pdf"
+            ),
+            Some("10.1101/2020.03.09.983247".to_string())
+        );
+        assert_eq!(
+            parse_biorxiv_doi("https://www.medrxiv.org/content/10.1101/2020.04.14.20062463v1"),
+            Some("10.1101/2020.04.14.20062463".to_string())
+        );
+        assert_eq!(
+            parse_biorxiv_doi("10.1101/123456"),
+            Some("10.1101/123456".to_string())
+        );
+        // Cold Spring Harbor journal DOIs are left to the DOI source
+        assert_eq!(parse_biorxiv_doi("10.1101/gr.123456.111"), None);
+    }
+
+    #[test]
+    fn test_preprint_into_paper() {
+        let json = r#"{"collection": [
+            {"doi": "10.1101/2020.03.09.983247", "title": "v1 title", "version": "1", "server": "bioRxiv"},
+            {"doi": "10.1101/2020.03.09.983247", "title": "Final title", "authors": "Smith, J.; Doe, A. B.",
+             "abstract": "We sequenced...", "date": "2020-03-20", "version": "2",
+             "category": "genomics", "server": "bioRxiv"}
+        ]}"#;
+        let details: DetailsResponse = serde_json::from_str(json).unwrap();
+        let paper = Paper::from(details.collection.into_iter().last().unwrap());
+
+        assert_eq!(paper.title, "Final title");
+        assert_eq!(paper.authors, vec!["J. Smith", "A. B. Doe"]);
+        assert_eq!(paper.categories, vec!["genomics"]);
+        assert_eq!(
+            paper.pdf_url.as_deref(),
+            Some("https://www.biorxiv.org/content/10.1101/2020.03.09.983247v2.full.pdf")
+        );
+    }
+}
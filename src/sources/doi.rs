@@ -0,0 +1,252 @@
+use crate::models::paper::Paper;
+use crate::sources::http::send_checked;
+use crate::sources::paper_source::{doi_from_key, strip_markup, PaperSource};
+use futures::future::BoxFuture;
+use reqwest::Url;
+use serde::Deserialize;
+use shared::errors::{AppError, AppResult};
+
+/// Crossref REST API endpoint
+pub const CROSSREF_API_URL: &str = "https://api.crossref.org/works";
+
+/// Papers identified by DOI, with metadata from Crossref
+pub struct DoiSource;
+
+impl DoiSource {
+    async fn fetch_paper(&self, doi: &str) -> AppResult<Paper> {
+        let response =
+            send_checked(reqwest::Client::new().get(crossref_url(doi)?), "Crossref").await?;
+        let work: CrossrefResponse = response
+            .json()
+            .await
+            .map_err(|e| AppError::NetworkError(format!("Invalid Crossref response: {}", e)))?;
+
+        Ok(work.message.into())
+    }
+}
+
+/// Crossref URL of a DOI, with the DOI as one percent-encoded path segment
+///
+/// DOI suffixes may contain '/', '?', '#', and other characters that would
+/// otherwise change the request's path or query.
+fn crossref_url(doi: &str) -> AppResult<Url> {
+    let mut url = Url::parse(CROSSREF_API_URL)
+        .map_err(|e| AppError::InternalAppError(format!("Invalid Crossref URL: {}", e)))?;
+    url.path_segments_mut()
+        .map_err(|_| AppError::InternalAppError("Crossref URL cannot have a path".to_string()))?
+        .push(doi);
+    Ok(url)
+}
+
+impl PaperSource for DoiSource {
+    fn name(&self) -> &'static str {
+        "doi"
+    }
+
+    fn parse_id(&self, input: &str) -> Option<String> {
+        parse_doi(input)
+    }
+
+    fn id_from_key(&self, key_id: &str) -> String {
+        doi_from_key(key_id)
+    }
+
+    fn fetch<'a>(&'a self, id: &'a str) -> BoxFuture<'a, AppResult<Paper>> {
+        Box::pin(self.fetch_paper(id))
+    }
+}
+
+/// Parse a bare DOI, a `doi:` identifier, or a doi.org URL; DOIs are case-insensitive
+pub fn parse_doi(input: &str) -> Option<String> {
+    let input = input.trim();
+    let doi = [
+        "https://doi.org/",
+        "http://doi.org/",
+        "https://dx.doi.org/",
+        "http://dx.doi.org/",
+    ]
+    .iter()
+    .find_map(|prefix| input.strip_prefix(prefix))
+    .or_else(|| input.strip_prefix("doi:"))
+    .or_else(|| input.strip_prefix("DOI:"))
+    .unwrap_or(input)
+    .trim();
+
+    let (prefix, suffix) = doi.split_once('/')?;
+    let registrant = prefix.strip_prefix("10.")?;
+    let valid = !registrant.is_empty()
+        && registrant.chars().all(|c| c.is_ascii_digit() || c == '.')
+        && !suffix.is_empty()
+        && !suffix.contains(char::is_whitespace);
+    valid.then(|| doi.to_lowercase())
+}
+
+#[derive(Debug, Deserialize)]
+struct CrossrefResponse {
+    message: CrossrefWork,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct CrossrefWork {
+    #[serde(rename = "DOI")]
+    doi: String,
+    #[serde(default)]
+    title: Vec<String>,
+    #[serde(default)]
+    author: Vec<CrossrefAuthor>,
+    #[serde(default, rename = "abstract")]
+    abstract_text: Option<String>,
+    #[serde(default)]
+    published: Option<CrossrefDate>,
+    #[serde(default)]
+    issued: Option<CrossrefDate>,
+    #[serde(default)]
+    link: Vec<CrossrefLink>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CrossrefAuthor {
+    #[serde(default)]
+    given: Option<String>,
+    #[serde(default)]
+    family: Option<String>,
+    /// Set instead of given/family for organizations
+    #[serde(default)]
+    name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct CrossrefDate {
+    #[serde(default)]
+    date_parts: Vec<Vec<Option<u32>>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct CrossrefLink {
+    #[serde(rename = "URL")]
+    url: String,
+    #[serde(default)]
+    content_type: Option<String>,
+}
+
+impl CrossrefDate {
+    /// `YYYY-MM-DD`, or `YYYY-MM` / `YYYY` when Crossref is less precise
+    fn to_iso(&self) -> Option<String> {
+        let parts: Vec<u32> = self.date_parts.first()?.iter().map_while(|p| *p).collect();
+        match parts.as_slice() {
+            [y, m, d, ..] => Some(format!("{:04}-{:02}-{:02}", y, m, d)),
+            [y, m] => Some(format!("{:04}-{:02}", y, m)),
+            [y] => Some(format!("{:04}", y)),
+            [] => None,
+        }
+    }
+}
+
+impl From<CrossrefWork> for Paper {
+    fn from(work: CrossrefWork) -> Self {
+        Paper {
+            title: work
+                .title
+                .into_iter()
+                .next()
+                .map(|t| strip_markup(&t))
+                .unwrap_or_else(|| work.doi.clone()),
+            authors: work
+                .author
+                .into_iter()
+                .filter_map(|a| match (a.given, a.family) {
+                    (Some(given), Some(family)) => Some(format!("{} {}", given, family)),
+                    (None, Some(family)) => Some(family),
+                    (given, None) => a.name.or(given),
+                })
+                .collect(),
+            abstract_text: work
+                .abstract_text
+                .map(|a| strip_markup(&a))
+                .unwrap_or_default(),
+            arxiv_id: None,
+            ss_id: None,
+            categories: vec![],
+            published_date: work
+                .published
+                .as_ref()
+                .or(work.issued.as_ref())
+                .and_then(CrossrefDate::to_iso),
+            pdf_url: work
+                .link
+                .into_iter()
+                .find(|l| l.content_type.as_deref() == Some("application/pdf"))
+                .map(|l| l.url),
+            content: None,
+            structure: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_doi() {
+        assert_eq!(
+            parse_doi("https://doi.org/10.1145/3442188.3445922"),
+            Some("10.1145/3442188.3445922".to_string())
+        );
+        assert_eq!(
+            parse_doi("doi:10.18653/v1/N19-1423"),
+            Some("10.18653/v1/n19-1423".to_string())
+        );
+        assert_eq!(parse_doi("10.1145"), None);
+        assert_eq!(parse_doi("2301.00001"), None);
+        assert_eq!(parse_doi("https://example.com/10.1145/1"), None);
+    }
+
+    #[test]
+    fn test_crossref_url() {
+        assert_eq!(
+            crossref_url("10.1145/3442188.3445922").unwrap().as_str(),
+            "https://api.crossref.org/works/10.1145%2F3442188.3445922"
+        );
+        assert_eq!(
+            crossref_url("10.1002/(SICI)1097-4571#x?y")
+                .unwrap()
+                .as_str(),
+            "https://api.crossref.org/works/10.1002%2F(SICI)1097-4571%23x%3Fy"
+        );
+    }
+
+    #[test]
+    fn test_crossref_work() {
+        let json = r#"{"message": {
+            "DOI": "10.1145/3442188.3445922",
+            "title": ["On the Dangers of Stochastic Parrots"],
+            "author": [
+                {"given": "Emily M.", "family": "Bender"},
+                {"name": "ACM FAccT Committee"}
+            ],
+            "abstract": "<jats:p>The past 3 years of work in NLP...</jats:p>",
+            "published": {"date-parts": [[2021, 3, 1]]},
+            "link": [
+                {"URL": "https://dl.acm.org/doi/pdf/10.1145/3442188.3445922", "content-type": "application/pdf"}
+            ]
+        }}"#;
+        let response: CrossrefResponse = serde_json::from_str(json).unwrap();
+        let paper = Paper::from(response.message);
+
+        assert_eq!(paper.title, "On the Dangers of Stochastic Parrots");
+        assert_eq!(
+            paper.authors,
+            vec!["Emily M. Bender", "ACM FAccT Committee"]
+        );
+        assert_eq!(paper.abstract_text, "The past 3 years of work in NLP...");
+        assert_eq!(paper.published_date.as_deref(), Some("2021-03-01"));
+        assert_eq!(
+            paper.pdf_url.as_deref(),
+            Some("https://dl.acm.org/doi/pdf/10.1145/3442188.3445922")
+        );
+    }
+}
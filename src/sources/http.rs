@@ -0,0 +1,451 @@
+use chrono::{DateTime, Utc};
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::{RequestBuilder, Response, StatusCode, Url};
+use shared::errors::{AppError, AppResult};
+use std::collections::HashMap;
+use std::env;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+/// Default number of retries after the first attempt
+const DEFAULT_MAX_RETRIES: u32 = 3;
+
+/// Default delay before the first retry; doubles with every attempt
+const DEFAULT_RETRY_BASE_MS: u64 = 500;
+
+/// Upper bound on a single backoff delay
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Longest Retry-After worth waiting for in-line; longer ones are returned to the caller
+const MAX_RETRY_AFTER_SECS: u64 = 60;
+
+/// Upstream services that get their own rate limit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Service {
+    Arxiv,
+    SemanticScholar,
+}
+
+impl Service {
+    /// The service a URL belongs to, if it is rate limited
+    pub fn for_url(url: &Url) -> Option<Self> {
+        let host = url.host_str()?;
+        let is = |domain: &str| host == domain || host.ends_with(&format!(".{}", domain));
+        if is("arxiv.org") {
+            Some(Self::Arxiv)
+        } else if is("semanticscholar.org") {
+            Some(Self::SemanticScholar)
+        } else {
+            None
+        }
+    }
+
+    fn env_prefix(self) -> &'static str {
+        match self {
+            Self::Arxiv => "ARXIV",
+            Self::SemanticScholar => "SEMANTIC_SCHOLAR",
+        }
+    }
+
+    /// Published limits: arXiv asks for one request every three seconds;
+    /// Semantic Scholar allows about 1 rps with an API key and shares a much
+    /// smaller pool between all unauthenticated clients.
+    fn default_rate_limit(self) -> RateLimit {
+        match self {
+            Self::Arxiv => RateLimit {
+                requests_per_sec: 1.0 / 3.0,
+                burst: 1,
+            },
+            Self::SemanticScholar if env::var("SEMANTIC_SCHOLAR_API_KEY").is_ok() => RateLimit {
+                requests_per_sec: 1.0,
+                burst: 1,
+            },
+            Self::SemanticScholar => RateLimit {
+                requests_per_sec: 0.3,
+                burst: 3,
+            },
+        }
+    }
+}
+
+/// Token-bucket settings for one service
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateLimit {
+    pub requests_per_sec: f64,
+    pub burst: u32,
+}
+
+impl RateLimit {
+    /// Read `{PREFIX}_RATE_LIMIT_PER_SEC` and `{PREFIX}_RATE_LIMIT_BURST`, e.g. ARXIV_RATE_LIMIT_PER_SEC
+    pub fn from_env(service: Service) -> Self {
+        let default = service.default_rate_limit();
+        let prefix = service.env_prefix();
+        Self {
+            requests_per_sec: env_parse(&format!("{}_RATE_LIMIT_PER_SEC", prefix))
+                .filter(|rps: &f64| *rps > 0.0)
+                .unwrap_or(default.requests_per_sec),
+            burst: env_parse(&format!("{}_RATE_LIMIT_BURST", prefix))
+                .filter(|burst: &u32| *burst > 0)
+                .unwrap_or(default.burst),
+        }
+    }
+}
+
+pub(crate) fn env_parse<T: std::str::FromStr>(name: &str) -> Option<T> {
+    env::var(name).ok().and_then(|v| v.trim().parse().ok())
+}
+
+/// Token bucket shared by every request to one service
+#[derive(Debug)]
+pub struct TokenBucket {
+    limit: RateLimit,
+    state: Mutex<BucketState>,
+}
+
+#[derive(Debug)]
+struct BucketState {
+    tokens: f64,
+    refilled_at: Instant,
+    paused_until: Option<Instant>,
+}
+
+impl TokenBucket {
+    pub fn new(limit: RateLimit) -> Self {
+        Self {
+            limit,
+            state: Mutex::new(BucketState {
+                tokens: limit.burst as f64,
+                refilled_at: Instant::now(),
+                paused_until: None,
+            }),
+        }
+    }
+
+    /// Wait until a request may be sent, then take a token
+    pub async fn acquire(&self) {
+        loop {
+            let wait = self.try_take(Instant::now());
+            match wait {
+                None => return,
+                Some(wait) => tokio::time::sleep(wait).await,
+            }
+        }
+    }
+
+    /// Hold off every request to the service for `wait` (e.g., after a Retry-After)
+    pub fn pause(&self, wait: Duration) {
+        let until = Instant::now() + wait;
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.paused_until = Some(state.paused_until.map_or(until, |p| p.max(until)));
+    }
+
+    /// Take a token if one is available, or return how long to wait for one
+    fn try_take(&self, now: Instant) -> Option<Duration> {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+
+        if let Some(until) = state.paused_until {
+            if until > now {
+                return Some(until - now);
+            }
+            state.paused_until = None;
+        }
+
+        let elapsed = now
+            .saturating_duration_since(state.refilled_at)
+            .as_secs_f64();
+        state.tokens =
+            (state.tokens + elapsed * self.limit.requests_per_sec).min(self.limit.burst as f64);
+        state.refilled_at = now;
+
+        if state.tokens >= 1.0 {
+            state.tokens -= 1.0;
+            None
+        } else {
+            Some(Duration::from_secs_f64(
+                (1.0 - state.tokens) / self.limit.requests_per_sec,
+            ))
+        }
+    }
+}
+
+static BUCKETS: LazyLock<HashMap<Service, TokenBucket>> = LazyLock::new(|| {
+    [Service::Arxiv, Service::SemanticScholar]
+        .into_iter()
+        .map(|service| (service, TokenBucket::new(RateLimit::from_env(service))))
+        .collect()
+});
+
+/// The process-wide bucket of a service
+pub fn bucket(service: Service) -> &'static TokenBucket {
+    &BUCKETS[&service]
+}
+
+/// How often and how long to back off after transient failures
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    pub base_delay: Duration,
+}
+
+impl RetryPolicy {
+    /// Read HTTP_MAX_RETRIES and HTTP_RETRY_BASE_MS
+    pub fn from_env() -> Self {
+        Self {
+            max_retries: env_parse("HTTP_MAX_RETRIES").unwrap_or(DEFAULT_MAX_RETRIES),
+            base_delay: Duration::from_millis(
+                env_parse("HTTP_RETRY_BASE_MS").unwrap_or(DEFAULT_RETRY_BASE_MS),
+            ),
+        }
+    }
+
+    /// Exponential backoff before retry `attempt` (0-based), with equal jitter
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let exp = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(MAX_BACKOFF);
+        let half = exp / 2;
+        half + half.mul_f64(fastrand::f64())
+    }
+
+    /// Delay before retrying after `retry_after_secs`, or `None` to give up
+    pub fn retry_delay(&self, attempt: u32, retry_after_secs: Option<u64>) -> Option<Duration> {
+        if attempt >= self.max_retries {
+            return None;
+        }
+        match retry_after_secs {
+            Some(secs) if secs > MAX_RETRY_AFTER_SECS => None,
+            Some(secs) => Some(Duration::from_secs(secs)),
+            None => Some(self.backoff(attempt)),
+        }
+    }
+}
+
+/// Whether a response status is worth retrying
+fn is_retryable_status(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+/// Send a request through its service's rate limit, retrying 429s, 5xx
+/// responses, and connection failures with backoff
+///
+/// The last response is returned as-is when retries run out, so callers
+/// still see the 429 (and its Retry-After) and can report it.
+pub async fn send_with_retry(request: RequestBuilder) -> reqwest::Result<Response> {
+    let (client, request) = request.build_split();
+    let request = request?;
+    let service = Service::for_url(request.url());
+    let policy = RetryPolicy::from_env();
+
+    let mut attempt = 0;
+    loop {
+        if let Some(service) = service {
+            bucket(service).acquire().await;
+        }
+        // Streaming bodies cannot be replayed; send those once
+        let Some(this_try) = request.try_clone() else {
+            return client.execute(request).await;
+        };
+
+        let result = client.execute(this_try).await;
+        let delay = match result {
+            Ok(ref response) if is_retryable_status(response.status()) => {
+                let retry_after = retry_after_secs(response.headers());
+                let delay = policy.retry_delay(attempt, retry_after);
+                if let (Some(service), Some(secs)) = (service, retry_after) {
+                    bucket(service).pause(Duration::from_secs(secs));
+                }
+                delay
+            }
+            Err(ref e) if e.is_timeout() || e.is_connect() => policy.retry_delay(attempt, None),
+            _ => None,
+        };
+
+        let Some(delay) = delay else {
+            return result;
+        };
+        tracing::warn!(
+            "{} failed ({}), retrying in {:?}",
+            request.url(),
+            match result {
+                Ok(ref response) => response.status().to_string(),
+                Err(ref e) => e.to_string(),
+            },
+            delay
+        );
+        tokio::time::sleep(delay).await;
+        attempt += 1;
+    }
+}
+
+/// Send a request with [`send_with_retry`], turning error statuses into [`AppError`]s
+///
+/// `service` names the upstream in error messages (e.g., "Crossref").
+pub async fn send_checked(request: RequestBuilder, service: &str) -> AppResult<Response> {
+    let response = send_with_retry(request)
+        .await
+        .map_err(|e| AppError::NetworkError(format!("{} request failed: {}", service, e)))?;
+
+    let status = response.status();
+    if status == StatusCode::NOT_FOUND {
+        return Err(AppError::PaperNotFound(format!(
+            "{} has no paper at {}",
+            service,
+            response.url()
+        )));
+    }
+    if status == StatusCode::TOO_MANY_REQUESTS {
+        return Err(AppError::RateLimitExceeded {
+            message: format!("{} returned {}", service, status),
+            retry_after_secs: retry_after_secs(response.headers()),
+        });
+    }
+    if !status.is_success() {
+        return Err(AppError::NetworkError(format!(
+            "{} returned {}",
+            service, status
+        )));
+    }
+    Ok(response)
+}
+
+/// Parse `input` as an http(s) URL; identifiers such as `PMID:123` parse as URLs too
+pub fn web_url(input: &str) -> Option<Url> {
+    Url::parse(input)
+        .ok()
+        .filter(|url| matches!(url.scheme(), "http" | "https"))
+}
+
+/// Seconds to wait according to a response's Retry-After header
+///
+/// Accepts both forms the header allows: delta-seconds and an HTTP date.
+pub fn retry_after_secs(headers: &HeaderMap) -> Option<u64> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+    parse_retry_after(value, Utc::now())
+}
+
+fn parse_retry_after(value: &str, now: DateTime<Utc>) -> Option<u64> {
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(secs);
+    }
+    let date = DateTime::parse_from_rfc2822(value).ok()?;
+    let wait = date.with_timezone(&Utc).signed_duration_since(now);
+    Some(wait.num_seconds().max(0) as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_retry_after() {
+        let now = DateTime::parse_from_rfc3339("2015-10-21T07:28:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+
+        assert_eq!(parse_retry_after("120", now), Some(120));
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:28:30 GMT", now),
+            Some(30)
+        );
+        // Dates in the past mean "retry now"
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:00:00 GMT", now),
+            Some(0)
+        );
+        assert_eq!(parse_retry_after("soon", now), None);
+    }
+
+    #[test]
+    fn test_service_for_url() {
+        let service = |url: &str| Service::for_url(&Url::parse(url).unwrap());
+        assert_eq!(
+            service("http://export.arxiv.org/api/query"),
+            Some(Service::Arxiv)
+        );
+        assert_eq!(
+            service("https://arxiv.org/pdf/1706.03762"),
+            Some(Service::Arxiv)
+        );
+        assert_eq!(
+            service("https://api.semanticscholar.org/graph/v1/paper/search"),
+            Some(Service::SemanticScholar)
+        );
+        assert_eq!(service("https://notarxiv.org/paper.pdf"), None);
+    }
+
+    #[test]
+    fn test_token_bucket() {
+        let bucket = TokenBucket::new(RateLimit {
+            requests_per_sec: 2.0,
+            burst: 2,
+        });
+        let start = Instant::now();
+
+        // The burst is available at once, then tokens refill at the rate
+        assert_eq!(bucket.try_take(start), None);
+        assert_eq!(bucket.try_take(start), None);
+        assert_eq!(bucket.try_take(start), Some(Duration::from_millis(500)));
+        assert_eq!(bucket.try_take(start + Duration::from_millis(500)), None);
+
+        bucket.pause(Duration::from_secs(60));
+        assert!(bucket
+            .try_take(Instant::now() + Duration::from_secs(1))
+            .is_some());
+    }
+
+    #[test]
+    fn test_retry_delay() {
+        let policy = RetryPolicy {
+            max_retries: 3,
+            base_delay: Duration::from_millis(100),
+        };
+
+        for attempt in 0..3 {
+            let delay = policy.retry_delay(attempt, None).unwrap();
+            let full = Duration::from_millis(100 * 2u64.pow(attempt));
+            assert!(delay >= full / 2 && delay <= full);
+        }
+        assert_eq!(policy.retry_delay(3, None), None);
+
+        // Retry-After wins over backoff, unless it is too long to wait in-line
+        assert_eq!(policy.retry_delay(0, Some(5)), Some(Duration::from_secs(5)));
+        assert_eq!(policy.retry_delay(0, Some(3600)), None);
+    }
+
+    #[tokio::test]
+    async fn test_send_with_retry() {
+        use std::sync::atomic::{AtomicU32, Ordering};
+        use std::sync::Arc;
+
+        // Fails twice (once with Retry-After), then succeeds
+        let hits = Arc::new(AtomicU32::new(0));
+        let counter = hits.clone();
+        let app = axum::Router::new().route(
+            "/",
+            axum::routing::get(move || {
+                let counter = counter.clone();
+                async move {
+                    match counter.fetch_add(1, Ordering::SeqCst) {
+                        0 => (
+                            StatusCode::TOO_MANY_REQUESTS,
+                            [(RETRY_AFTER, "0")],
+                            "slow down",
+                        ),
+                        1 => (StatusCode::BAD_GATEWAY, [(RETRY_AFTER, "0")], "upstream"),
+                        _ => (StatusCode::OK, [(RETRY_AFTER, "0")], "ok"),
+                    }
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let response = send_with_retry(reqwest::Client::new().get(&url))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(hits.load(Ordering::SeqCst), 3);
+    }
+}
@@ -0,0 +1,139 @@
+/// Extract arXiv ID from various URL formats
+/// Supports:
+/// - https://arxiv.org/abs/2301.00001
+/// - https://arxiv.org/pdf/2301.00001.pdf
+/// - http://arxiv.org/abs/2301.00001v1
+pub fn extract_arxiv_id(url: &str) -> Option<String> {
+    let url = url.trim();
+
+    // Pattern for arxiv.org URLs
+    if url.contains("arxiv.org") {
+        // Try /abs/ format
+        if let Some(pos) = url.find("/abs/") {
+            let id_part = &url[pos + 5..];
+            let id = id_part.split(['?', '#', '/']).next().unwrap_or(id_part);
+            let id = strip_version_suffix(id);
+            if !id.is_empty() {
+                return Some(id.to_string());
+            }
+        }
+
+        // Try /pdf/ format
+        if let Some(pos) = url.find("/pdf/") {
+            let id_part = &url[pos + 5..];
+            let id = id_part
+                .trim_end_matches(".pdf")
+                .split(['?', '#', '/'])
+                .next()
+                .unwrap_or(id_part);
+            let id = strip_version_suffix(id);
+            if !id.is_empty() {
+                return Some(id.to_string());
+            }
+        }
+    }
+
+    // Check if it's just an arxiv ID (e.g., "2301.00001")
+    if url.chars().all(|c| c.is_ascii_digit() || c == '.') && url.contains('.') {
+        return Some(url.to_string());
+    }
+
+    None
+}
+
+/// Strip version suffix like "v1", "v2" from arxiv ID
+fn strip_version_suffix(id: &str) -> &str {
+    // Look for pattern like "v" followed by digits at the end
+    if let Some(v_pos) = id.rfind('v') {
+        let after_v = &id[v_pos + 1..];
+        // Check if everything after 'v' is digits
+        if !after_v.is_empty() && after_v.chars().all(|c| c.is_ascii_digit()) {
+            return &id[..v_pos];
+        }
+    }
+    id
+}
+
+/// Extract Semantic Scholar paper ID from URL
+/// Supports:
+/// - https://www.semanticscholar.org/paper/Title-Name/abc123def456
+/// - https://api.semanticscholar.org/CorpusID:12345678
+pub fn extract_ss_id(url: &str) -> Option<String> {
+    let url = url.trim();
+
+    // Pattern for semanticscholar.org paper URLs
+    if url.contains("semanticscholar.org/paper/") {
+        // The ID is typically the last path segment
+        let parts: Vec<&str> = url
+            .trim_end_matches('/')
+            .split('/')
+            .filter(|s| !s.is_empty())
+            .collect();
+        if let Some(id) = parts.last() {
+            // Clean up query params
+            let id = id.split('?').next().unwrap_or(id);
+            if !id.is_empty() && id.len() >= 8 {
+                return Some(id.to_string());
+            }
+        }
+    }
+
+    // Pattern for CorpusID
+    if let Some(pos) = url.find("CorpusID:") {
+        let id_part = &url[pos + 9..];
+        let id = id_part
+            .split(|c: char| !c.is_ascii_digit())
+            .next()
+            .unwrap_or(id_part);
+        if !id.is_empty() {
+            return Some(id.to_string());
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_arxiv_id() {
+        assert_eq!(
+            extract_arxiv_id("https://arxiv.org/abs/2301.00001"),
+            Some("2301.00001".to_string())
+        );
+        assert_eq!(
+            extract_arxiv_id("https://arxiv.org/abs/2301.00001v2"),
+            Some("2301.00001".to_string())
+        );
+        assert_eq!(
+            extract_arxiv_id("https://arxiv.org/pdf/2301.00001.pdf"),
+            Some("2301.00001".to_string())
+        );
+        assert_eq!(
+            extract_arxiv_id("http://arxiv.org/abs/1706.03762"),
+            Some("1706.03762".to_string())
+        );
+        assert_eq!(
+            extract_arxiv_id("2301.00001"),
+            Some("2301.00001".to_string())
+        );
+        assert_eq!(extract_arxiv_id("https://example.com"), None);
+    }
+
+    #[test]
+    fn test_extract_ss_id() {
+        assert_eq!(
+            extract_ss_id(
+                "https://www.semanticscholar.org/paper/Attention-Is-All-You-Need/abc123def456"
+            ),
+            Some("abc123def456".to_string())
+        );
+        assert_eq!(
+            extract_ss_id("https://api.semanticscholar.org/CorpusID:12345678"),
+            Some("12345678".to_string())
+        );
+        assert_eq!(extract_ss_id("https://example.com"), None);
+    }
+}
@@ -0,0 +1,21 @@
+pub mod acl;
+pub mod arxiv;
+pub mod biorxiv;
+pub mod doi;
+pub mod http;
+pub mod ids;
+pub mod openreview;
+pub mod paper_client;
+pub mod paper_source;
+pub mod pdf;
+pub mod pubmed;
+pub mod references;
+pub mod resolve;
+pub mod semantic_scholar;
+
+pub use arxiv::{ArxivClient, ArxivQuery};
+pub use paper_client::RateLimitedPaperClient;
+pub use paper_source::{find_source, paper_sources, source_named, PaperSource};
+pub use references::resolve_references;
+pub use resolve::{resolve_title, TitleMatch};
+pub use semantic_scholar::{SemanticScholarClient, SemanticScholarQuery};
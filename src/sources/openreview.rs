@@ -0,0 +1,178 @@
+use crate::models::paper::Paper;
+use crate::sources::http::{send_checked, web_url};
+use crate::sources::paper_source::PaperSource;
+use chrono::DateTime;
+use futures::future::BoxFuture;
+use serde::Deserialize;
+use serde_json::Value;
+use shared::errors::{AppError, AppResult};
+
+/// OpenReview API v2 (venues from 2023 on)
+pub const OPENREVIEW_API_URL: &str = "https://api2.openreview.net";
+
+/// OpenReview API v1, still serving older venues
+pub const OPENREVIEW_API_V1_URL: &str = "https://api.openreview.net";
+
+/// Papers on OpenReview, identified by forum ID
+pub struct OpenReviewSource;
+
+impl OpenReviewSource {
+    async fn fetch_paper(&self, id: &str) -> AppResult<Paper> {
+        // Older forums are only served by API v1; try v2 first
+        for base_url in [OPENREVIEW_API_URL, OPENREVIEW_API_V1_URL] {
+            let request = reqwest::Client::new()
+                .get(format!("{}/notes", base_url))
+                .query(&[("id", id)]);
+            let notes: NotesResponse = match send_checked(request, "OpenReview").await {
+                Ok(response) => response.json().await.map_err(|e| {
+                    AppError::NetworkError(format!("Invalid OpenReview response: {}", e))
+                })?,
+                Err(AppError::PaperNotFound(_)) => continue,
+                Err(e) => return Err(e),
+            };
+            if let Some(note) = notes.notes.into_iter().next() {
+                return Ok(note.into_paper(id));
+            }
+        }
+        Err(AppError::PaperNotFound(format!("OpenReview forum {}", id)))
+    }
+}
+
+impl PaperSource for OpenReviewSource {
+    fn name(&self) -> &'static str {
+        "openreview"
+    }
+
+    fn parse_id(&self, input: &str) -> Option<String> {
+        parse_openreview_id(input)
+    }
+
+    fn fetch<'a>(&'a self, id: &'a str) -> BoxFuture<'a, AppResult<Paper>> {
+        Box::pin(self.fetch_paper(id))
+    }
+}
+
+/// Forum ID from an openreview.net `/forum?id=` or `/pdf?id=` URL
+pub fn parse_openreview_id(input: &str) -> Option<String> {
+    let url = web_url(input)?;
+    let host = url.host_str()?;
+    if host != "openreview.net" && host != "www.openreview.net" {
+        return None;
+    }
+    if !matches!(url.path(), "/forum" | "/pdf" | "/forum/" | "/pdf/") {
+        return None;
+    }
+    url.query_pairs()
+        .find(|(key, _)| key == "id")
+        .map(|(_, id)| id.into_owned())
+        .filter(|id| !id.is_empty())
+}
+
+#[derive(Debug, Deserialize)]
+struct NotesResponse {
+    #[serde(default)]
+    notes: Vec<Note>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Note {
+    #[serde(default)]
+    content: serde_json::Map<String, Value>,
+    /// Publication date, then creation date, in milliseconds since the epoch
+    #[serde(default)]
+    pdate: Option<i64>,
+    #[serde(default)]
+    cdate: Option<i64>,
+}
+
+impl Note {
+    /// A content field; API v2 wraps every value as `{"value": ...}`
+    fn field(&self, name: &str) -> Option<&Value> {
+        let value = self.content.get(name)?;
+        Some(value.get("value").unwrap_or(value))
+    }
+
+    fn text(&self, name: &str) -> Option<String> {
+        self.field(name)?.as_str().map(|s| s.trim().to_string())
+    }
+
+    fn into_paper(self, id: &str) -> Paper {
+        Paper {
+            title: self.text("title").unwrap_or_else(|| id.to_string()),
+            authors: self
+                .field("authors")
+                .and_then(Value::as_array)
+                .map(|authors| {
+                    authors
+                        .iter()
+                        .filter_map(|a| a.as_str().map(str::to_string))
+                        .collect()
+                })
+                .unwrap_or_default(),
+            abstract_text: self.text("abstract").unwrap_or_default(),
+            arxiv_id: None,
+            ss_id: None,
+            categories: vec![],
+            published_date: self
+                .pdate
+                .or(self.cdate)
+                .and_then(DateTime::from_timestamp_millis)
+                .map(|d| d.to_rfc3339()),
+            pdf_url: Some(format!("https://openreview.net/pdf?id={}", id)),
+            content: None,
+            structure: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_openreview_id() {
+        assert_eq!(
+            parse_openreview_id("https://openreview.net/forum?id=rJ4km2R5t7"),
+            Some("rJ4km2R5t7".to_string())
+        );
+        assert_eq!(
+            parse_openreview_id("https://openreview.net/pdf?id=YicbFdNTTy&noteId=abc"),
+            Some("YicbFdNTTy".to_string())
+        );
+        assert_eq!(
+            parse_openreview_id("https://openreview.net/group?id=ICLR.cc/2024"),
+            None
+        );
+        assert_eq!(parse_openreview_id("rJ4km2R5t7"), None);
+    }
+
+    #[test]
+    fn test_note_into_paper() {
+        // API v2 wraps values; API v1 does not
+        for content in [
+            r#"{"title": {"value": "An Image is Worth 16x16 Words"}, "authors": {"value": ["Alexey Dosovitskiy"]}, "abstract": {"value": "While the Transformer..."}}"#,
+            r#"{"title": "An Image is Worth 16x16 Words", "authors": ["Alexey Dosovitskiy"], "abstract": "While the Transformer..."}"#,
+        ] {
+            let json = format!(
+                r#"{{"notes": [{{"content": {}, "pdate": 1610000000000}}]}}"#,
+                content
+            );
+            let response: NotesResponse = serde_json::from_str(&json).unwrap();
+            let paper = response
+                .notes
+                .into_iter()
+                .next()
+                .unwrap()
+                .into_paper("YicbFdNTTy");
+
+            assert_eq!(paper.title, "An Image is Worth 16x16 Words");
+            assert_eq!(paper.authors, vec!["Alexey Dosovitskiy"]);
+            assert_eq!(paper.abstract_text, "While the Transformer...");
+            assert!(paper.published_date.unwrap().starts_with("2021-01-07"));
+            assert_eq!(
+                paper.pdf_url.as_deref(),
+                Some("https://openreview.net/pdf?id=YicbFdNTTy")
+            );
+        }
+    }
+}
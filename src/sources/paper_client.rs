@@ -0,0 +1,222 @@
+use crate::sources::http::{bucket, RetryPolicy, Service};
+use academic_paper_interpreter::client::PaperClient;
+use academic_paper_interpreter::models::AcademicPaper;
+use shared::errors::{AppError, AppResult};
+use std::future::Future;
+
+/// Services a library fetch sends requests to
+const LIBRARY_SERVICES: &[Service] = &[Service::Arxiv, Service::SemanticScholar];
+
+/// [`PaperClient`] that shares the arXiv and Semantic Scholar rate limits
+/// with our own clients and retries transient failures
+///
+/// The library does its own HTTP, so limits are applied per call rather
+/// than per request. A fetch reaches both arXiv and Semantic Scholar
+/// whichever ID it starts from, so each call takes a token from both.
+pub struct RateLimitedPaperClient {
+    inner: PaperClient,
+    policy: RetryPolicy,
+}
+
+impl RateLimitedPaperClient {
+    pub fn new() -> Self {
+        Self {
+            inner: PaperClient::new(),
+            policy: RetryPolicy::from_env(),
+        }
+    }
+
+    /// Fetch a paper with its PDF text by arXiv ID
+    pub async fn fetch_by_arxiv_id(&self, arxiv_id: &str) -> AppResult<AcademicPaper> {
+        call_with_retry(LIBRARY_SERVICES, &self.policy, || {
+            self.inner.fetch_by_arxiv_id(arxiv_id)
+        })
+        .await
+    }
+
+    /// Fetch a paper with its PDF text by Semantic Scholar paper ID
+    pub async fn fetch_by_ss_id(&self, ss_id: &str) -> AppResult<AcademicPaper> {
+        call_with_retry(LIBRARY_SERVICES, &self.policy, || {
+            self.inner.fetch_by_ss_id(ss_id)
+        })
+        .await
+    }
+}
+
+impl Default for RateLimitedPaperClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Run a library call under the rate limits of every service it reaches,
+/// retrying rate limits and network errors with backoff
+async fn call_with_retry<T, F, Fut>(
+    services: &[Service],
+    policy: &RetryPolicy,
+    call: F,
+) -> AppResult<T>
+where
+    F: Fn() -> Fut,
+    Fut: Future<Output = anyhow::Result<T>>,
+{
+    let mut attempt = 0;
+    loop {
+        for &service in services {
+            bucket(service).acquire().await;
+        }
+        let error = match call().await {
+            Ok(value) => return Ok(value),
+            Err(e) => classify_error(e),
+        };
+
+        let delay = if error.is_retryable() {
+            policy.retry_delay(attempt, error.retry_after_secs())
+        } else {
+            None
+        };
+        let Some(delay) = delay else {
+            return Err(error);
+        };
+        // Which of the call's services limited it is unknown, so all back off
+        if matches!(error, AppError::RateLimitExceeded { .. }) {
+            for &service in services {
+                bucket(service).pause(delay);
+            }
+        }
+        tracing::warn!("{}, retrying in {:?}", error, delay);
+        tokio::time::sleep(delay).await;
+        attempt += 1;
+    }
+}
+
+/// Classify an error from the paper client library into an [`AppError`]
+///
+/// The library reports failures as `anyhow` errors, so HTTP errors found in
+/// the chain are classified by status, and anything else by its message.
+pub fn classify_error(error: anyhow::Error) -> AppError {
+    let error = match error.downcast::<AppError>() {
+        Ok(app_error) => return app_error,
+        Err(error) => error,
+    };
+    let message = format!("{:#}", error);
+
+    if let Some(http) = error
+        .chain()
+        .find_map(|cause| cause.downcast_ref::<reqwest::Error>())
+    {
+        match http.status().map(|s| s.as_u16()) {
+            Some(404) => return AppError::PaperNotFound(message),
+            Some(429) => {
+                return AppError::RateLimitExceeded {
+                    message,
+                    retry_after_secs: None,
+                };
+            }
+            Some(status) if (400..500).contains(&status) => {
+                return AppError::InvalidRequest(message);
+            }
+            _ => return AppError::NetworkError(message),
+        }
+    }
+
+    classify_message(message)
+}
+
+fn classify_message(message: String) -> AppError {
+    let lower = message.to_lowercase();
+    let has = |needles: &[&str]| needles.iter().any(|n| lower.contains(n));
+
+    if has(&["429", "rate limit", "too many requests"]) {
+        AppError::RateLimitExceeded {
+            message,
+            retry_after_secs: None,
+        }
+    } else if has(&["not found", "404", "no paper", "no results"]) {
+        AppError::PaperNotFound(message)
+    } else if has(&["invalid arxiv id", "invalid arxiv identifier"]) {
+        AppError::InvalidArxivId(message)
+    } else if has(&["pdf"]) {
+        AppError::PdfFetchFailed(message)
+    } else if has(&["timed out", "timeout", "connection", "dns", "network"]) {
+        AppError::NetworkError(message)
+    } else {
+        AppError::InternalAppError(message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::time::Duration;
+
+    const FAST: RetryPolicy = RetryPolicy {
+        max_retries: 2,
+        base_delay: Duration::from_millis(1),
+    };
+
+    #[tokio::test]
+    async fn test_call_with_retry() {
+        // Rate limits are retried until the call succeeds
+        let calls = AtomicU32::new(0);
+        let result = call_with_retry(&[], &FAST, || async {
+            match calls.fetch_add(1, Ordering::SeqCst) {
+                0 => Err(anyhow::anyhow!("429 Too Many Requests")),
+                n => Ok(n),
+            }
+        })
+        .await;
+        assert_eq!(result.unwrap(), 1);
+
+        // Retries stop after max_retries
+        let calls = AtomicU32::new(0);
+        let result: AppResult<()> = call_with_retry(&[], &FAST, || async {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Err(anyhow::anyhow!("connection reset"))
+        })
+        .await;
+        assert!(matches!(result, Err(AppError::NetworkError(_))));
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+        // Permanent errors are not retried
+        let calls = AtomicU32::new(0);
+        let result: AppResult<()> = call_with_retry(&[], &FAST, || async {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Err(anyhow::anyhow!("Paper not found: 2301.99999"))
+        })
+        .await;
+        assert!(matches!(result, Err(AppError::PaperNotFound(_))));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_classify_error() {
+        let classify = |message: &str| classify_error(anyhow::anyhow!(message.to_string()));
+
+        assert!(matches!(
+            classify("Semantic Scholar API error: 429 Too Many Requests"),
+            AppError::RateLimitExceeded { .. }
+        ));
+        assert!(matches!(
+            classify("Paper not found: 2301.99999"),
+            AppError::PaperNotFound(_)
+        ));
+        assert!(matches!(
+            classify("Failed to download PDF"),
+            AppError::PdfFetchFailed(_)
+        ));
+        assert!(matches!(
+            classify("operation timed out"),
+            AppError::NetworkError(_)
+        ));
+        assert!(matches!(
+            classify("something odd"),
+            AppError::InternalAppError(_)
+        ));
+
+        // Errors that already are AppErrors pass through unchanged
+        let error = classify_error(AppError::InvalidArxivId("abc".to_string()).into());
+        assert!(matches!(error, AppError::InvalidArxivId(_)));
+    }
+}
@@ -0,0 +1,136 @@
+use crate::models::paper::Paper;
+use crate::sources::acl::AclAnthologySource;
+use crate::sources::biorxiv::BiorxivSource;
+use crate::sources::doi::DoiSource;
+use crate::sources::openreview::OpenReviewSource;
+use crate::sources::pubmed::PubMedSource;
+use futures::future::BoxFuture;
+use shared::errors::AppResult;
+
+/// A catalog that papers can be fetched from by URL or identifier
+///
+/// arXiv and Semantic Scholar go through the paper client library instead;
+/// these sources return metadata plus a `pdf_url`, and the caller downloads
+/// the PDF when it needs the text.
+pub trait PaperSource: Send + Sync {
+    /// Name used in cache keys and resource URIs (e.g., `doi`)
+    fn name(&self) -> &'static str;
+
+    /// The source's normalized ID if `input` is one of its URLs or identifiers
+    fn parse_id(&self, input: &str) -> Option<String>;
+
+    /// Recover an ID from the cache-key form, where '/' was replaced by '_'
+    fn id_from_key(&self, key_id: &str) -> String {
+        key_id.to_string()
+    }
+
+    /// Fetch a paper's metadata
+    fn fetch<'a>(&'a self, id: &'a str) -> BoxFuture<'a, AppResult<Paper>>;
+}
+
+/// Sources in matching order; DOI comes last so that more specific
+/// sources (e.g., bioRxiv DOIs) win
+static SOURCES: [&dyn PaperSource; 5] = [
+    &OpenReviewSource,
+    &AclAnthologySource,
+    &PubMedSource,
+    &BiorxivSource,
+    &DoiSource,
+];
+
+/// All registered sources
+pub fn paper_sources() -> &'static [&'static dyn PaperSource] {
+    &SOURCES
+}
+
+/// The first source that recognizes `input`, with its ID
+pub fn find_source(input: &str) -> Option<(&'static dyn PaperSource, String)> {
+    let input = input.trim();
+    SOURCES
+        .iter()
+        .find_map(|source| source.parse_id(input).map(|id| (*source, id)))
+}
+
+/// The source registered under `name`
+pub fn source_named(name: &str) -> Option<&'static dyn PaperSource> {
+    SOURCES.iter().copied().find(|source| source.name() == name)
+}
+
+/// Remove HTML/JATS tags (e.g., `<jats:p>`) and collapse whitespace
+///
+/// Block-level tags become word breaks; inline ones such as `<i>` vanish.
+pub(crate) fn strip_markup(text: &str) -> String {
+    const BLOCK_TAGS: [&str; 7] = ["p", "br", "div", "sec", "title", "li", "list-item"];
+
+    let mut out = String::with_capacity(text.len());
+    let mut tag: Option<String> = None;
+    for c in text.chars() {
+        match (&mut tag, c) {
+            (None, '<') => tag = Some(String::new()),
+            (Some(name), '>') => {
+                let name = name.trim_start_matches('/');
+                let name = name.split([' ', '/']).next().unwrap_or_default();
+                let local = name.rsplit(':').next().unwrap_or(name);
+                if BLOCK_TAGS.contains(&local.to_lowercase().as_str()) {
+                    out.push(' ');
+                }
+                tag = None;
+            }
+            (Some(name), c) => name.push(c),
+            (None, c) => out.push(c),
+        }
+    }
+    out.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Restore the '/' after a DOI prefix (e.g., `10.1145_123` to `10.1145/123`)
+pub(crate) fn doi_from_key(key_id: &str) -> String {
+    key_id.replacen('_', "/", 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_source() {
+        let name = |input: &str| find_source(input).map(|(source, id)| (source.name(), id));
+
+        assert_eq!(
+            name("https://openreview.net/forum?id=rJ4km2R5t7"),
+            Some(("openreview", "rJ4km2R5t7".to_string()))
+        );
+        assert_eq!(
+            name("https://aclanthology.org/2020.acl-main.1/"),
+            Some(("acl", "2020.acl-main.1".to_string()))
+        );
+        assert_eq!(
+            name("PMID:31452104"),
+            Some(("pubmed", "31452104".to_string()))
+        );
+        assert_eq!(
+            name("10.1101/2020.03.09.983247"),
+            Some(("biorxiv", "10.1101/2020.03.09.983247".to_string()))
+        );
+        assert_eq!(
+            name("https://doi.org/10.1145/3442188.3445922"),
+            Some(("doi", "10.1145/3442188.3445922".to_string()))
+        );
+        assert_eq!(name("https://example.com/paper.pdf"), None);
+
+        for source in paper_sources() {
+            assert_eq!(source_named(source.name()).unwrap().name(), source.name());
+        }
+        assert!(source_named("arxiv").is_none());
+    }
+
+    #[test]
+    fn test_strip_markup() {
+        assert_eq!(
+            strip_markup(
+                "<jats:p>We study <i>attention</i>\n in depth.</jats:p><jats:p>Next</jats:p>"
+            ),
+            "We study attention in depth. Next"
+        );
+    }
+}
@@ -0,0 +1,516 @@
+use crate::models::paper::Paper;
+use crate::sources::http::{env_parse, send_with_retry};
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use reqwest::{redirect, Url};
+use shared::errors::{AppError, AppResult};
+use std::env;
+use std::net::{IpAddr, SocketAddr};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, LazyLock};
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+
+/// Default upper bound on a PDF's size, downloaded or read (MB)
+const DEFAULT_PDF_MAX_MB: u64 = 100;
+
+/// Default timeout of a whole PDF download
+const DEFAULT_PDF_TIMEOUT_SECS: u64 = 120;
+
+/// Most redirects followed for one download
+const MAX_REDIRECTS: usize = 10;
+
+/// Client for PDF downloads, whose URLs come from callers and paper metadata
+///
+/// Hosts are resolved through [`PublicResolver`] and IP literals are checked
+/// on every redirect, so a download cannot reach the server's own network.
+/// Proxies are not used because they would resolve hosts themselves. If the
+/// client cannot be built, downloads fail rather than fall back to a client
+/// without these checks.
+static PDF_CLIENT: LazyLock<Result<reqwest::Client, String>> = LazyLock::new(|| {
+    let timeout = env_parse("PDF_TIMEOUT_SECS").unwrap_or(DEFAULT_PDF_TIMEOUT_SECS);
+    reqwest::Client::builder()
+        .timeout(Duration::from_secs(timeout))
+        .no_proxy()
+        .dns_resolver(Arc::new(PublicResolver))
+        .redirect(redirect::Policy::custom(|attempt| {
+            if attempt.previous().len() >= MAX_REDIRECTS {
+                return attempt.error("too many redirects");
+            }
+            match check_public_url(attempt.url()) {
+                Ok(()) => attempt.follow(),
+                Err(e) => attempt.error(e.to_string()),
+            }
+        }))
+        .build()
+        .map_err(|e| e.to_string())
+});
+
+/// PDF_MAX_MB in bytes
+fn max_pdf_bytes() -> u64 {
+    env_parse::<u64>("PDF_MAX_MB").unwrap_or(DEFAULT_PDF_MAX_MB) * 1024 * 1024
+}
+
+fn too_large(what: &str, max_bytes: u64) -> AppError {
+    AppError::PdfFetchFailed(format!(
+        "{} is larger than the {} MB limit (PDF_MAX_MB)",
+        what,
+        max_bytes / (1024 * 1024)
+    ))
+}
+
+/// Download a PDF's bytes, giving up as soon as `ct` is cancelled
+pub async fn download_pdf(url: &str, ct: &CancellationToken) -> AppResult<Vec<u8>> {
+    ct.run_until_cancelled(download(url))
+        .await
+        .unwrap_or_else(|| Err(AppError::Cancelled(format!("Download of {} aborted", url))))
+}
+
+async fn download(url: &str) -> AppResult<Vec<u8>> {
+    let parsed =
+        Url::parse(url).map_err(|e| AppError::InvalidRequest(format!("{}: {}", url, e)))?;
+    check_public_url(&parsed)?;
+    let client = PDF_CLIENT
+        .as_ref()
+        .map_err(|e| AppError::PdfFetchFailed(format!("PDF download client unavailable: {}", e)))?;
+
+    let mut response = send_with_retry(client.get(parsed))
+        .await
+        .map_err(|e| AppError::PdfFetchFailed(format!("{}: {:#}", url, e)))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        return Err(AppError::PdfFetchFailed(format!(
+            "{} returned {}",
+            url, status
+        )));
+    }
+
+    // The body is read in chunks so an oversized one is never held whole
+    let max_bytes = max_pdf_bytes();
+    if response.content_length().is_some_and(|len| len > max_bytes) {
+        return Err(too_large(url, max_bytes));
+    }
+    let mut bytes = Vec::new();
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| AppError::PdfFetchFailed(format!("{}: {}", url, e)))?
+    {
+        if (bytes.len() + chunk.len()) as u64 > max_bytes {
+            return Err(too_large(url, max_bytes));
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+
+    Ok(bytes)
+}
+
+/// Refuse URLs that are not http(s) or name an internal IP address
+///
+/// Host names are checked when they are resolved, by [`PublicResolver`].
+fn check_public_url(url: &Url) -> AppResult<()> {
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(AppError::InvalidRequest(format!(
+            "Unsupported PDF URL scheme: {}",
+            url
+        )));
+    }
+    let host = url
+        .host_str()
+        .ok_or_else(|| AppError::InvalidRequest(format!("PDF URL has no host: {}", url)))?;
+    // IPv6 literals keep their brackets in the host string
+    let Ok(ip) = host
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .parse::<IpAddr>()
+    else {
+        return Ok(());
+    };
+    if is_internal(ip) {
+        return Err(AppError::InvalidRequest(format!(
+            "PDF URL points to an internal address: {}",
+            url
+        )));
+    }
+    Ok(())
+}
+
+/// Resolver that drops loopback, private, and link-local addresses, failing
+/// when a host has no others
+struct PublicResolver;
+
+impl Resolve for PublicResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let host = name.as_str().to_string();
+        Box::pin(async move {
+            let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host.as_str(), 0))
+                .await?
+                .filter(|addr| !is_internal(addr.ip()))
+                .collect();
+            if addrs.is_empty() {
+                return Err(format!("{} resolves only to internal addresses", host).into());
+            }
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+/// Addresses a download must not reach: loopback, private, link-local,
+/// carrier-grade NAT, unspecified, broadcast, and multicast
+fn is_internal(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [a, b, ..] = ip.octets();
+            ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+                || ip.is_multicast()
+                || a == 0
+                || (a == 100 && (b & 0xc0) == 64)
+        }
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => is_internal(IpAddr::V4(ip)),
+            None => {
+                let first = ip.segments()[0];
+                ip.is_loopback()
+                    || ip.is_unspecified()
+                    || ip.is_multicast()
+                    || (first & 0xfe00) == 0xfc00
+                    || (first & 0xffc0) == 0xfe80
+            }
+        },
+    }
+}
+
+/// Extract plain text from PDF bytes on a blocking thread
+pub async fn extract_pdf_text(bytes: Vec<u8>) -> AppResult<String> {
+    if !bytes.starts_with(b"%PDF") {
+        return Err(AppError::PdfFetchFailed(
+            "Content is not a PDF document".to_string(),
+        ));
+    }
+
+    tokio::task::spawn_blocking(move || pdf_extract::extract_text_from_mem(&bytes))
+        .await
+        .map_err(|e| AppError::InternalAppError(format!("PDF extraction task failed: {}", e)))?
+        .map_err(|e| AppError::PdfFetchFailed(format!("Text extraction failed: {}", e)))
+}
+
+/// Where a PDF outside the catalogs comes from
+#[derive(Debug, Clone, PartialEq)]
+pub enum PdfSource {
+    Url(String),
+    Path(String),
+}
+
+impl PdfSource {
+    /// The PDF's URL to record on the paper; local files have none
+    pub fn url(&self) -> Option<String> {
+        match self {
+            PdfSource::Url(url) => Some(url.clone()),
+            PdfSource::Path(_) => None,
+        }
+    }
+}
+
+/// Read a PDF file under PDF_ROOT_DIR
+///
+/// Local files are off unless PDF_ROOT_DIR is set. Relative paths are taken
+/// from the root, and paths that resolve outside it are refused.
+pub async fn read_pdf_file(path: &str) -> AppResult<Vec<u8>> {
+    let root = env::var_os("PDF_ROOT_DIR")
+        .filter(|root| !root.is_empty())
+        .ok_or_else(|| {
+            AppError::InvalidRequest(
+                "pdf_path is disabled on this server (PDF_ROOT_DIR is not set)".to_string(),
+            )
+        })?;
+    let path = resolve_under_root(Path::new(&root), path).await?;
+
+    let max_bytes = max_pdf_bytes();
+    let len = tokio::fs::metadata(&path)
+        .await
+        .map_err(|e| AppError::PdfFetchFailed(format!("{}: {}", path.display(), e)))?
+        .len();
+    if len > max_bytes {
+        return Err(too_large(&path.display().to_string(), max_bytes));
+    }
+    tokio::fs::read(&path)
+        .await
+        .map_err(|e| AppError::PdfFetchFailed(format!("{}: {}", path.display(), e)))
+}
+
+/// Canonicalize `path` against `root`, following symlinks, and require the
+/// result to stay under `root`
+///
+/// Missing files and files outside the root get the same error, so callers
+/// cannot probe the rest of the filesystem.
+async fn resolve_under_root(root: &Path, path: &str) -> AppResult<PathBuf> {
+    let root = tokio::fs::canonicalize(root).await.map_err(|e| {
+        AppError::InternalAppError(format!("PDF_ROOT_DIR {}: {}", root.display(), e))
+    })?;
+    match tokio::fs::canonicalize(root.join(path)).await {
+        Ok(resolved) if resolved.starts_with(&root) => Ok(resolved),
+        _ => Err(AppError::InvalidRequest(format!(
+            "{} is not a file under PDF_ROOT_DIR",
+            path
+        ))),
+    }
+}
+
+/// Build a Paper from PDF bytes: extracted text plus any embedded title and authors
+pub async fn paper_from_pdf(bytes: Vec<u8>, pdf_url: Option<String>) -> AppResult<Paper> {
+    let metadata = {
+        let bytes = bytes.clone();
+        tokio::task::spawn_blocking(move || pdf_metadata(&bytes))
+            .await
+            .map_err(|e| AppError::InternalAppError(format!("PDF metadata task failed: {}", e)))?
+    };
+    let text = extract_pdf_text(bytes).await?;
+    Ok(paper_from_pdf_text(text, metadata, pdf_url))
+}
+
+/// Title and authors from a PDF's document information dictionary
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PdfMetadata {
+    pub title: Option<String>,
+    pub authors: Vec<String>,
+}
+
+/// Read the Title and Author entries, ignoring placeholders left by authoring tools
+pub fn pdf_metadata(bytes: &[u8]) -> PdfMetadata {
+    let Ok(doc) = lopdf::Document::load_mem(bytes) else {
+        return PdfMetadata::default();
+    };
+    let info = doc
+        .trailer
+        .get(b"Info")
+        .and_then(|info| doc.dereference(info))
+        .and_then(|(_, info)| info.as_dict());
+    let Ok(info) = info else {
+        return PdfMetadata::default();
+    };
+    let text = |key: &[u8]| {
+        info.get(key)
+            .and_then(|value| doc.dereference(value))
+            .and_then(|(_, value)| lopdf::decode_text_string(value))
+            .ok()
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+    };
+
+    PdfMetadata {
+        title: text(b"Title").filter(|title| !is_placeholder_title(title)),
+        authors: text(b"Author")
+            .map(|authors| split_authors(&authors))
+            .unwrap_or_default(),
+    }
+}
+
+/// Titles such as "Microsoft Word - paper.docx" or "main.pdf" name the file, not the paper
+fn is_placeholder_title(title: &str) -> bool {
+    let lower = title.to_lowercase();
+    lower == "untitled"
+        || lower.starts_with("microsoft word - ")
+        || [".pdf", ".doc", ".docx", ".tex", ".dvi"]
+            .iter()
+            .any(|ext| lower.ends_with(ext))
+}
+
+/// Split an Author entry such as "A. Smith; B. Jones" or "A. Smith, B. Jones and C. Lee"
+fn split_authors(authors: &str) -> Vec<String> {
+    let parts: Vec<&str> = if authors.contains(';') {
+        authors.split(';').collect()
+    } else {
+        authors
+            .split(',')
+            .flat_map(|part| part.split(" and "))
+            .collect()
+    };
+    parts
+        .into_iter()
+        .map(|name| name.trim().trim_start_matches("and ").trim())
+        .filter(|name| !name.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Build a Paper from extracted PDF text when no catalog metadata is available
+///
+/// Embedded metadata wins; otherwise the title is a best guess taken from
+/// the first non-empty line, and the abstract from the text after an
+/// "Abstract" heading.
+pub fn paper_from_pdf_text(text: String, metadata: PdfMetadata, pdf_url: Option<String>) -> Paper {
+    let title = metadata.title.unwrap_or_else(|| {
+        text.lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty())
+            .unwrap_or("Untitled")
+            .to_string()
+    });
+
+    Paper {
+        title,
+        authors: metadata.authors,
+        abstract_text: abstract_from_text(&text).unwrap_or_default(),
+        arxiv_id: None,
+        ss_id: None,
+        categories: vec![],
+        published_date: None,
+        pdf_url,
+        content: Some(text),
+        structure: None,
+    }
+}
+
+/// Longest abstract taken from the body text
+const MAX_ABSTRACT_CHARS: usize = 3000;
+
+/// The paragraph(s) between an "Abstract" heading and the introduction
+fn abstract_from_text(text: &str) -> Option<String> {
+    let mut lines = text.lines().map(str::trim);
+    let first = lines.find_map(|line| {
+        let rest = line
+            .get(..8)
+            .filter(|head| head.eq_ignore_ascii_case("abstract"))
+            .map(|_| &line[8..])?;
+        Some(
+            rest.trim_start_matches(['.', ':', '—', '-', ' '])
+                .to_string(),
+        )
+    })?;
+
+    let mut words: Vec<&str> = first.split_whitespace().collect();
+    for line in lines {
+        let lower = line.to_lowercase();
+        let heading = lower
+            .trim_start_matches(|c: char| c.is_ascii_digit() || c == '.' || c == ' ')
+            .trim_start_matches("i ")
+            .trim_start_matches("i. ");
+        if heading == "introduction" || heading.starts_with("introduction ") {
+            break;
+        }
+        words.extend(line.split_whitespace());
+    }
+
+    let abstract_text: String = words.join(" ").chars().take(MAX_ABSTRACT_CHARS).collect();
+    (!abstract_text.is_empty()).then_some(abstract_text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paper_from_pdf_text() {
+        let text = "\n  Attention Is All You Need \nAshish Vaswani\n\nAbstract\nThe dominant \
+                    sequence transduction models\nare based on RNNs.\n\n1 Introduction\nRNNs...\n";
+        let paper = paper_from_pdf_text(
+            text.to_string(),
+            PdfMetadata::default(),
+            Some("https://example.com/paper.pdf".to_string()),
+        );
+        assert_eq!(paper.title, "Attention Is All You Need");
+        assert_eq!(
+            paper.abstract_text,
+            "The dominant sequence transduction models are based on RNNs."
+        );
+        assert_eq!(
+            paper.pdf_url.as_deref(),
+            Some("https://example.com/paper.pdf")
+        );
+        assert!(paper.content.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_download_refuses_internal_hosts() {
+        for url in [
+            "http://127.0.0.1/paper.pdf",
+            "http://169.254.169.254/latest/meta-data",
+            "http://[::1]:8080/paper.pdf",
+            "http://[::ffff:10.0.0.1]/paper.pdf",
+            "file:///etc/passwd",
+        ] {
+            let result = download_pdf(url, &CancellationToken::new()).await;
+            assert!(
+                matches!(result, Err(AppError::InvalidRequest(_))),
+                "{} was not refused",
+                url
+            );
+        }
+        assert!(PublicResolver
+            .resolve("localhost".parse().unwrap())
+            .await
+            .is_err());
+
+        assert!(!is_internal("93.184.216.34".parse().unwrap()));
+        assert!(!is_internal("2606:2800:220:1::".parse().unwrap()));
+        assert!(is_internal("100.64.0.1".parse().unwrap()));
+        assert!(is_internal("fd00::1".parse().unwrap()));
+    }
+
+    #[tokio::test]
+    async fn test_resolve_under_root() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("papers");
+        std::fs::create_dir(&root).unwrap();
+        std::fs::write(root.join("paper.pdf"), b"%PDF").unwrap();
+        std::fs::write(dir.path().join("secret.pdf"), b"%PDF").unwrap();
+
+        let resolved = resolve_under_root(&root, "paper.pdf").await.unwrap();
+        assert!(resolved.ends_with("papers/paper.pdf"));
+        let absolute = root.join("paper.pdf");
+        assert!(resolve_under_root(&root, absolute.to_str().unwrap())
+            .await
+            .is_ok());
+
+        for path in ["../secret.pdf", "missing.pdf", "/etc/passwd"] {
+            assert!(matches!(
+                resolve_under_root(&root, path).await,
+                Err(AppError::InvalidRequest(_))
+            ));
+        }
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(dir.path().join("secret.pdf"), root.join("link.pdf"))
+                .unwrap();
+            assert!(resolve_under_root(&root, "link.pdf").await.is_err());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_extract_pdf_text_rejects_non_pdf() {
+        let result = extract_pdf_text(b"<html></html>".to_vec()).await;
+        assert!(matches!(result, Err(AppError::PdfFetchFailed(_))));
+    }
+
+    #[test]
+    fn test_pdf_metadata() {
+        use lopdf::{dictionary, Document, Object};
+
+        let mut doc = Document::with_version("1.5");
+        let info = doc.add_object(dictionary! {
+            "Title" => Object::string_literal("Attention Is All You Need"),
+            "Author" => Object::string_literal("Ashish Vaswani, Noam Shazeer and Niki Parmar"),
+        });
+        doc.trailer.set("Info", info);
+        let mut bytes = Vec::new();
+        doc.save_to(&mut bytes).unwrap();
+
+        let metadata = pdf_metadata(&bytes);
+        assert_eq!(metadata.title.as_deref(), Some("Attention Is All You Need"));
+        assert_eq!(
+            metadata.authors,
+            vec!["Ashish Vaswani", "Noam Shazeer", "Niki Parmar"]
+        );
+
+        assert_eq!(pdf_metadata(b"not a pdf"), PdfMetadata::default());
+        assert!(is_placeholder_title("Microsoft Word - camera_ready.docx"));
+        assert_eq!(
+            split_authors("Smith, J.; Jones, K."),
+            vec!["Smith, J.", "Jones, K."]
+        );
+    }
+}
@@ -0,0 +1,221 @@
+use crate::models::paper::Paper;
+use crate::sources::http::{send_checked, web_url};
+use crate::sources::paper_source::{strip_markup, PaperSource};
+use futures::future::BoxFuture;
+use serde::Deserialize;
+use shared::errors::{AppError, AppResult};
+
+/// Europe PMC REST search endpoint, which mirrors PubMed and PMC
+pub const EUROPE_PMC_API_URL: &str = "https://www.ebi.ac.uk/europepmc/webservices/rest/search";
+
+/// Papers identified by PubMed ID (digits) or PMC ID (`PMC` + digits)
+pub struct PubMedSource;
+
+impl PubMedSource {
+    async fn fetch_paper(&self, id: &str) -> AppResult<Paper> {
+        let query = if id.starts_with("PMC") {
+            format!("PMCID:{}", id)
+        } else {
+            format!("EXT_ID:{} AND SRC:MED", id)
+        };
+        let request = reqwest::Client::new().get(EUROPE_PMC_API_URL).query(&[
+            ("query", query.as_str()),
+            ("resultType", "core"),
+            ("format", "json"),
+        ]);
+        let response: SearchResponse = send_checked(request, "Europe PMC")
+            .await?
+            .json()
+            .await
+            .map_err(|e| AppError::NetworkError(format!("Invalid Europe PMC response: {}", e)))?;
+
+        response
+            .result_list
+            .result
+            .into_iter()
+            .next()
+            .map(Paper::from)
+            .ok_or_else(|| AppError::PaperNotFound(format!("PubMed ID {}", id)))
+    }
+}
+
+impl PaperSource for PubMedSource {
+    fn name(&self) -> &'static str {
+        "pubmed"
+    }
+
+    fn parse_id(&self, input: &str) -> Option<String> {
+        parse_pubmed_id(input)
+    }
+
+    fn fetch<'a>(&'a self, id: &'a str) -> BoxFuture<'a, AppResult<Paper>> {
+        Box::pin(self.fetch_paper(id))
+    }
+}
+
+/// PubMed or PMC ID from a `PMID:`/`PMC` identifier or a PubMed, PMC, or Europe PMC URL
+pub fn parse_pubmed_id(input: &str) -> Option<String> {
+    let input = input.trim();
+    let candidate = match web_url(input) {
+        Some(url) => {
+            let host = url.host_str()?;
+            let segments: Vec<&str> = url.path_segments()?.filter(|s| !s.is_empty()).collect();
+            match (host, segments.as_slice()) {
+                ("pubmed.ncbi.nlm.nih.gov", [id, ..]) => id.to_string(),
+                ("www.ncbi.nlm.nih.gov", ["pubmed", id, ..])
+                | ("www.ncbi.nlm.nih.gov", ["pmc", "articles", id, ..])
+                | ("pmc.ncbi.nlm.nih.gov", ["articles", id, ..])
+                | ("europepmc.org", ["article", "MED" | "PMC", id, ..])
+                | ("europepmc.org", ["abstract", "MED", id, ..]) => id.to_string(),
+                _ => return None,
+            }
+        }
+        None => input.to_string(),
+    };
+
+    let upper = candidate.to_uppercase();
+    let id = upper
+        .strip_prefix("PMID:")
+        .map(str::trim)
+        .unwrap_or(upper.as_str());
+    let is_digits = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_digit());
+
+    if let Some(pmc) = id.strip_prefix("PMC").filter(|n| is_digits(n)) {
+        return Some(format!("PMC{}", pmc));
+    }
+    // Bare numbers are too ambiguous; only accept them from a URL or with PMID:
+    let explicit = upper.starts_with("PMID:") || candidate != input;
+    (explicit && is_digits(id)).then(|| id.to_string())
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SearchResponse {
+    result_list: ResultList,
+}
+
+#[derive(Debug, Deserialize)]
+struct ResultList {
+    #[serde(default)]
+    result: Vec<EuropePmcArticle>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EuropePmcArticle {
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    author_list: Option<AuthorList>,
+    #[serde(default)]
+    abstract_text: Option<String>,
+    #[serde(default)]
+    first_publication_date: Option<String>,
+    #[serde(default)]
+    pmcid: Option<String>,
+    #[serde(default)]
+    is_open_access: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AuthorList {
+    #[serde(default)]
+    author: Vec<EuropePmcAuthor>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EuropePmcAuthor {
+    #[serde(default)]
+    full_name: Option<String>,
+    #[serde(default)]
+    collective_name: Option<String>,
+}
+
+impl From<EuropePmcArticle> for Paper {
+    fn from(article: EuropePmcArticle) -> Self {
+        // Europe PMC renders PDFs only for open-access PMC articles
+        let pdf_url = article
+            .pmcid
+            .filter(|_| article.is_open_access.as_deref() == Some("Y"))
+            .map(|pmcid| format!("https://europepmc.org/articles/{}?pdf=render", pmcid));
+
+        Paper {
+            title: strip_markup(&article.title)
+                .trim_end_matches('.')
+                .to_string(),
+            authors: article
+                .author_list
+                .map(|list| {
+                    list.author
+                        .into_iter()
+                        .filter_map(|a| a.full_name.or(a.collective_name))
+                        .collect()
+                })
+                .unwrap_or_default(),
+            abstract_text: article
+                .abstract_text
+                .map(|a| strip_markup(&a))
+                .unwrap_or_default(),
+            arxiv_id: None,
+            ss_id: None,
+            categories: vec![],
+            published_date: article.first_publication_date,
+            pdf_url,
+            content: None,
+            structure: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pubmed_id() {
+        assert_eq!(
+            parse_pubmed_id("PMID: 31452104"),
+            Some("31452104".to_string())
+        );
+        assert_eq!(
+            parse_pubmed_id("pmc6716516"),
+            Some("PMC6716516".to_string())
+        );
+        assert_eq!(
+            parse_pubmed_id("https://pubmed.ncbi.nlm.nih.gov/31452104/"),
+            Some("31452104".to_string())
+        );
+        assert_eq!(
+            parse_pubmed_id("https://www.ncbi.nlm.nih.gov/pmc/articles/PMC6716516/"),
+            Some("PMC6716516".to_string())
+        );
+        assert_eq!(parse_pubmed_id("31452104"), None);
+        assert_eq!(parse_pubmed_id("https://example.com/31452104"), None);
+    }
+
+    #[test]
+    fn test_europe_pmc_article() {
+        let json = r#"{"resultList": {"result": [{
+            "title": "Highly accurate protein structure prediction with AlphaFold.",
+            "authorList": {"author": [{"fullName": "Jumper J"}, {"collectiveName": "DeepMind"}]},
+            "abstractText": "Proteins are <i>essential</i> to life.",
+            "firstPublicationDate": "2021-07-15",
+            "pmcid": "PMC8371605",
+            "isOpenAccess": "Y"
+        }]}}"#;
+        let response: SearchResponse = serde_json::from_str(json).unwrap();
+        let paper = Paper::from(response.result_list.result.into_iter().next().unwrap());
+
+        assert_eq!(
+            paper.title,
+            "Highly accurate protein structure prediction with AlphaFold"
+        );
+        assert_eq!(paper.authors, vec!["Jumper J", "DeepMind"]);
+        assert_eq!(paper.abstract_text, "Proteins are essential to life.");
+        assert_eq!(
+            paper.pdf_url.as_deref(),
+            Some("https://europepmc.org/articles/PMC8371605?pdf=render")
+        );
+    }
+}
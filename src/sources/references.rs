@@ -0,0 +1,143 @@
+use crate::models::paper::{PaperSummary, Reference};
+use crate::sources::resolve::title_similarity;
+use crate::sources::semantic_scholar::{CitationDirection, MAX_NEIGHBORS};
+use crate::sources::SemanticScholarClient;
+use shared::errors::AppResult;
+
+/// Minimum title similarity for a parsed entry to match one of Semantic
+/// Scholar's references; parsed titles often keep stray punctuation or venue text
+const REFERENCE_MATCH_THRESHOLD: f64 = 0.85;
+
+/// Match parsed reference entries to Semantic Scholar papers
+///
+/// Entries with an arXiv ID or DOI are looked up in one batch. The rest are
+/// matched by title against Semantic Scholar's reference list for the citing
+/// paper, `citing_id`, when it is in the catalog. Entries that cannot be
+/// matched are left as parsed.
+pub async fn resolve_references(
+    references: &mut [Reference],
+    citing_id: Option<&str>,
+) -> AppResult<()> {
+    let client = SemanticScholarClient::new();
+
+    let lookups: Vec<(usize, String)> = references
+        .iter()
+        .enumerate()
+        .filter_map(|(i, r)| {
+            let id = match (&r.arxiv_id, &r.doi) {
+                (Some(arxiv_id), _) => format!("arXiv:{}", arxiv_id),
+                (None, Some(doi)) => format!("DOI:{}", doi),
+                (None, None) => return None,
+            };
+            Some((i, id))
+        })
+        .collect();
+    if !lookups.is_empty() {
+        let ids: Vec<String> = lookups.iter().map(|(_, id)| id.clone()).collect();
+        let papers = client.fetch_batch(&ids).await?;
+        for ((i, _), paper) in lookups.into_iter().zip(papers) {
+            if let Some(paper) = paper {
+                apply_match(&mut references[i], paper);
+            }
+        }
+    }
+
+    let unresolved = references
+        .iter()
+        .any(|r| r.ss_id.is_none() && r.title.is_some());
+    if let Some(citing_id) = citing_id
+        && unresolved
+    {
+        let cited: Vec<PaperSummary> = client
+            .neighbors(citing_id, CitationDirection::References, 0, MAX_NEIGHBORS)
+            .await?
+            .neighbors
+            .into_iter()
+            .map(|n| n.paper)
+            .collect();
+        match_by_title(references, &cited);
+    }
+    Ok(())
+}
+
+/// Give each unmatched entry the best catalog paper whose title is close enough
+fn match_by_title(references: &mut [Reference], cited: &[PaperSummary]) {
+    for reference in references.iter_mut().filter(|r| r.ss_id.is_none()) {
+        let Some(title) = reference.title.as_deref() else {
+            continue;
+        };
+        let best = cited
+            .iter()
+            .map(|paper| (title_similarity(title, &paper.title), paper))
+            .filter(|(score, _)| *score >= REFERENCE_MATCH_THRESHOLD)
+            .max_by(|a, b| a.0.total_cmp(&b.0));
+        if let Some((_, paper)) = best {
+            apply_match(reference, paper.clone());
+        }
+    }
+}
+
+/// Take the catalog's ID, title, and counts, keeping parsed fields it lacks
+fn apply_match(reference: &mut Reference, paper: PaperSummary) {
+    reference.ss_id = paper.ss_id;
+    reference.citation_count = paper.citation_count;
+    if !paper.title.is_empty() {
+        reference.title = Some(paper.title);
+    }
+    if reference.arxiv_id.is_none() {
+        reference.arxiv_id = paper.arxiv_id;
+    }
+    if reference.year.is_none() {
+        reference.year = paper
+            .published_date
+            .as_deref()
+            .and_then(|d| d.get(..4))
+            .and_then(|y| y.parse().ok());
+    }
+    if reference.authors.is_empty() {
+        reference.authors = paper.authors;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_match_by_title() {
+        let mut references = vec![
+            Reference {
+                raw: "...".to_string(),
+                title: Some("Attention is all you need.".to_string()),
+                ..Default::default()
+            },
+            Reference {
+                raw: "...".to_string(),
+                title: Some("A paper Semantic Scholar never matched".to_string()),
+                ..Default::default()
+            },
+        ];
+        let cited = vec![PaperSummary {
+            title: "Attention Is All You Need".to_string(),
+            authors: vec!["Ashish Vaswani".to_string()],
+            abstract_text: String::new(),
+            arxiv_id: Some("1706.03762".to_string()),
+            ss_id: Some("204e3073870fae3d05bcbc2f6a8e263d9b72e776".to_string()),
+            categories: vec![],
+            published_date: Some("2017-06-12".to_string()),
+            citation_count: Some(100000),
+            pdf_url: None,
+        }];
+
+        match_by_title(&mut references, &cited);
+
+        assert_eq!(
+            references[0].title.as_deref(),
+            Some("Attention Is All You Need")
+        );
+        assert_eq!(references[0].arxiv_id.as_deref(), Some("1706.03762"));
+        assert_eq!(references[0].year, Some(2017));
+        assert!(references[0].ss_id.is_some());
+        assert!(references[1].ss_id.is_none());
+    }
+}
@@ -0,0 +1,193 @@
+use crate::models::paper::PaperSummary;
+use crate::sources::{ArxivClient, ArxivQuery, SemanticScholarClient, SemanticScholarQuery};
+use serde::Serialize;
+use shared::errors::AppResult;
+
+/// Minimum similarity for a search result to count as the requested title
+pub const TITLE_MATCH_THRESHOLD: f64 = 0.9;
+
+/// Runner-up matches within this margin of the best make the title ambiguous
+const AMBIGUITY_MARGIN: f64 = 0.05;
+
+/// Minimum similarity for a result to be reported as a candidate
+const CANDIDATE_THRESHOLD: f64 = 0.5;
+
+/// Maximum number of candidates reported back
+const MAX_CANDIDATES: usize = 5;
+
+/// Search results requested per backend when resolving a title
+const SEARCH_LIMIT: u32 = 10;
+
+/// A search result scored against the requested title
+#[derive(Debug, Clone, Serialize)]
+pub struct TitleCandidate {
+    #[serde(flatten)]
+    pub paper: PaperSummary,
+    pub score: f64,
+}
+
+/// Outcome of resolving a paper by title
+#[derive(Debug, Clone)]
+pub enum TitleMatch {
+    /// A single result matched above the threshold
+    Found(TitleCandidate),
+    /// Several results matched about equally well
+    Ambiguous(Vec<TitleCandidate>),
+    /// Nothing matched; the closest results, if any
+    NotFound(Vec<TitleCandidate>),
+}
+
+/// Resolve a title to a catalog entry, trying arXiv first and then Semantic Scholar
+pub async fn resolve_title(title: &str) -> AppResult<TitleMatch> {
+    let keywords = normalize_title(title);
+
+    let arxiv_results = ArxivClient::new()
+        .search(&ArxivQuery {
+            keywords: keywords.clone(),
+            max_results: SEARCH_LIMIT,
+            ..Default::default()
+        })
+        .await;
+    let arxiv_match = match arxiv_results {
+        Ok(results) => pick_best_match(title, results),
+        Err(e) => {
+            tracing::warn!("arXiv title search failed: {}", e);
+            TitleMatch::NotFound(vec![])
+        }
+    };
+    if !matches!(arxiv_match, TitleMatch::NotFound(_)) {
+        return Ok(arxiv_match);
+    }
+
+    let s2_results = SemanticScholarClient::new()
+        .search(&SemanticScholarQuery {
+            keywords,
+            limit: SEARCH_LIMIT,
+            ..Default::default()
+        })
+        .await?;
+
+    Ok(match pick_best_match(title, s2_results) {
+        TitleMatch::NotFound(mut candidates) => {
+            // Report the closest results from both backends
+            if let TitleMatch::NotFound(arxiv_candidates) = arxiv_match {
+                candidates.extend(arxiv_candidates);
+            }
+            candidates.sort_by(|a, b| b.score.total_cmp(&a.score));
+            candidates.truncate(MAX_CANDIDATES);
+            TitleMatch::NotFound(candidates)
+        }
+        other => other,
+    })
+}
+
+/// Score search results against a title and pick the match, if unambiguous
+pub fn pick_best_match(title: &str, results: Vec<PaperSummary>) -> TitleMatch {
+    let mut candidates: Vec<TitleCandidate> = results
+        .into_iter()
+        .map(|paper| TitleCandidate {
+            score: title_similarity(title, &paper.title),
+            paper,
+        })
+        .filter(|c| c.score >= CANDIDATE_THRESHOLD)
+        .collect();
+    candidates.sort_by(|a, b| b.score.total_cmp(&a.score));
+    candidates.truncate(MAX_CANDIDATES);
+
+    let Some(best) = candidates.first() else {
+        return TitleMatch::NotFound(candidates);
+    };
+    if best.score < TITLE_MATCH_THRESHOLD {
+        return TitleMatch::NotFound(candidates);
+    }
+
+    let contenders = candidates
+        .iter()
+        .filter(|c| c.score >= TITLE_MATCH_THRESHOLD && best.score - c.score <= AMBIGUITY_MARGIN)
+        .count();
+    if contenders > 1 {
+        candidates.truncate(contenders);
+        return TitleMatch::Ambiguous(candidates);
+    }
+
+    TitleMatch::Found(candidates.swap_remove(0))
+}
+
+/// Similarity of two titles in [0, 1], ignoring case, punctuation, and spacing
+pub fn title_similarity(a: &str, b: &str) -> f64 {
+    strsim::normalized_levenshtein(&normalize_title(a), &normalize_title(b))
+}
+
+/// Lowercase a title and reduce it to alphanumeric words separated by single spaces
+fn normalize_title(title: &str) -> String {
+    title
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary(title: &str, arxiv_id: &str) -> PaperSummary {
+        PaperSummary {
+            title: title.to_string(),
+            authors: vec![],
+            abstract_text: String::new(),
+            arxiv_id: Some(arxiv_id.to_string()),
+            ss_id: None,
+            categories: vec![],
+            published_date: None,
+            citation_count: None,
+            pdf_url: None,
+        }
+    }
+
+    #[test]
+    fn test_title_similarity() {
+        assert_eq!(
+            title_similarity("Attention Is All You Need", "attention is all you need."),
+            1.0
+        );
+        assert!(
+            title_similarity(
+                "BERT: Pre-training of Deep Bidirectional Transformers",
+                "BERT Pre-training of Deep Bidirectional Transformer"
+            ) > TITLE_MATCH_THRESHOLD
+        );
+        assert!(title_similarity("Attention Is All You Need", "Deep Residual Learning") < 0.5);
+    }
+
+    #[test]
+    fn test_pick_best_match() {
+        let results = vec![
+            summary("Attention Is Not All You Need", "2103.03404"),
+            summary("Attention Is All You Need", "1706.03762"),
+        ];
+        match pick_best_match("attention is all you need", results) {
+            TitleMatch::Found(c) => assert_eq!(c.paper.arxiv_id.as_deref(), Some("1706.03762")),
+            other => panic!("expected a match, got {:?}", other),
+        }
+
+        let results = vec![
+            summary("Neural Machine Translation", "1"),
+            summary("Neural Machine Translation", "2"),
+        ];
+        assert!(matches!(
+            pick_best_match("Neural Machine Translation", results),
+            TitleMatch::Ambiguous(ref c) if c.len() == 2
+        ));
+
+        let results = vec![summary(
+            "Deep Residual Learning for Image Recognition",
+            "1512.03385",
+        )];
+        assert!(matches!(
+            pick_best_match("Residual Learning", results),
+            TitleMatch::NotFound(_)
+        ));
+    }
+}
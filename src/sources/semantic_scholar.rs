@@ -0,0 +1,443 @@
+use crate::models::paper::PaperSummary;
+use crate::sources::http::{retry_after_secs, send_with_retry};
+use chrono::NaiveDate;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use shared::errors::{AppError, AppResult};
+use std::env;
+
+/// Semantic Scholar Graph API endpoint
+pub const SEMANTIC_SCHOLAR_API_URL: &str = "https://api.semanticscholar.org/graph/v1";
+
+/// Fields requested for every paper
+const PAPER_FIELDS: &str =
+    "paperId,title,abstract,authors,externalIds,publicationDate,citationCount,openAccessPdf";
+
+/// Most IDs the batch endpoint accepts per request
+const MAX_BATCH_IDS: usize = 500;
+
+/// Most papers the citations and references endpoints return per request
+pub const MAX_NEIGHBORS: u32 = 1000;
+
+/// Most papers the recommendations endpoint returns
+pub const MAX_RECOMMENDATIONS: u32 = 500;
+
+/// Fields requested for citing and cited papers; abstracts are left out to
+/// keep pages of up to a thousand papers small
+const NEIGHBOR_FIELDS: &str =
+    "paperId,title,authors,externalIds,publicationDate,citationCount,isInfluential";
+
+/// Semantic Scholar Recommendations API endpoint
+pub const SEMANTIC_SCHOLAR_RECOMMENDATIONS_URL: &str =
+    "https://api.semanticscholar.org/recommendations/v1";
+
+/// Which side of the citation graph to walk
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CitationDirection {
+    /// Papers that cite the paper
+    Citations,
+    /// Papers the paper cites
+    References,
+}
+
+impl CitationDirection {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CitationDirection::Citations => "citations",
+            CitationDirection::References => "references",
+        }
+    }
+}
+
+/// A paper next to another in the citation graph
+#[derive(Debug, Clone)]
+pub struct Neighbor {
+    pub paper: PaperSummary,
+    /// Whether Semantic Scholar classifies the citation as influential
+    pub is_influential: bool,
+}
+
+/// A page of neighbors, with the offset of the next page if there is one
+#[derive(Debug, Clone)]
+pub struct NeighborPage {
+    pub neighbors: Vec<Neighbor>,
+    pub next: Option<u32>,
+}
+
+/// Candidate pool for recommendations
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RecommendationPool {
+    /// Recently published papers
+    #[default]
+    Recent,
+    /// All computer science papers
+    AllCs,
+}
+
+impl RecommendationPool {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RecommendationPool::Recent => "recent",
+            RecommendationPool::AllCs => "all-cs",
+        }
+    }
+}
+
+/// Search query for the Semantic Scholar paper search API
+#[derive(Debug, Clone, Default)]
+pub struct SemanticScholarQuery {
+    pub keywords: String,
+    pub fields_of_study: Option<String>,
+    pub published_after: Option<NaiveDate>,
+    pub published_before: Option<NaiveDate>,
+    pub limit: u32,
+}
+
+impl SemanticScholarQuery {
+    /// Build the `publicationDateOrYear` range parameter, if any bound is set
+    pub fn date_range(&self) -> Option<String> {
+        if self.published_after.is_none() && self.published_before.is_none() {
+            return None;
+        }
+        let fmt = |d: Option<NaiveDate>| d.map(|d| d.format("%Y-%m-%d").to_string());
+        Some(format!(
+            "{}:{}",
+            fmt(self.published_after).unwrap_or_default(),
+            fmt(self.published_before).unwrap_or_default()
+        ))
+    }
+}
+
+/// Map an arXiv category (e.g., cs.CL) to the closest Semantic Scholar field of study
+pub fn field_of_study_for_category(category: &str) -> Option<&'static str> {
+    let archive = category.split('.').next().unwrap_or(category);
+    match archive {
+        "cs" => Some("Computer Science"),
+        "math" | "stat" => Some("Mathematics"),
+        "physics" | "astro-ph" | "cond-mat" | "gr-qc" | "hep-ex" | "hep-lat" | "hep-ph"
+        | "hep-th" | "math-ph" | "nlin" | "nucl-ex" | "nucl-th" | "quant-ph" => Some("Physics"),
+        "q-bio" => Some("Biology"),
+        "q-fin" | "econ" => Some("Economics"),
+        "eess" => Some("Engineering"),
+        _ => None,
+    }
+}
+
+/// Client for the Semantic Scholar Graph API
+pub struct SemanticScholarClient {
+    http: reqwest::Client,
+    base_url: String,
+    recommendations_url: String,
+    api_key: Option<String>,
+}
+
+impl SemanticScholarClient {
+    pub fn new() -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            base_url: SEMANTIC_SCHOLAR_API_URL.to_string(),
+            recommendations_url: SEMANTIC_SCHOLAR_RECOMMENDATIONS_URL.to_string(),
+            api_key: env::var("SEMANTIC_SCHOLAR_API_KEY").ok(),
+        }
+    }
+
+    /// Search papers by keywords, ranked by relevance
+    pub async fn search(&self, query: &SemanticScholarQuery) -> AppResult<Vec<PaperSummary>> {
+        let mut params = vec![
+            ("query", query.keywords.clone()),
+            ("fields", PAPER_FIELDS.to_string()),
+            ("limit", query.limit.min(100).to_string()),
+        ];
+        if let Some(ref fields_of_study) = query.fields_of_study {
+            params.push(("fieldsOfStudy", fields_of_study.clone()));
+        }
+        if let Some(range) = query.date_range() {
+            params.push(("publicationDateOrYear", range));
+        }
+
+        let result: SearchResponse = self.get("/paper/search", &params).await?;
+
+        Ok(result.data.into_iter().map(PaperSummary::from).collect())
+    }
+
+    /// Fetch metadata for a single paper (no PDF download)
+    ///
+    /// Accepts a Semantic Scholar paper ID, a bare CorpusID number, or any
+    /// prefixed ID the API understands (e.g., `arXiv:1706.03762`, `DOI:...`).
+    pub async fn fetch_paper(&self, id: &str) -> AppResult<PaperSummary> {
+        let id = if id.chars().all(|c| c.is_ascii_digit()) {
+            format!("CorpusId:{}", id)
+        } else {
+            id.to_string()
+        };
+
+        let paper: S2Paper = self
+            .get(
+                &format!("/paper/{}", id),
+                &[("fields", PAPER_FIELDS.to_string())],
+            )
+            .await?;
+
+        Ok(paper.into())
+    }
+
+    /// Look up several papers in one request, by any ID fetch_paper accepts
+    ///
+    /// Results are in the order of `ids`, with None for IDs Semantic Scholar
+    /// does not know.
+    pub async fn fetch_batch(&self, ids: &[String]) -> AppResult<Vec<Option<PaperSummary>>> {
+        let mut papers = Vec::with_capacity(ids.len());
+        for chunk in ids.chunks(MAX_BATCH_IDS) {
+            let request = self
+                .http
+                .post(format!("{}/paper/batch", self.base_url))
+                .query(&[("fields", PAPER_FIELDS)])
+                .json(&serde_json::json!({ "ids": chunk }));
+            let results: Vec<Option<S2Paper>> = self.send(request, "/paper/batch").await?;
+            papers.extend(results.into_iter().map(|p| p.map(PaperSummary::from)));
+        }
+        Ok(papers)
+    }
+
+    /// One page of the papers citing, or cited by, a paper
+    ///
+    /// Reference entries Semantic Scholar could not match to a paper are left out.
+    pub async fn neighbors(
+        &self,
+        id: &str,
+        direction: CitationDirection,
+        offset: u32,
+        limit: u32,
+    ) -> AppResult<NeighborPage> {
+        let result: NeighborsResponse = self
+            .get(
+                &format!("/paper/{}/{}", id, direction.as_str()),
+                &[
+                    ("fields", NEIGHBOR_FIELDS.to_string()),
+                    ("offset", offset.to_string()),
+                    ("limit", limit.min(MAX_NEIGHBORS).to_string()),
+                ],
+            )
+            .await?;
+
+        let neighbors = result
+            .data
+            .into_iter()
+            .filter_map(|edge| {
+                let paper = match direction {
+                    CitationDirection::Citations => edge.citing_paper,
+                    CitationDirection::References => edge.cited_paper,
+                };
+                let paper = serde_json::from_value::<S2Paper>(paper).ok()?;
+                Some(Neighbor {
+                    paper: paper.into(),
+                    is_influential: edge.is_influential.unwrap_or(false),
+                })
+            })
+            .collect();
+        Ok(NeighborPage {
+            neighbors,
+            next: result.next,
+        })
+    }
+
+    /// Papers Semantic Scholar recommends as similar to a paper, best first
+    pub async fn recommendations(
+        &self,
+        id: &str,
+        pool: RecommendationPool,
+        limit: u32,
+    ) -> AppResult<Vec<PaperSummary>> {
+        let path = format!("/papers/forpaper/{}", id);
+        let request = self
+            .http
+            .get(format!("{}{}", self.recommendations_url, path))
+            .query(&[
+                ("fields", PAPER_FIELDS.to_string()),
+                ("from", pool.as_str().to_string()),
+                ("limit", limit.min(MAX_RECOMMENDATIONS).to_string()),
+            ]);
+        let result: RecommendationsResponse = self.send(request, &path).await?;
+
+        Ok(result
+            .recommended_papers
+            .into_iter()
+            .map(PaperSummary::from)
+            .collect())
+    }
+
+    async fn get<T: serde::de::DeserializeOwned>(
+        &self,
+        path: &str,
+        params: &[(&str, String)],
+    ) -> AppResult<T> {
+        let request = self
+            .http
+            .get(format!("{}{}", self.base_url, path))
+            .query(params);
+        self.send(request, path).await
+    }
+
+    async fn send<T: serde::de::DeserializeOwned>(
+        &self,
+        mut request: reqwest::RequestBuilder,
+        path: &str,
+    ) -> AppResult<T> {
+        if let Some(ref key) = self.api_key {
+            request = request.header("x-api-key", key);
+        }
+
+        let response = send_with_retry(request).await.map_err(|e| {
+            AppError::NetworkError(format!("Semantic Scholar request failed: {}", e))
+        })?;
+
+        let status = response.status();
+        if status.as_u16() == 404 {
+            return Err(AppError::PaperNotFound(format!(
+                "Semantic Scholar has no paper at {}",
+                path
+            )));
+        }
+        if status.as_u16() == 429 {
+            return Err(AppError::RateLimitExceeded {
+                message: "Semantic Scholar returned 429 Too Many Requests".to_string(),
+                retry_after_secs: retry_after_secs(response.headers()),
+            });
+        }
+        if !status.is_success() {
+            return Err(AppError::NetworkError(format!(
+                "Semantic Scholar returned {}",
+                status
+            )));
+        }
+
+        response.json().await.map_err(|e| {
+            AppError::NetworkError(format!("Invalid Semantic Scholar response: {}", e))
+        })
+    }
+}
+
+impl Default for SemanticScholarClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchResponse {
+    #[serde(default)]
+    data: Vec<S2Paper>,
+}
+
+#[derive(Debug, Deserialize)]
+struct NeighborsResponse {
+    #[serde(default)]
+    next: Option<u32>,
+    #[serde(default)]
+    data: Vec<S2Edge>,
+}
+
+/// A citation; the paper on the far side has a null paperId when unmatched
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct S2Edge {
+    #[serde(default)]
+    citing_paper: serde_json::Value,
+    #[serde(default)]
+    cited_paper: serde_json::Value,
+    #[serde(default)]
+    is_influential: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RecommendationsResponse {
+    #[serde(default)]
+    recommended_papers: Vec<S2Paper>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct S2Paper {
+    paper_id: String,
+    #[serde(default)]
+    title: String,
+    #[serde(default, rename = "abstract")]
+    abstract_text: Option<String>,
+    #[serde(default)]
+    authors: Vec<S2Author>,
+    #[serde(default)]
+    external_ids: Option<S2ExternalIds>,
+    #[serde(default)]
+    publication_date: Option<String>,
+    #[serde(default)]
+    citation_count: Option<u32>,
+    #[serde(default)]
+    open_access_pdf: Option<S2OpenAccessPdf>,
+}
+
+#[derive(Debug, Deserialize)]
+struct S2Author {
+    #[serde(default)]
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct S2ExternalIds {
+    #[serde(rename = "ArXiv", default)]
+    arxiv: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct S2OpenAccessPdf {
+    #[serde(default)]
+    url: Option<String>,
+}
+
+impl From<S2Paper> for PaperSummary {
+    fn from(p: S2Paper) -> Self {
+        PaperSummary {
+            title: p.title,
+            authors: p.authors.into_iter().map(|a| a.name).collect(),
+            abstract_text: p.abstract_text.unwrap_or_default(),
+            arxiv_id: p.external_ids.and_then(|ids| ids.arxiv),
+            ss_id: Some(p.paper_id),
+            categories: vec![],
+            published_date: p.publication_date,
+            citation_count: p.citation_count,
+            pdf_url: p.open_access_pdf.and_then(|pdf| pdf.url),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_date_range() {
+        let mut query = SemanticScholarQuery::default();
+        assert_eq!(query.date_range(), None);
+
+        query.published_after = NaiveDate::from_ymd_opt(2020, 1, 1);
+        assert_eq!(query.date_range(), Some("2020-01-01:".to_string()));
+
+        query.published_before = NaiveDate::from_ymd_opt(2021, 6, 30);
+        assert_eq!(
+            query.date_range(),
+            Some("2020-01-01:2021-06-30".to_string())
+        );
+    }
+
+    #[test]
+    fn test_field_of_study_for_category() {
+        assert_eq!(
+            field_of_study_for_category("cs.CL"),
+            Some("Computer Science")
+        );
+        assert_eq!(field_of_study_for_category("stat.ML"), Some("Mathematics"));
+        assert_eq!(field_of_study_for_category("hep-th"), Some("Physics"));
+        assert_eq!(field_of_study_for_category("unknown.XX"), None);
+    }
+}